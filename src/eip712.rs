@@ -0,0 +1,81 @@
+use alloc::vec::Vec;
+use alloy_primitives::{address, Address, FixedBytes, U256};
+use alloy_sol_types::SolValue;
+use stylus_sdk::{crypto::keccak, prelude::*, stylus_core::calls::context::Call};
+
+// ecrecover 预编译合约地址
+const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+
+// secp256k1 曲线阶的一半，s 大于该值的签名视为可延展签名
+const SECP256K1N_HALF: U256 = U256::from_limbs([
+    0xdfe92f46681b20a0,
+    0x5d576e7357a4501d,
+    0xffffffffffffffff,
+    0x7fffffffffffffff,
+]);
+
+// 签名域版本号
+pub const VERSION: &str = "1";
+
+// 计算 EIP-712 域分隔符，绑定合约名称、链 ID 和合约地址
+pub fn domain_separator(vm: &dyn Host, name: &str) -> FixedBytes<32> {
+    let type_hash = keccak(
+        "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+    );
+    keccak(
+        (
+            type_hash,
+            keccak(name),
+            keccak(VERSION),
+            U256::from(vm.chain_id()),
+            vm.contract_address(),
+        )
+            .abi_encode(),
+    )
+}
+
+// 计算最终需要签名的摘要：keccak256("\x19\x01" ‖ domainSeparator ‖ structHash)
+pub fn typed_data_hash(
+    domain_separator: FixedBytes<32>,
+    struct_hash: FixedBytes<32>,
+) -> FixedBytes<32> {
+    let mut data = Vec::with_capacity(66);
+    data.extend_from_slice(&[0x19, 0x01]);
+    data.extend_from_slice(domain_separator.as_slice());
+    data.extend_from_slice(struct_hash.as_slice());
+    keccak(data)
+}
+
+// 通过 ecrecover 预编译恢复签名者地址，签名无效时返回 None
+pub fn recover(
+    vm: &dyn Host,
+    digest: FixedBytes<32>,
+    v: u8,
+    r: FixedBytes<32>,
+    s: FixedBytes<32>,
+) -> Option<Address> {
+    // 只接受 27/28 形式的 v
+    if v != 27 && v != 28 {
+        return None;
+    }
+    // 拒绝高位 s，防止同一签名出现两种合法形式
+    if U256::from_be_bytes(s.0) > SECP256K1N_HALF {
+        return None;
+    }
+    // 按预编译要求拼接 hash ‖ v ‖ r ‖ s
+    let mut input = Vec::with_capacity(128);
+    input.extend_from_slice(digest.as_slice());
+    input.extend_from_slice(&U256::from(v).to_be_bytes::<32>());
+    input.extend_from_slice(r.as_slice());
+    input.extend_from_slice(s.as_slice());
+    let output = vm.static_call(&Call::new(), ECRECOVER, &input).ok()?;
+    // 签名无效时预编译返回空数据
+    if output.len() != 32 {
+        return None;
+    }
+    let signer = Address::from_slice(&output[12..]);
+    if signer.is_zero() {
+        return None;
+    }
+    Some(signer)
+}
@@ -0,0 +1,39 @@
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::SolValue;
+use stylus_sdk::crypto::keccak;
+
+// ERC-1167 代理字节码在实现地址之前的部分
+const PROXY_PREFIX: [u8; 20] = [
+    0x3d, 0x60, 0xad, 0x80, 0x60, 0x0a, 0x3d, 0x39, 0x81, 0xf3, 0x36, 0x3d, 0x3d, 0x37, 0x3d, 0x3d,
+    0x3d, 0x36, 0x3d, 0x73,
+];
+
+// ERC-1167 代理字节码在实现地址之后的部分
+const PROXY_SUFFIX: [u8; 15] = [
+    0x5a, 0xf4, 0x3d, 0x82, 0x80, 0x3e, 0x90, 0x3d, 0x91, 0x60, 0x2b, 0x57, 0xfd, 0x5b, 0xf3,
+];
+
+// 按 ERC-6551 注册表的 CREATE2 规则计算 token 绑定账户地址
+pub fn account_address(
+    registry: Address,
+    implementation: Address,
+    salt: FixedBytes<32>,
+    chain_id: U256,
+    token_contract: Address,
+    token_id: U256,
+) -> Address {
+    // 拼接账户代理的创建字节码，末尾附带 token 信息
+    let mut bytecode = Vec::with_capacity(55 + 128);
+    bytecode.extend_from_slice(&PROXY_PREFIX);
+    bytecode.extend_from_slice(implementation.as_slice());
+    bytecode.extend_from_slice(&PROXY_SUFFIX);
+    bytecode.extend_from_slice(&(salt, chain_id, token_contract, token_id).abi_encode());
+    // keccak256(0xff ‖ registry ‖ salt ‖ keccak256(bytecode)) 的后 20 字节
+    let mut data = Vec::with_capacity(85);
+    data.push(0xff);
+    data.extend_from_slice(registry.as_slice());
+    data.extend_from_slice(salt.as_slice());
+    data.extend_from_slice(keccak(bytecode).as_slice());
+    Address::from_slice(&keccak(data)[12..])
+}
@@ -1,8 +1,15 @@
 use alloc::{string::String, vec, vec::Vec};
-use alloy_primitives::{Address, FixedBytes, U256};
-use alloy_sol_types::sol;
+use alloy_primitives::{Address, FixedBytes, Uint, I64, U16, U256, U64, U8};
+use alloy_sol_types::{sol, SolCall, SolValue};
 use core::{borrow::BorrowMut, marker::PhantomData};
-use stylus_sdk::{abi::Bytes, evm, msg, prelude::*};
+use stylus_sdk::{
+    abi::Bytes,
+    crypto::keccak,
+    prelude::*,
+    stylus_core::calls::{context::Call, errors::Error as CallError},
+};
+
+use crate::{base58, base64, eip712};
 
 // 定义 ERC-721 所需的参数 trait
 pub trait Erc721Params {
@@ -10,36 +17,316 @@ pub trait Erc721Params {
     const NAME: &'static str;
     // NFT 的符号，常量
     const SYMBOL: &'static str;
+    // 最大供应量，None 表示不限量
+    const MAX_SUPPLY: Option<u64> = None;
+    // 揭示前统一返回的占位 URI，None 表示揭示前直接返回真实 URI
+    const PLACEHOLDER_URI: Option<&'static str> = None;
+    // 同一 token 两次转账之间的冷却秒数，0 表示不限制
+    const TRANSFER_COOLDOWN: u64 = 0;
+    // 铸造后允许转账的宽限秒数，超过后 token 不可转让，None 表示不限制
+    const TRANSFER_GRACE: Option<u64> = None;
+    // 批量方法单次可处理的最大数量
+    const MAX_BATCH: usize = 100;
+    // 是否为灵魂绑定集合，为 true 时 token 铸造后不可转让，但可销毁
+    const SOULBOUND: bool = false;
+    // 元数据是否随持有者变化，为 true 时每次转账都记录 ERC-4906 元数据更新事件
+    const DYNAMIC_METADATA: bool = false;
+    // 单个地址同时持有的 token 级授权数量上限，None 表示不限制，操作者授权不计入
+    const MAX_TOKEN_APPROVALS_HELD: Option<u64> = None;
+    // 单个拥有者同时授权的操作者数量上限，None 表示不限制，撤销授权始终允许
+    const MAX_OPERATORS_PER_OWNER: Option<u64> = None;
+    // 安全转账是否检查接收合约的 onERC721Received，为 false 时安全转账等同普通转账，
+    // 转入不支持 ERC-721 的合约后 token 可能永久无法转出，仅在接收方均为已知合约时关闭
+    const ENFORCE_RECEIVER: bool = true;
+    // 是否允许销毁，为 false 时所有销毁途径均回滚
+    const BURNABLE: bool = true;
+    // 是否只允许拥有者本人销毁，为 true 时操作者和被授权者即使可转账也不能销毁
+    const OWNER_ONLY_BURN: bool = false;
+    // 基础 URI 和专属 URI 的最大字节长度，None 表示不限制
+    const MAX_URI_LEN: Option<usize> = None;
+    // 按序铸造时随机抽出的稀有 token 数量，需设置 MAX_SUPPLY 才会生效
+    const GOLDEN_COUNT: u64 = 0;
+    // 取得 token（铸造或转入）后须持有的最短秒数，0 表示不限制
+    const MIN_HOLD_TIME: u64 = 0;
+    // 是否启用 ERC721Enumerable 枚举查询，为 false 时枚举方法回滚且不声明支持该接口
+    const ENUMERABLE: bool = true;
+    // 是否启用 ERC-5007 有效期查询，为 false 时相关方法回滚且不声明支持该接口
+    const TIMED: bool = true;
+    // 是否启用 ERC-4907 租用，为 false 时相关方法回滚且不声明支持该接口
+    const RENTABLE: bool = true;
+    // 是否启用 ERC-2981 版税查询，为 false 时 royalty_info 回滚且不声明支持该接口
+    const ROYALTIES: bool = true;
     // 获取指定 token_id 的 URI
     fn token_uri(token_id: U256) -> String;
+    // 每次普通转账后调用，返回需要写入的链上属性 (key, value)，返回空列表时不做任何修改，
+    // 可用于按转账次数重新计算动态稀有度，元数据已冻结的 token 不调用
+    fn on_transfer_mutate(_token_id: U256, _transfer_count: U256) -> Vec<(String, String)> {
+        Vec::new()
+    }
+    // 链上模式下生成 token 的 SVG 图像，默认显示集合名称和 token_id
+    fn render_svg(token_id: U256) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 350 350\"><rect width=\"100%\" height=\"100%\" fill=\"black\"/><text x=\"50%\" y=\"50%\" fill=\"white\" text-anchor=\"middle\">{} #{}</text></svg>",
+            Self::NAME,
+            token_id
+        )
+    }
 }
 
 // 定义 ERC-721 合约的存储结构
 sol_storage! {
     pub struct Erc721<T: Erc721Params> {
-        // token_id 到拥有者地址的映射
-        mapping(uint256 => address) owners;
+        // token_id 到拥有者信息的映射，拥有者与附加数据打包在同一存储槽
+        mapping(uint256 => TokenOwnership) owners;
         // 地址到余额的映射
         mapping(address => uint256) balances;
         // token_id 到授权用户地址的映射
         mapping(uint256 => address) token_approvals;
-        // 拥有者地址到操作者地址的授权映射
+        // 拥有者地址到操作者地址的授权映射，转账时保留，直到拥有者撤销
         mapping(address => mapping(address => bool)) operator_approvals;
-        // 总供应量
+        // 现存供应量，铸造时增加，销毁时减少
         uint256 total_supply;
+        // 拥有者地址到签名转账 nonce 的映射
+        mapping(address => uint256) transfer_nonces;
+        // 拥有者地址到其 token 列表（索引 => token_id）的映射
+        mapping(address => mapping(uint256 => uint256)) owned_tokens;
+        // token_id 在拥有者列表中的索引
+        mapping(uint256 => uint256) owned_tokens_index;
+        // 所有现存 token 的列表
+        uint256[] all_tokens;
+        // token_id 在全局列表中的索引
+        mapping(uint256 => uint256) all_tokens_index;
+        // 扩展注册的接口 ID
+        mapping(bytes4 => bool) registered_interfaces;
+        // 下一个待铸造的 token_id
+        uint256 next_token_id;
+        // token_id 到有效期的映射（ERC-5007）
+        mapping(uint256 => TimeInfo) time_info;
+        // token 是否处于质押状态
+        mapping(uint256 => bool) staked;
+        // token 开始质押的时间戳
+        mapping(uint256 => uint256) staked_at;
+        // 元数据是否已揭示
+        bool revealed;
+        // 揭示时确定的元数据偏移量
+        uint256 shuffle_offset;
+        // 参与偏移映射的 token 数量
+        uint256 shuffle_supply;
+        // token 授权的过期时间，为 0 表示永不过期
+        mapping(uint256 => uint256) approval_deadlines;
+        // token 剩余可使用次数
+        mapping(uint256 => uint256) uses_remaining;
+        // 持有者到其投票权受托人的映射
+        mapping(address => address) delegates;
+        // 受托人到其投票权检查点列表的映射
+        mapping(address => Checkpoint[]) checkpoints;
+        // 当前快照 ID，从 1 开始
+        uint256 current_snapshot_id;
+        // 地址到其余额快照列表的映射
+        mapping(address => BalanceSnapshot[]) balance_snapshots;
+        // 拥有者地址到操作者签名授权 nonce 的映射
+        mapping(address => uint256) operator_nonces;
+        // token_id 到专属 URI 的映射
+        mapping(uint256 => string) token_uris;
+        // 基础 URI，为空时使用 Erc721Params 中定义的 URI
+        string base_uri;
+        // token_id 到铸造时间戳的映射
+        mapping(uint256 => uint256) minted_at;
+        // 全部 token 的累计转账次数，不含铸造和销毁
+        uint256 total_transfers;
+        // token_id 到累计转账次数的映射，不含铸造和销毁
+        mapping(uint256 => uint256) transfer_count;
+        // token_id 到解锁者地址的映射，非零表示已锁定
+        mapping(uint256 => address) unlockers;
+        // 缓存的 EIP-712 域分隔符及其对应的链 ID
+        bytes32 cached_domain_separator;
+        uint256 cached_chain_id;
+        // 默认操作者，未主动退出的持有者均视为已授权
+        address default_operator;
+        // 持有者是否已退出对某个默认操作者的授权
+        mapping(address => mapping(address => bool)) default_operator_opt_out;
+        // token URI 的生成方式，取值见 URI_MODE_* 常量，
+        // 为 0 时依次使用专属 URI、揭示前占位 URI、基础 URI，最后回退到 Erc721Params 的 URI
+        uint8 uri_mode;
+        // 已暂停操作的位掩码，取值见 PAUSE_* 常量
+        uint8 paused_flags;
+        // 分段揭示列表，每段拥有独立的基础 URI
+        RevealSegment[] reveal_segments;
+        // token_id 到专属版税的映射
+        mapping(uint256 => TokenRoyalty) token_royalties;
+        // 扩展注册的接口 ID 列表，供枚举查询
+        bytes4[] registered_interface_list;
+        // 调用 onERC721Received 时转发的 gas 上限，为 0 表示转发全部剩余 gas
+        uint64 receiver_gas_limit;
+        // 元数据版本号，非零时拼接到生成的 URI 末尾，用于让网关缓存失效
+        uint256 metadata_version;
+        // 余额不为零的地址数量
+        uint256 holder_count;
+        // 基础 URI 后追加的后缀，未设置时使用 .json
+        string uri_suffix;
+        bool uri_suffix_set;
+        // 拥有者地址到签名销毁 nonce 的映射
+        mapping(address => uint256) burn_nonces;
+        // 地址到其当前持有的 token 级授权数量的映射
+        mapping(address => uint256) token_approvals_held;
+        // 拥有者地址到其当前授权的操作者数量的映射
+        mapping(address => uint256) operator_counts;
+        // 已销毁 token 返回的墓碑 URI，为空时查询已销毁 token 的 URI 仍回滚
+        string tombstone_uri;
+        // token_id 到元数据是否已冻结的映射，冻结后不可再修改专属 URI
+        mapping(uint256 => bool) token_frozen;
+        // 拥有者到其为操作者设置的转账额度的映射
+        mapping(address => mapping(address => OperatorBudget)) operator_budgets;
+        // token_id 到链上属性列表的映射，链上模式下写入 JSON 元数据
+        mapping(uint256 => Attribute[]) attributes;
+        // 地址是否已通过 KYC 验证
+        mapping(address => bool) kyc_verified;
+        // 是否只允许已验证地址持有和转出 token
+        bool kyc_required;
+        // token_id 到签名授权 nonce 的映射，每次转账或主动作废时递增
+        mapping(uint256 => uint256) permit_nonces;
+        // token_id 是否为稀有 token
+        mapping(uint256 => bool) golden;
+        // 已抽出的稀有 token 数量
+        uint256 golden_assigned;
+        // 已参与稀有抽取的 token 数量，包含按指定 id 铸造的 token
+        uint256 golden_drawn;
+        // token_id 到二进制 IPFS CID 的映射，比完整 URI 字符串占用更少存储
+        mapping(uint256 => bytes) token_cids;
+        // token_id 到租用者信息的映射（ERC-4907）
+        mapping(uint256 => UserInfo) users;
+        // 地址到其累计被铸造 token 数量的映射
+        mapping(address => uint256) mint_counts;
+        // 按累计铸造数量降序排列的铸造排行榜，最多保留 LEADERBOARD_SIZE 个地址
+        address[] top_minters;
+        // token_id 到最近一次转移记录的映射，供其他合约在链上查询
+        mapping(uint256 => TransferInfo) last_transfer_info;
         // 用于支持 Erc721Params 的 PhantomData
         PhantomData<T> phantom;
     }
+
+    // 投票权检查点，记录自某区块起的票数
+    pub struct Checkpoint {
+        uint64 from_block;
+        uint256 votes;
+    }
+
+    // 余额快照，记录某个快照 ID 时的余额
+    pub struct BalanceSnapshot {
+        uint256 id;
+        uint256 value;
+    }
+
+    // token 的拥有者信息，地址与最近一次转移时间共用一个存储槽，
+    // owner 位于槽的低位，与旧的 address 映射布局兼容
+    pub struct TokenOwnership {
+        address owner;
+        uint64 last_transfer;
+        // 是否已销毁，用于区分已销毁 token 与连续铸造中未显式写入的 token
+        bool burned;
+        // 以本槽为起点、共用本槽拥有者的连续 token 数量，0 表示只记录本 token
+        uint16 run_len;
+    }
+
+    // token 的专属版税，set 为 false 时使用集合默认版税
+    #[derive(Erase)]
+    pub struct TokenRoyalty {
+        bool set;
+        uint96 bps;
+    }
+
+    // 操作者的剩余转账额度，set 为 false 时不限制
+    #[derive(Erase)]
+    pub struct OperatorBudget {
+        bool set;
+        uint256 remaining;
+    }
+
+    // token 的租用者及其到期时间，两者共用一个存储槽
+    #[derive(Erase)]
+    pub struct UserInfo {
+        address user;
+        uint64 expires;
+    }
+
+    // token 最近一次转移的双方及时间
+    pub struct TransferInfo {
+        address from;
+        address to;
+        uint64 timestamp;
+    }
+
+    // token 的链上属性，对应 JSON 元数据中的 trait_type 和 value
+    pub struct Attribute {
+        string key;
+        string value;
+    }
+
+    // 分段揭示的 token 区间 [start, end] 及其基础 URI
+    pub struct RevealSegment {
+        uint256 start;
+        uint256 end;
+        string base_uri;
+    }
+
+    // token 的有效期，开始和结束时间共用一个存储槽
+    pub struct TimeInfo {
+        int64 start_time;
+        int64 end_time;
+    }
 }
 
 // 定义事件和 Solidity 错误类型
 sol! {
+    #![sol(all_derives)]
     // 转账事件
     event Transfer(address indexed from, address indexed to, uint256 indexed token_id);
     // 授权事件
     event Approval(address indexed owner, address indexed approved, uint256 indexed token_id);
     // 批量授权事件
     event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+    // 质押事件
+    event Staked(address indexed owner, uint256 indexed token_id, uint256 timestamp);
+    // 解除质押事件
+    event Unstaked(address indexed owner, uint256 indexed token_id, uint256 timestamp);
+    // token 使用次数消耗事件
+    event Consumed(uint256 indexed token_id, uint256 remaining);
+    // ERC-4906 元数据更新事件
+    event MetadataUpdate(uint256 token_id);
+    // token 专属版税设置事件
+    event RoyaltySet(uint256 indexed token_id, uint256 bps);
+    // token 专属版税重置为默认事件
+    event RoyaltyReset(uint256 indexed token_id);
+    // 销毁事件，与转账到零地址的 Transfer 事件同时记录
+    event Burn(address indexed owner, uint256 indexed token_id);
+    // ERC-4906 批量元数据更新事件
+    event BatchMetadataUpdate(uint256 from_token_id, uint256 to_token_id);
+    // token 锁定事件
+    event Locked(uint256 token_id);
+    // token 解锁事件
+    event Unlocked(uint256 token_id);
+    // 投票权受托人变更事件
+    event DelegateChanged(address indexed delegator, address indexed from_delegate, address indexed to_delegate);
+    // 受托人票数变更事件
+    event DelegateVotesChanged(address indexed delegate, uint256 previous_votes, uint256 new_votes);
+    // 余额快照事件
+    event Snapshot(uint256 id);
+    // 元数据揭示事件
+    event Revealed(uint256 shuffle_offset);
+    // 默认操作者变更事件，所有未退出持有者的有效授权随之变化
+    event DefaultOperatorChanged(address indexed previous_operator, address indexed new_operator);
+    // token 元数据冻结事件
+    event TokenMetadataFrozen(uint256 indexed token_id);
+    // 操作者转账额度设置事件，额度为 uint256 最大值表示不限制
+    event OperatorBudgetSet(address indexed owner, address indexed operator, uint256 budget);
+    // 地址 KYC 验证状态变更事件
+    event KycUpdated(address indexed account, bool verified);
+    // token 签名授权 nonce 被主动作废事件
+    event NonceInvalidated(uint256 indexed token_id, uint256 new_nonce);
+    // 稀有 token 铸造事件
+    event GoldenMinted(uint256 indexed token_id);
+    // ERC-4907 租用者变更事件
+    event UpdateUser(uint256 indexed token_id, address indexed user, uint64 expires);
 
     // token_id 未被铸造或已被销毁
     error InvalidTokenId(uint256 token_id);
@@ -51,20 +338,282 @@ sol! {
     error TransferToZero(uint256 token_id);
     // 接收者拒绝接收 token_id
     error ReceiverRefused(address receiver, uint256 token_id, bytes4 returned);
+    // 签名已过期
+    error ExpiredSignature(uint256 deadline);
+    // 签名无效或签名者无权操作
+    error InvalidSignature();
+    // 调用者不是合约所有者
+    error NotContractOwner(address caller);
+    // 调用者不在白名单中
+    error NotAllowlisted(address account);
+    // 白名单额度已用完
+    error AllowlistExhausted(address account);
+    // 拥有者列表索引越界
+    error OwnerIndexOutOfBounds(address owner, uint256 index);
+    // 全局列表索引越界
+    error GlobalIndexOutOfBounds(uint256 index);
+    // 已达到最大供应量
+    error MaxSupplyReached(uint256 max_supply);
+    // 结束时间早于开始时间
+    error InvalidTimeRange(int64 start_time, int64 end_time);
+    // token 处于质押状态，不可转移
+    error TokenStaked(uint256 token_id);
+    // token 未处于质押状态
+    error TokenNotStaked(uint256 token_id);
+    // 调用者不是待接收的合约所有者
+    error NotPendingOwner(address caller);
+    // 支付金额不足
+    error InsufficientPayment(uint256 required, uint256 paid);
+    // 退还多付金额失败
+    error RefundFailed(address to, uint256 amount);
+    // 拍卖配置无效，结束价格高于起始价格
+    error InvalidAuctionConfig(uint256 start_price, uint256 end_price);
+    // 元数据已经揭示过
+    error AlreadyRevealed();
+    // 来源证明哈希已设置过
+    error ProvenanceAlreadySet();
+    // 调用者缺少所需角色
+    error MissingRole(address account, bytes32 role);
+    // 包装 token 未对应任何已存入的外部 token
+    error NotWrapped(uint256 wrapper_id);
+    // 外部集合的 token 转移失败
+    error ExternalTransferFailed(address collection, uint256 token_id);
+    // token 已没有剩余使用次数
+    error NoUsesLeft(uint256 token_id);
+    // 单笔交易铸造数量超过上限
+    error TxLimitExceeded(uint256 requested, uint256 limit);
+    // 查询的区块尚未产生
+    error FutureLookup(uint256 block_number, uint256 current_block);
+    // 快照 ID 不存在
+    error NonexistentSnapshot(uint256 snapshot_id);
+    // token_id 已被铸造
+    error AlreadyMinted(uint256 token_id);
+    // ETH 转账失败
+    error EthTransferFailed(address to, uint256 amount);
+    // token 仍处于转账冷却期
+    error TransferCooldown(uint256 token_id, uint256 remaining);
+    // 通过注册表创建 token 绑定账户失败
+    error AccountCreationFailed(address registry, uint256 token_id);
+    // 合约已初始化
+    error AlreadyInitialized();
+    // ERC-20 代币转账失败
+    error Erc20TransferFailed(address token, address to, uint256 amount);
+    // 收款人与份额配置无效
+    error InvalidPayees(uint256 payees, uint256 shares);
+    // 收款人没有可提取的款项
+    error NothingToRelease(address account);
+    // 收款人仍有未提取的款项
+    error PendingPayments(address account, uint256 amount);
+    // 版税比例超过 100%
+    error InvalidRoyalty(uint256 bps);
+    // 当前没有进行中的铸造阶段
+    error NoActivePhase();
+    // 铸造阶段不存在
+    error NonexistentPhase(uint256 phase);
+    // 铸造阶段的时间窗口无效
+    error InvalidPhaseWindow(uint256 start, uint256 end);
+    // 已达到该阶段的单地址铸造上限
+    error PhaseLimitReached(uint256 phase, address account, uint256 limit);
+    // 受限销毁模式下调用者不是销毁授权合约
+    error NotBurnAuthority(address caller);
+    // 接收者的 onERC721Received 调用回滚，附带原始回滚数据
+    error ReceiverReverted(address receiver, uint256 token_id, bytes reason);
+    // token 已超过转让宽限期，不可转让
+    error TokenLocked(uint256 token_id);
+    // 调用者不是 token 的指定解锁者
+    error NotUnlocker(address caller, uint256 token_id);
+    // 批量操作数量超过上限
+    error BatchTooLarge(uint256 len, uint256 max);
+    // 不支持的 URI 模式
+    error InvalidUriMode(uint8 mode);
+    // 铸造 nonce 与当前记录不一致
+    error BadNonce(address account, uint256 expected, uint256 provided);
+    // 未支付足额的转账手续费
+    error FeeRequired(uint256 required, uint256 paid);
+    // 该类操作已暂停
+    error OperationPaused(uint8 flag);
+    // token 区间无效
+    error InvalidRange(uint256 start, uint256 end);
+    // 默克尔证明无效
+    error InvalidProof(address account);
+    // 空投额度已领取
+    error AlreadyClaimed(address account);
+    // 调用者不是允许的交易市场
+    error MarketplaceNotAllowed(address caller);
+    // 批量参数长度不一致
+    error LengthMismatch(uint256 left, uint256 right);
+    // 不允许推荐自己
+    error SelfReferral(address account);
+    // 创作者与平台的收益分配配置无效
+    error InvalidProceedsSplit(address creator, address platform, uint256 platform_bps);
+    // 地址没有铸造额度
+    error NoAllowance(address account);
+    // 请求铸造的数量超过剩余额度
+    error AllowanceExceeded(address account, uint256 requested, uint256 allowance);
+    // 未开启恢复模式
+    error RecoveryModeDisabled();
+    // 未配置托管合约
+    error VaultNotSet();
+    // 调用者不是托管合约
+    error NotVault(address caller);
+    // 地址持有的 token 级授权数量已达上限
+    error TooManyApprovals(address operator, uint256 limit);
+    // 拥有者授权的操作者数量已达上限
+    error TooManyOperators(address owner, uint256 limit);
+    // 荷兰式拍卖结束价格低于保留价
+    error BelowReserve(uint256 end_price, uint256 reserve_price);
+    // 配置已永久封存
+    error ContractSealed();
+    // 集合不允许销毁
+    error BurnDisabled();
+    // token 的元数据已冻结
+    error TokenFrozen(uint256 token_id);
+    // token 已超过退款期限或未开启退款
+    error RefundWindowClosed(uint256 token_id, uint256 deadline);
+    // 操作者的转账额度已用完
+    error BudgetExhausted(address owner, address operator);
+    // 未允许合约持有自身 token 时尝试转给本合约
+    error TransferToContractSelf(uint256 token_id);
+    // 地址已在候补名单中
+    error AlreadyWaitlisted(address account, uint256 position);
+    // 地址未通过 KYC 验证
+    error NotKycVerified(address account);
+    // 地址在同一区块内已铸造过
+    error MintThrottled(address account, uint256 block_number);
+    // URI 长度超过上限
+    error UriTooLong(uint256 len, uint256 max);
+    // 铸造阶段的配额已售罄
+    error PhaseSoldOut(uint256 phase, uint256 max_in_phase);
+    // 转账备注长度超过上限
+    error MemoTooLong(uint256 len, uint256 max);
+    // 当前持有者持有 token 的时间未达到最短持有时间
+    error MinHoldNotMet(uint256 token_id, uint256 transferable_at);
+    // 免费铸造已领完或未开放
+    error FreeMintEnded(uint256 cap);
+    // 地址已领取过免费铸造
+    error AlreadyClaimedFree(address account);
+    // 供应量计数溢出
+    error SupplyOverflow();
+    // 供应量计数下溢，正常情况下不可达
+    error SupplyUnderflow();
+    // 严格模式下转账回调失败
+    error HookFailed(address hook, uint256 token_id);
+    // 铸造时附带的 token 数据长度超过上限
+    error TokenDataTooLong(uint256 len, uint256 max);
+    // 迁移已封存
+    error MigrationSealed();
+    // 接收方为未列入白名单的合约
+    error DestinationNotAllowed(address to);
+    // 调用者不是已配置的转账回调合约
+    error NotSaleHook(address caller);
+    // ERC-20 铸造扣款失败
+    error PaymentFailed(address token, uint256 amount);
+    // 调用者对本合约的 ERC-20 授权额度不足
+    error InsufficientAllowance(uint256 allowance, uint256 required);
+    // 集合未启用该接口对应的扩展
+    error ExtensionDisabled(bytes4 interface_id);
 }
 
 // 定义 ERC-721 错误枚举
 #[derive(SolidityError)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
 pub enum Erc721Error {
     InvalidTokenId(InvalidTokenId),
     NotOwner(NotOwner),
     NotApproved(NotApproved),
     TransferToZero(TransferToZero),
     ReceiverRefused(ReceiverRefused),
+    ExpiredSignature(ExpiredSignature),
+    InvalidSignature(InvalidSignature),
+    NotContractOwner(NotContractOwner),
+    NotAllowlisted(NotAllowlisted),
+    AllowlistExhausted(AllowlistExhausted),
+    OwnerIndexOutOfBounds(OwnerIndexOutOfBounds),
+    GlobalIndexOutOfBounds(GlobalIndexOutOfBounds),
+    MaxSupplyReached(MaxSupplyReached),
+    InvalidTimeRange(InvalidTimeRange),
+    TokenStaked(TokenStaked),
+    TokenNotStaked(TokenNotStaked),
+    NotPendingOwner(NotPendingOwner),
+    InsufficientPayment(InsufficientPayment),
+    RefundFailed(RefundFailed),
+    InvalidAuctionConfig(InvalidAuctionConfig),
+    AlreadyRevealed(AlreadyRevealed),
+    ProvenanceAlreadySet(ProvenanceAlreadySet),
+    MissingRole(MissingRole),
+    NotWrapped(NotWrapped),
+    ExternalTransferFailed(ExternalTransferFailed),
+    NoUsesLeft(NoUsesLeft),
+    TxLimitExceeded(TxLimitExceeded),
+    FutureLookup(FutureLookup),
+    NonexistentSnapshot(NonexistentSnapshot),
+    AlreadyMinted(AlreadyMinted),
+    EthTransferFailed(EthTransferFailed),
+    TransferCooldown(TransferCooldown),
+    AccountCreationFailed(AccountCreationFailed),
+    AlreadyInitialized(AlreadyInitialized),
+    Erc20TransferFailed(Erc20TransferFailed),
+    InvalidPayees(InvalidPayees),
+    NothingToRelease(NothingToRelease),
+    PendingPayments(PendingPayments),
+    InvalidRoyalty(InvalidRoyalty),
+    NoActivePhase(NoActivePhase),
+    NonexistentPhase(NonexistentPhase),
+    InvalidPhaseWindow(InvalidPhaseWindow),
+    PhaseLimitReached(PhaseLimitReached),
+    NotBurnAuthority(NotBurnAuthority),
+    ReceiverReverted(ReceiverReverted),
+    TokenLocked(TokenLocked),
+    NotUnlocker(NotUnlocker),
+    BatchTooLarge(BatchTooLarge),
+    InvalidUriMode(InvalidUriMode),
+    BadNonce(BadNonce),
+    FeeRequired(FeeRequired),
+    OperationPaused(OperationPaused),
+    InvalidRange(InvalidRange),
+    InvalidProof(InvalidProof),
+    AlreadyClaimed(AlreadyClaimed),
+    MarketplaceNotAllowed(MarketplaceNotAllowed),
+    LengthMismatch(LengthMismatch),
+    SelfReferral(SelfReferral),
+    InvalidProceedsSplit(InvalidProceedsSplit),
+    NoAllowance(NoAllowance),
+    AllowanceExceeded(AllowanceExceeded),
+    RecoveryModeDisabled(RecoveryModeDisabled),
+    VaultNotSet(VaultNotSet),
+    NotVault(NotVault),
+    TooManyApprovals(TooManyApprovals),
+    TooManyOperators(TooManyOperators),
+    BelowReserve(BelowReserve),
+    ContractSealed(ContractSealed),
+    BurnDisabled(BurnDisabled),
+    TokenFrozen(TokenFrozen),
+    RefundWindowClosed(RefundWindowClosed),
+    BudgetExhausted(BudgetExhausted),
+    TransferToContractSelf(TransferToContractSelf),
+    AlreadyWaitlisted(AlreadyWaitlisted),
+    NotKycVerified(NotKycVerified),
+    MintThrottled(MintThrottled),
+    UriTooLong(UriTooLong),
+    PhaseSoldOut(PhaseSoldOut),
+    MemoTooLong(MemoTooLong),
+    MinHoldNotMet(MinHoldNotMet),
+    FreeMintEnded(FreeMintEnded),
+    AlreadyClaimedFree(AlreadyClaimedFree),
+    SupplyOverflow(SupplyOverflow),
+    SupplyUnderflow(SupplyUnderflow),
+    HookFailed(HookFailed),
+    TokenDataTooLong(TokenDataTooLong),
+    MigrationSealed(MigrationSealed),
+    DestinationNotAllowed(DestinationNotAllowed),
+    NotSaleHook(NotSaleHook),
+    PaymentFailed(PaymentFailed),
+    InsufficientAllowance(InsufficientAllowance),
+    ExtensionDisabled(ExtensionDisabled),
 }
 
 // 定义 IERC721TokenReceiver 接口
-sol_interface! {
+sol! {
     // 用于调用实现 IERC721TokenReceiver 的合约的 onERC721Received 方法
     interface IERC721TokenReceiver {
         function onERC721Received(address operator, address from, uint256 token_id, bytes data) external returns(bytes4);
@@ -74,14 +623,76 @@ sol_interface! {
 // 定义 onERC721Received 方法的选择器常量
 const ERC721_TOKEN_RECEIVER_ID: u32 = 0x150b7a02;
 
+// 基础支持的接口 ID
+const IERC165: u32 = 0x01ffc9a7;
+const IERC721: u32 = 0x80ac58cd;
+const IERC721_METADATA: u32 = 0x5b5e139f;
+// ERC-5192 灵魂绑定接口 ID，仅 SOULBOUND 时支持
+const IERC5192: u32 = 0xb45a3c0e;
+
+// 可选扩展的接口 ID，仅在 Erc721Params 中启用时注册
+pub const IERC721_ENUMERABLE: FixedBytes<4> = FixedBytes([0x78, 0x0e, 0x9d, 0x63]);
+pub const IERC5007: FixedBytes<4> = FixedBytes([0xf1, 0x40, 0xbe, 0x0d]);
+pub const IERC4907: FixedBytes<4> = FixedBytes([0xad, 0x09, 0x2b, 0x5c]);
+pub const IERC2981: FixedBytes<4> = FixedBytes([0x2a, 0x55, 0x20, 0x5a]);
+// ERC-4906 元数据更新接口 ID，元数据更新事件始终记录
+pub const IERC4906: FixedBytes<4> = FixedBytes([0x49, 0x06, 0x49, 0x06]);
+
+// 暂停标志位：铸造
+const PAUSE_MINT: u8 = 1 << 0;
+// 暂停标志位：转账
+const PAUSE_TRANSFER: u8 = 1 << 1;
+// 暂停标志位：授权
+const PAUSE_APPROVE: u8 = 1 << 2;
+// 暂停标志位：销毁
+const PAUSE_BURN: u8 = 1 << 3;
+
+// 基础 URI 加 token_id 模式
+const URI_MODE_BASE: u8 = 1;
+// 仅使用 token 专属 URI 模式
+const URI_MODE_TOKEN: u8 = 2;
+// 链上生成 JSON 元数据模式
+const URI_MODE_ONCHAIN: u8 = 3;
+
+// 铸造排行榜保留的地址数量，限制每次铸造更新排行榜的开销
+const LEADERBOARD_SIZE: usize = 10;
+
+// 签名转账结构体的 EIP-712 类型字符串
+const TRANSFER_TYPE: &str =
+    "Transfer(address from,address to,uint256 tokenId,uint256 nonce,uint256 deadline)";
+
+// 签名批量授权结构体的 EIP-712 类型字符串
+const PERMIT_FOR_ALL_TYPE: &str =
+    "PermitForAll(address owner,address operator,bool approved,uint256 nonce,uint256 deadline)";
+
+// 签名单个 token 授权结构体的 EIP-712 类型字符串
+const PERMIT_TYPE: &str = "Permit(address spender,uint256 tokenId,uint256 nonce,uint256 deadline)";
+
+// 签名销毁结构体的 EIP-712 类型字符串
+const BURN_TYPE: &str = "Burn(address owner,uint256 tokenId,uint256 nonce,uint256 deadline)";
+
+// 转义 JSON 字符串中的引号、反斜杠和控制字符
+fn json_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
 // 实现 ERC-721 内部方法
 impl<T: Erc721Params> Erc721<T> {
-    // 检查 msg::sender 是否有权操作指定 token
-    fn require_authorized_to_spend(
+    // 检查 msg::sender 是否有权操作指定 token，返回已验证的拥有者
+    pub fn require_authorized_to_spend(
         &self,
         from: Address,
         token_id: U256,
-    ) -> Result<(), Erc721Error> {
+    ) -> Result<Address, Erc721Error> {
         // 获取 token_id 的拥有者
         let owner = self.owner_of(token_id)?;
         // 验证 from 是否为拥有者
@@ -92,27 +703,159 @@ impl<T: Erc721Params> Erc721<T> {
                 real_owner: owner,
             }));
         }
-        // 如果调用者是拥有者，直接返回
-        if msg::sender() == owner {
-            return Ok(());
-        }
-        // 检查调用者是否为拥有者的操作者
-        if self.operator_approvals.getter(owner).get(msg::sender()) {
-            return Ok(());
-        }
-        // 检查调用者是否被授权操作此 token
-        if msg::sender() == self.token_approvals.get(token_id) {
-            return Ok(());
+        // 调用者为拥有者、操作者或被授权者时通过
+        if self.is_authorized(owner, self.vm().msg_sender(), token_id) {
+            return Ok(owner);
         }
         // 如果无授权，返回错误
         Err(Erc721Error::NotApproved(NotApproved {
             owner,
-            spender: msg::sender(),
+            spender: self.vm().msg_sender(),
             token_id,
         }))
     }
 
-    // 执行 token 转账操作
+    // 检查 msg::sender 是否有权销毁指定 token，OWNER_ONLY_BURN 时只接受拥有者本人，返回拥有者
+    pub fn require_authorized_to_burn(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+        if !T::OWNER_ONLY_BURN {
+            return self.require_authorized_to_spend(owner, token_id);
+        }
+        if self.vm().msg_sender() != owner {
+            return Err(Erc721Error::NotOwner(NotOwner {
+                from: self.vm().msg_sender(),
+                token_id,
+                real_owner: owner,
+            }));
+        }
+        Ok(owner)
+    }
+
+    // 检查指定类型的操作是否已暂停
+    fn require_not_paused(&self, flag: u8) -> Result<(), Erc721Error> {
+        if self.paused_flags.get().to::<u8>() & flag != 0 {
+            return Err(Erc721Error::OperationPaused(OperationPaused { flag }));
+        }
+        Ok(())
+    }
+
+    // 要求 KYC 时检查非零地址是否已验证
+    fn require_kyc(&self, account: Address) -> Result<(), Erc721Error> {
+        if self.kyc_required.get() && !account.is_zero() && !self.kyc_verified.get(account) {
+            return Err(Erc721Error::NotKycVerified(NotKycVerified { account }));
+        }
+        Ok(())
+    }
+
+    // 设置地址的 KYC 验证状态
+    pub fn set_kyc(&mut self, account: Address, verified: bool) {
+        self.kyc_verified.insert(account, verified);
+        log(self.vm(), KycUpdated { account, verified });
+    }
+
+    // 开启或关闭 KYC 要求，已持有的 token 不受影响，但未验证地址无法再转出
+    pub fn set_kyc_required(&mut self, required: bool) {
+        self.kyc_required.set(required);
+    }
+
+    // 设置已暂停操作的位掩码
+    pub fn set_paused(&mut self, flags: u8) {
+        self.paused_flags.set(U8::from(flags));
+    }
+
+    // 检查 spender 是否为拥有者、拥有者的操作者或 token 的被授权者
+    fn is_authorized(&self, owner: Address, spender: Address, token_id: U256) -> bool {
+        spender == owner
+            || self.is_operator(owner, spender)
+            || spender == self.approved_of(token_id)
+    }
+
+    // 获取 token 当前有效的授权地址，授权过期时返回零地址
+    fn approved_of(&self, token_id: U256) -> Address {
+        let deadline = self.approval_deadlines.get(token_id);
+        if !deadline.is_zero() && U256::from(self.vm().block_timestamp()) > deadline {
+            return Address::ZERO;
+        }
+        self.token_approvals.get(token_id)
+    }
+
+    // 获取当前持有者取得 token 的时间，即最近一次铸造或转入的时间
+    fn acquired_at(&self, token_id: U256) -> U256 {
+        let last_transfer = self
+            .owners
+            .getter(self.ownership_index(token_id))
+            .last_transfer
+            .get();
+        U256::from(last_transfer.to::<u64>())
+    }
+
+    // 计算 token 距离冷却期结束的剩余秒数
+    fn cooldown_of(&self, token_id: U256) -> U256 {
+        let ready_at = self.acquired_at(token_id) + U256::from(T::TRANSFER_COOLDOWN);
+        ready_at.saturating_sub(U256::from(self.vm().block_timestamp()))
+    }
+
+    // 计算当前持有者满足最短持有时间、可以转出 token 的时间
+    fn hold_deadline(&self, token_id: U256) -> U256 {
+        self.acquired_at(token_id) + U256::from(T::MIN_HOLD_TIME)
+    }
+
+    // 计算 token 可转让的截止时间，不限制时返回 uint256 最大值
+    fn transferable_deadline(&self, token_id: U256) -> U256 {
+        match T::TRANSFER_GRACE {
+            Some(grace) => self.minted_at.get(token_id) + U256::from(grace),
+            None => U256::MAX,
+        }
+    }
+
+    // 读取 token 的拥有者，未铸造或已销毁时为零地址
+    fn owner_raw(&self, token_id: U256) -> Address {
+        self.owners
+            .getter(self.ownership_index(token_id))
+            .owner
+            .get()
+    }
+
+    // 计算下一次铸造将分配的 token_id，跳过已按指定 id 铸造的 token
+    fn upcoming_token_id(&self) -> U256 {
+        let mut token_id = self.next_token_id.get();
+        while self.is_minted(token_id) {
+            token_id += U256::from(1u8);
+        }
+        token_id
+    }
+
+    // 检查 token 是否曾被铸造，包含已销毁的 token
+    fn is_minted(&self, token_id: U256) -> bool {
+        !self.owner_raw(token_id).is_zero() || self.owners.getter(token_id).burned.get()
+    }
+
+    // 查找保存 token 拥有者信息的存储槽索引
+    // 连续铸造只写入首个 token，其余 token 需向前查找最近的显式拥有者，
+    // 且该槽记录的连续区间需覆盖本 token，区间长度不超过 MAX_BATCH，因此最多向前查找 MAX_BATCH 个槽
+    fn ownership_index(&self, token_id: U256) -> U256 {
+        let slot = self.owners.getter(token_id);
+        if !slot.owner.get().is_zero() || slot.burned.get() || token_id >= self.next_token_id.get()
+        {
+            return token_id;
+        }
+        let mut index = token_id;
+        let mut steps = 0;
+        while index > U256::ZERO && steps < T::MAX_BATCH {
+            index -= U256::from(1);
+            steps += 1;
+            let slot = self.owners.getter(index);
+            if !slot.owner.get().is_zero() || slot.burned.get() {
+                if index + U256::from(slot.run_len.get()) > token_id {
+                    return index;
+                }
+                break;
+            }
+        }
+        token_id
+    }
+
+    // 执行 token 转账操作，验证 from 为当前拥有者
     pub fn transfer(
         &mut self,
         token_id: U256,
@@ -120,8 +863,7 @@ impl<T: Erc721Params> Erc721<T> {
         to: Address,
     ) -> Result<(), Erc721Error> {
         // 获取 token_id 的拥有者
-        let mut owner = self.owners.setter(token_id);
-        let previous_owner = owner.get();
+        let previous_owner = self.owner_raw(token_id);
         // 验证 from 是否为拥有者
         if previous_owner != from {
             return Err(Erc721Error::NotOwner(NotOwner {
@@ -130,226 +872,2283 @@ impl<T: Erc721Params> Erc721<T> {
                 real_owner: previous_owner,
             }));
         }
-        // 更新 token 的拥有者
-        owner.set(to);
-        // 减少 from 的余额
-        let mut from_balance = self.balances.setter(from);
-        let balance = from_balance.get() - U256::from(1);
-        from_balance.set(balance);
-        // 增加 to 的余额
-        let mut to_balance = self.balances.setter(to);
-        let balance = to_balance.get() + U256::from(1);
-        to_balance.set(balance);
-        // 清除 token 的授权记录
-        self.token_approvals.delete(token_id);
-        // 记录转账事件
-        evm::log(Transfer { from, to, token_id });
-        Ok(())
+        self.transfer_verified(token_id, from, to)
     }
 
-    // 如果接收者是合约，调用 onERC721Received 方法
-    fn call_receiver<S: TopLevelStorage>(
-        storage: &mut S,
+    // 执行 token 转账操作，调用方需已验证 from 为当前拥有者
+    fn transfer_verified(
+        &mut self,
         token_id: U256,
         from: Address,
         to: Address,
-        data: Vec<u8>,
     ) -> Result<(), Erc721Error> {
-        // 检查接收者是否为合约
-        if to.has_code() {
-            // 创建接收者接口实例
-            let receiver = IERC721TokenReceiver::new(to);
-            // 调用 onERC721Received 方法
-            let received = receiver
-                .on_erc_721_received(&mut *storage, msg::sender(), from, token_id, data.into())
-                .map_err(|_e| {
-                    Erc721Error::ReceiverRefused(ReceiverRefused {
-                        receiver: receiver.address,
-                        token_id,
-                        returned: alloy_primitives::FixedBytes(0_u32.to_be_bytes()),
-                    })
-                })?
-                .0;
-            // 验证返回的选择器是否正确
-            if u32::from_be_bytes(received) != ERC721_TOKEN_RECEIVER_ID {
-                return Err(Erc721Error::ReceiverRefused(ReceiverRefused {
-                    receiver: receiver.address,
+        // 按操作类型检查是否已暂停
+        self.require_not_paused(if from.is_zero() {
+            PAUSE_MINT
+        } else if to.is_zero() {
+            PAUSE_BURN
+        } else {
+            PAUSE_TRANSFER
+        })?;
+        // 要求 KYC 时，铸造和转账的非零发送方和接收方均需已验证，销毁不受限制
+        if !to.is_zero() {
+            self.require_kyc(from)?;
+            self.require_kyc(to)?;
+        }
+        // 质押中的 token 不可转移
+        if self.staked.get(token_id) {
+            return Err(Erc721Error::TokenStaked(TokenStaked { token_id }));
+        }
+        // 锁定中的 token 不可转移或销毁
+        if !self.unlockers.get(token_id).is_zero() {
+            return Err(Erc721Error::TokenLocked(TokenLocked { token_id }));
+        }
+        // 普通转账需等待冷却期结束，铸造和销毁不受限制
+        if !from.is_zero() && !to.is_zero() {
+            // 灵魂绑定集合不可转让
+            if T::SOULBOUND {
+                return Err(Erc721Error::TokenLocked(TokenLocked { token_id }));
+            }
+            let remaining = self.cooldown_of(token_id);
+            if !remaining.is_zero() {
+                return Err(Erc721Error::TransferCooldown(TransferCooldown {
+                    token_id,
+                    remaining,
+                }));
+            }
+            // 持有时间未达到最短持有时间则不可转出
+            let transferable_at = self.hold_deadline(token_id);
+            if U256::from(self.vm().block_timestamp()) < transferable_at {
+                return Err(Erc721Error::MinHoldNotMet(MinHoldNotMet {
                     token_id,
-                    returned: alloy_primitives::FixedBytes(received),
+                    transferable_at,
                 }));
             }
+            // 超过铸造后的宽限期则不可转让
+            if U256::from(self.vm().block_timestamp()) > self.transferable_deadline(token_id) {
+                return Err(Erc721Error::TokenLocked(TokenLocked { token_id }));
+            }
+        }
+        // 转给自己时同样经过上述检查，但不改动余额和索引，只清除授权并记录事件
+        if from == to && !from.is_zero() {
+            self.clear_token_approval(token_id);
+            log(self.vm(), Transfer { from, to, token_id });
+            return Ok(());
+        }
+        if !from.is_zero() && !to.is_zero() {
+            // 转账后旧持有者签发的授权签名全部失效
+            self.permit_nonces
+                .insert(token_id, self.permit_nonces.get(token_id) + U256::from(1));
+            // 统计转账次数，铸造和销毁不计入
+            self.total_transfers
+                .set(self.total_transfers.get() + U256::from(1));
+            self.transfer_count
+                .insert(token_id, self.transfer_count.get(token_id) + U256::from(1));
+        }
+        // 铸造时记录时间并计入排行榜，销毁时清除
+        if from.is_zero() {
+            self.minted_at
+                .insert(token_id, U256::from(self.vm().block_timestamp()));
+            self.record_mints(to, U256::from(1));
+        } else if to.is_zero() {
+            self.minted_at.delete(token_id);
+        }
+        // 下一个 token 若依赖本 token 的槽隐式确定拥有者，先将其显式写入
+        if !from.is_zero() {
+            let next_id = token_id + U256::from(1);
+            let next_slot = self.owners.getter(next_id);
+            if next_id < self.next_token_id.get()
+                && next_slot.owner.get().is_zero()
+                && !next_slot.burned.get()
+            {
+                let index = self.ownership_index(token_id);
+                let (last_transfer, run_end) = {
+                    let origin = self.owners.getter(index);
+                    (
+                        origin.last_transfer.get(),
+                        index + U256::from(origin.run_len.get()),
+                    )
+                };
+                // 区间仍覆盖下一个 token 时，将剩余部分交由下一个槽记录
+                if run_end > next_id {
+                    let mut next_slot = self.owners.setter(next_id);
+                    next_slot.owner.set(from);
+                    next_slot.last_transfer.set(last_transfer);
+                    next_slot.run_len.set(U16::from(run_end - next_id));
+                }
+            }
+        }
+        // 更新 token 的拥有者和转移时间，两者写入同一存储槽，销毁时一并清除并标记
+        let now = U64::from(self.vm().block_timestamp());
+        let mut ownership = self.owners.setter(token_id);
+        ownership.owner.set(to);
+        ownership.run_len.set(U16::ZERO);
+        if to.is_zero() {
+            ownership.last_transfer.set(U64::ZERO);
+            ownership.burned.set(true);
+        } else {
+            ownership.last_transfer.set(now);
+        }
+        // 余额变化前为双方记录快照
+        if !from.is_zero() {
+            self.update_balance_snapshot(from);
+        }
+        if !to.is_zero() {
+            self.update_balance_snapshot(to);
+        }
+        // 减少 from 的余额，铸造时不改动零地址的余额槽
+        let balance = if from.is_zero() {
+            U256::ZERO
+        } else {
+            self.unchecked_dec(from)
+        };
+        // 更新枚举索引，铸造时加入全局列表，否则从 from 的列表中移除
+        if from.is_zero() {
+            self.add_token_to_all_tokens_enumeration(token_id);
+        } else {
+            self.remove_token_from_owner_enumeration(from, token_id, balance);
+        }
+        // 销毁时从全局列表移除，否则加入 to 的列表
+        if to.is_zero() {
+            self.remove_token_from_all_tokens_enumeration(token_id);
+        } else {
+            let index = self.balances.get(to);
+            self.add_token_to_owner_enumeration(to, token_id, index);
+        }
+        // 增加 to 的余额，销毁时不改动零地址的余额槽
+        let to_balance = if to.is_zero() {
+            U256::ZERO
+        } else {
+            self.unchecked_inc(to)
+        };
+        // 持有者数量：from 清空时减一，to 首次持有时加一，零地址不计入
+        if !from.is_zero() && balance.is_zero() {
+            self.holder_count
+                .set(self.holder_count.get() - U256::from(1));
+        }
+        if !to.is_zero() && to_balance == U256::from(1) {
+            self.holder_count
+                .set(self.holder_count.get() + U256::from(1));
+        }
+        // 清除 token 级授权记录，操作者授权按持有者记录，不受转账影响
+        self.clear_token_approval(token_id);
+        // 持有者变更或销毁时清除租用者
+        self.clear_user(token_id);
+        // 将一票投票权从 from 的受托人转移到 to 的受托人
+        let from_delegate = self.delegates.get(from);
+        let to_delegate = self.delegates.get(to);
+        self.move_voting_power(from_delegate, to_delegate, U256::from(1));
+        // 记录最近一次转移，铸造和销毁同样记录
+        let mut info = self.last_transfer_info.setter(token_id);
+        info.from.set(from);
+        info.to.set(to);
+        info.timestamp.set(now);
+        // 记录转账事件
+        log(self.vm(), Transfer { from, to, token_id });
+        // 灵魂绑定集合在铸造时记录 ERC-5192 锁定事件
+        if T::SOULBOUND && from.is_zero() {
+            log(self.vm(), Locked { token_id });
+        }
+        // 动态元数据集合在持有者变更后通知刷新，铸造和销毁不记录
+        if T::DYNAMIC_METADATA && !from.is_zero() && !to.is_zero() {
+            log(self.vm(), MetadataUpdate { token_id });
+        }
+        // 由转账钩子更新属性，DYNAMIC_METADATA 已记录过元数据更新事件时不重复记录
+        if !from.is_zero() && !to.is_zero() && !self.token_frozen.get(token_id) {
+            let mutations = T::on_transfer_mutate(token_id, self.transfer_count.get(token_id));
+            if !mutations.is_empty() {
+                for (key, value) in mutations {
+                    self.write_attribute(token_id, key, value);
+                }
+                if !T::DYNAMIC_METADATA {
+                    log(self.vm(), MetadataUpdate { token_id });
+                }
+            }
         }
         Ok(())
     }
 
-    // 执行安全转账并调用 onERC721Received
-    pub fn safe_transfer<S: TopLevelStorage + BorrowMut<Self>>(
-        storage: &mut S,
-        token_id: U256,
-        from: Address,
-        to: Address,
-        data: Vec<u8>,
-    ) -> Result<(), Erc721Error> {
-        // 执行转账
-        storage.borrow_mut().transfer(token_id, from, to)?;
-        // 调用接收者检查
-        Self::call_receiver(storage, token_id, from, to, data)
+    // 清除 token 的租用者，未设置时不记录事件
+    fn clear_user(&mut self, token_id: U256) {
+        if self.users.getter(token_id).user.get().is_zero() {
+            return;
+        }
+        self.users.delete(token_id);
+        log(
+            self.vm(),
+            UpdateUser {
+                token_id,
+                user: Address::ZERO,
+                expires: 0,
+            },
+        );
     }
 
-    // 铸造新 token 并转账给 to
-    pub fn mint(&mut self, to: Address) -> Result<(), Erc721Error> {
-        // 获取当前总供应量作为新 token_id
-        let new_token_id = self.total_supply.get();
-        // 增加总供应量
-        self.total_supply.set(new_token_id + U256::from(1u8));
-        // 执行转账，从零地址到接收者
-        self.transfer(new_token_id, Address::default(), to)?;
-        Ok(())
+    // 累计地址被铸造的数量并更新铸造排行榜
+    fn record_mints(&mut self, account: Address, quantity: U256) {
+        let count = self.mint_counts.get(account) + quantity;
+        self.mint_counts.insert(account, count);
+        // 找到地址在排行榜中的位置，不在榜上时尝试加入末尾
+        let len = self.top_minters.len();
+        let mut index = match (0..len).find(|&i| self.top_minters.get(i) == Some(account)) {
+            Some(index) => index,
+            None if len < LEADERBOARD_SIZE => {
+                self.top_minters.push(account);
+                len
+            }
+            None => {
+                let last = self.top_minters.get(len - 1).unwrap_or_default();
+                if self.mint_counts.get(last) >= count {
+                    return;
+                }
+                if let Some(mut slot) = self.top_minters.setter(len - 1) {
+                    slot.set(account);
+                }
+                len - 1
+            }
+        };
+        // 向前冒泡，数量相同时先上榜的地址排在前面
+        while index > 0 {
+            let previous = self.top_minters.get(index - 1).unwrap_or_default();
+            if self.mint_counts.get(previous) >= count {
+                break;
+            }
+            if let Some(mut slot) = self.top_minters.setter(index - 1) {
+                slot.set(account);
+            }
+            if let Some(mut slot) = self.top_minters.setter(index) {
+                slot.set(previous);
+            }
+            index -= 1;
+        }
     }
 
-    // 销毁指定 token
-    pub fn burn(&mut self, from: Address, token_id: U256) -> Result<(), Erc721Error> {
-        // 执行转账到零地址
-        self.transfer(token_id, from, Address::default())?;
-        Ok(())
+    // 在两个受托人之间转移投票权，零地址表示无受托人
+    fn move_voting_power(&mut self, src: Address, dst: Address, amount: U256) {
+        if src == dst || amount.is_zero() {
+            return;
+        }
+        if !src.is_zero() {
+            let previous_votes = self.current_votes(src);
+            let new_votes = previous_votes - amount;
+            self.write_checkpoint(src, new_votes);
+            log(
+                self.vm(),
+                DelegateVotesChanged {
+                    delegate: src,
+                    previous_votes,
+                    new_votes,
+                },
+            );
+        }
+        if !dst.is_zero() {
+            let previous_votes = self.current_votes(dst);
+            let new_votes = previous_votes + amount;
+            self.write_checkpoint(dst, new_votes);
+            log(
+                self.vm(),
+                DelegateVotesChanged {
+                    delegate: dst,
+                    previous_votes,
+                    new_votes,
+                },
+            );
+        }
     }
-}
 
-// 实现 ERC-721 外部方法
-#[public]
-impl<T: Erc721Params> Erc721<T> {
-    // 获取 NFT 名称
-    pub fn name() -> Result<String, Erc721Error> {
-        Ok(T::NAME.into())
+    // 获取受托人当前票数
+    fn current_votes(&self, account: Address) -> U256 {
+        let checkpoints = self.checkpoints.getter(account);
+        match checkpoints.len() {
+            0 => U256::ZERO,
+            len => checkpoints
+                .getter(len - 1)
+                .map_or(U256::ZERO, |checkpoint| checkpoint.votes.get()),
+        }
     }
 
-    // 获取 NFT 符号
-    pub fn symbol() -> Result<String, Erc721Error> {
-        Ok(T::SYMBOL.into())
+    // 写入检查点，同一区块内多次变更只保留最后的票数
+    fn write_checkpoint(&mut self, account: Address, votes: U256) {
+        let block_number = self.vm().block_number();
+        let mut checkpoints = self.checkpoints.setter(account);
+        let len = checkpoints.len();
+        if len > 0 {
+            if let Some(mut last) = checkpoints.setter(len - 1) {
+                if last.from_block.get() == U64::from(block_number) {
+                    last.votes.set(votes);
+                    return;
+                }
+            }
+        }
+        let mut checkpoint = checkpoints.grow();
+        checkpoint.from_block.set(U64::from(block_number));
+        checkpoint.votes.set(votes);
     }
 
-    // 获取指定 token 的 URI
-    #[selector(name = "tokenURI")]
-    pub fn token_uri(&self, token_id: U256) -> Result<String, Erc721Error> {
-        // 确保 token 存在
-        self.owner_of(token_id)?;
-        Ok(T::token_uri(token_id))
+    // 创建新快照，返回快照 ID
+    pub fn snapshot(&mut self) -> U256 {
+        let id = self.current_snapshot_id.get() + U256::from(1);
+        self.current_snapshot_id.set(id);
+        log(self.vm(), Snapshot { id });
+        id
     }
 
-    // 获取指定地址的 NFT 余额
-    pub fn balance_of(&self, owner: Address) -> Result<U256, Erc721Error> {
-        Ok(self.balances.get(owner))
+    // 余额即将变化时，若本快照期间尚未记录则保存当前余额
+    fn update_balance_snapshot(&mut self, account: Address) {
+        let current_id = self.current_snapshot_id.get();
+        if current_id.is_zero() {
+            return;
+        }
+        let balance = self.balances.get(account);
+        let mut snapshots = self.balance_snapshots.setter(account);
+        let len = snapshots.len();
+        let last_id = match len {
+            0 => U256::ZERO,
+            len => snapshots
+                .getter(len - 1)
+                .map_or(U256::ZERO, |snapshot| snapshot.id.get()),
+        };
+        if last_id < current_id {
+            let mut snapshot = snapshots.grow();
+            snapshot.id.set(current_id);
+            snapshot.value.set(balance);
+        }
     }
 
-    // 获取指定 token 的拥有者
-    pub fn owner_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
-        // 获取 token 的拥有者
-        let owner = self.owners.get(token_id);
-        // 如果拥有者是零地址，token 无效
-        if owner.is_zero() {
-            return Err(Erc721Error::InvalidTokenId(InvalidTokenId { token_id }));
+    // 设置操作者授权并记录事件
+    fn set_operator_approval(
+        &mut self,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), Erc721Error> {
+        self.require_not_paused(PAUSE_APPROVE)?;
+        // 新增操作者时检查数量上限，重复授权和撤销不受限制
+        if let Some(limit) = T::MAX_OPERATORS_PER_OWNER {
+            if approved
+                && !self.operator_approvals.getter(owner).get(operator)
+                && self.operator_counts.get(owner) >= U256::from(limit)
+            {
+                return Err(Erc721Error::TooManyOperators(TooManyOperators {
+                    owner,
+                    limit: U256::from(limit),
+                }));
+            }
         }
-        Ok(owner)
+        self.write_operator_approval(owner, operator, approved);
+        Ok(())
     }
 
-    // 执行带数据的安全转账
-    #[selector(name = "safeTransferFrom")]
-    pub fn safe_transfer_from_with_data<S: TopLevelStorage + BorrowMut<Self>>(
-        storage: &mut S,
-        from: Address,
-        to: Address,
-        token_id: U256,
-        data: Bytes,
-    ) -> Result<(), Erc721Error> {
-        // 禁止转账到零地址
-        if to.is_zero() {
-            return Err(Erc721Error::TransferToZero(TransferToZero { token_id }));
+    // 写入操作者授权并记录事件，同步更新拥有者的操作者数量，不检查暂停状态和数量上限
+    pub fn write_operator_approval(&mut self, owner: Address, operator: Address, approved: bool) {
+        let previous = self.operator_approvals.getter(owner).get(operator);
+        if approved && !previous {
+            self.operator_counts
+                .insert(owner, self.operator_counts.get(owner) + U256::from(1));
+        } else if !approved && previous {
+            self.operator_counts
+                .insert(owner, self.operator_counts.get(owner) - U256::from(1));
         }
-        // 检查调用者是否有权限
-        storage
-            .borrow_mut()
-            .require_authorized_to_spend(from, token_id)?;
-        // 执行安全转账
-        Self::safe_transfer(storage, token_id, from, to, data.0)
+        self.operator_approvals
+            .setter(owner)
+            .insert(operator, approved);
+        // 对默认操作者的设置同时记录为是否退出
+        if operator == self.default_operator.get() {
+            self.default_operator_opt_out
+                .setter(owner)
+                .insert(operator, !approved);
+        }
+        // 记录批量授权事件
+        log(
+            self.vm(),
+            ApprovalForAll {
+                owner,
+                operator,
+                approved,
+            },
+        );
     }
 
-    // 执行不带数据的安全转账
-    #[selector(name = "safeTransferFrom")]
-    pub fn safe_transfer_from<S: TopLevelStorage + BorrowMut<Self>>(
-        storage: &mut S,
-        from: Address,
-        to: Address,
+    // 调用者以操作者身份转账时扣减其转账额度，拥有者本人和 token 被授权者不受限制
+    fn consume_operator_budget(
+        &mut self,
+        owner: Address,
         token_id: U256,
     ) -> Result<(), Erc721Error> {
-        // 调用带数据的安全转账，数据为空
-        Self::safe_transfer_from_with_data(storage, from, to, token_id, Bytes(vec![]))
+        let operator = self.vm().msg_sender();
+        self.consume_budget_of(owner, operator, token_id)
     }
 
-    // 执行普通转账
-    pub fn transfer_from(
+    // 扣减指定操作者的转账额度，签名转账时操作者为签名者而非调用者
+    fn consume_budget_of(
         &mut self,
-        from: Address,
-        to: Address,
+        owner: Address,
+        operator: Address,
         token_id: U256,
     ) -> Result<(), Erc721Error> {
-        // 禁止转账到零地址
-        if to.is_zero() {
-            return Err(Erc721Error::TransferToZero(TransferToZero { token_id }));
+        if operator == owner || operator == self.approved_of(token_id) {
+            return Ok(());
         }
-        // 检查调用者是否有权限
-        self.require_authorized_to_spend(from, token_id)?;
-        // 执行转账
-        self.transfer(token_id, from, to)?;
-        Ok(())
-    }
-
-    // 为指定 token 设置授权
-    pub fn approve(&mut self, approved: Address, token_id: U256) -> Result<(), Erc721Error> {
-        // 获取 token 的拥有者
-        let owner = self.owner_of(token_id)?;
-        // 验证调用者是否有权限
-        if msg::sender() != owner && !self.operator_approvals.getter(owner).get(msg::sender()) {
-            return Err(Erc721Error::NotApproved(NotApproved {
+        let (set, remaining) = {
+            let budgets = self.operator_budgets.getter(owner);
+            let budget = budgets.getter(operator);
+            (budget.set.get(), budget.remaining.get())
+        };
+        if !set {
+            return Ok(());
+        }
+        if remaining.is_zero() {
+            return Err(Erc721Error::BudgetExhausted(BudgetExhausted {
                 owner,
-                spender: msg::sender(),
-                token_id,
+                operator,
             }));
         }
-        // 设置授权
-        self.token_approvals.insert(token_id, approved);
-        // 记录授权事件
-        evm::log(Approval {
-            approved,
-            owner,
-            token_id,
-        });
+        self.operator_budgets
+            .setter(owner)
+            .setter(operator)
+            .remaining
+            .set(remaining - U256::from(1));
         Ok(())
     }
 
-    // 设置批量授权
-    pub fn set_approval_for_all(
+    // 检查 operator 是否为 owner 的操作者，包含未退出的默认操作者
+    fn is_operator(&self, owner: Address, operator: Address) -> bool {
+        if self.operator_approvals.getter(owner).get(operator) {
+            return true;
+        }
+        let default_operator = self.default_operator.get();
+        !default_operator.is_zero()
+            && operator == default_operator
+            && !self.default_operator_opt_out.getter(owner).get(operator)
+    }
+
+    // 设置默认操作者，零地址表示关闭
+    pub fn set_default_operator(&mut self, operator: Address) {
+        let previous_operator = self.default_operator.get();
+        if previous_operator == operator {
+            return;
+        }
+        self.default_operator.set(operator);
+        log(
+            self.vm(),
+            DefaultOperatorChanged {
+                previous_operator,
+                new_operator: operator,
+            },
+        );
+    }
+
+    // 检查签名期限并恢复 EIP-712 签名者
+    pub fn recover_signer(
+        &self,
+        struct_hash: FixedBytes<32>,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<Address, Erc721Error> {
+        // 检查签名是否过期
+        if U256::from(self.vm().block_timestamp()) > deadline {
+            return Err(Erc721Error::ExpiredSignature(ExpiredSignature { deadline }));
+        }
+        self.recover_typed_signer(struct_hash, v, r, s)
+    }
+
+    // 恢复不带期限的 EIP-712 签名者
+    pub fn recover_typed_signer(
+        &self,
+        struct_hash: FixedBytes<32>,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<Address, Erc721Error> {
+        let digest = eip712::typed_data_hash(self.current_domain_separator(), struct_hash);
+        eip712::recover(self.vm(), digest, v, r, s)
+            .ok_or(Erc721Error::InvalidSignature(InvalidSignature {}))
+    }
+
+    // 缓存当前链上的域分隔符
+    pub fn cache_domain_separator(&mut self) {
+        self.cached_domain_separator
+            .set(eip712::domain_separator(self.vm(), T::NAME));
+        self.cached_chain_id.set(U256::from(self.vm().chain_id()));
+    }
+
+    // 获取当前链上的域分隔符，链 ID 与缓存不一致时（如分叉后）重新计算
+    fn current_domain_separator(&self) -> FixedBytes<32> {
+        if self.cached_chain_id.get() == U256::from(self.vm().chain_id())
+            && !self.cached_domain_separator.get().is_zero()
+        {
+            return self.cached_domain_separator.get();
+        }
+        eip712::domain_separator(self.vm(), T::NAME)
+    }
+
+    // 检查批量操作的数量是否超过上限，所有批量方法共用
+    pub fn check_batch_len(len: usize) -> Result<(), Erc721Error> {
+        if len > T::MAX_BATCH {
+            return Err(Erc721Error::BatchTooLarge(BatchTooLarge {
+                len: U256::from(len),
+                max: U256::from(T::MAX_BATCH),
+            }));
+        }
+        Ok(())
+    }
+
+    // 设置调用 onERC721Received 时转发的 gas 上限，为 0 表示转发全部剩余 gas
+    pub fn set_receiver_gas_limit(&mut self, gas_limit: u64) {
+        self.receiver_gas_limit.set(U64::from(gas_limit));
+    }
+
+    // 注册扩展实现的接口 ID，供 supports_interface 查询
+    pub fn register_interface(&mut self, interface: FixedBytes<4>) {
+        // 0xffffffff 按 ERC165 规定永远不被支持
+        if interface == FixedBytes([0xff; 4]) {
+            return;
+        }
+        // 已注册的接口不重复加入列表
+        if self.registered_interfaces.get(interface) {
+            return;
+        }
+        self.registered_interfaces.insert(interface, true);
+        self.registered_interface_list.push(interface);
+    }
+
+    // 按 Erc721Params 注册已启用扩展的接口 ID，未启用的扩展不声明支持
+    pub fn register_extensions(&mut self) {
+        let extensions = [
+            (T::ENUMERABLE, IERC721_ENUMERABLE),
+            (T::TIMED, IERC5007),
+            (T::RENTABLE, IERC4907),
+            (T::ROYALTIES, IERC2981),
+            (true, IERC4906),
+        ];
+        for (enabled, interface) in extensions {
+            if enabled {
+                self.register_interface(interface);
+            }
+        }
+    }
+
+    // 检查扩展是否启用，未启用时以对应接口 ID 回滚
+    pub fn require_extension(enabled: bool, interface: FixedBytes<4>) -> Result<(), Erc721Error> {
+        if !enabled {
+            return Err(Erc721Error::ExtensionDisabled(ExtensionDisabled {
+                interface_id: interface,
+            }));
+        }
+        Ok(())
+    }
+
+    // 设置 token 级授权并更新被授权地址的授权计数，超过上限时回滚
+    fn set_token_approval(&mut self, token_id: U256, approved: Address) -> Result<(), Erc721Error> {
+        let previous = self.token_approvals.get(token_id);
+        if previous != approved && !approved.is_zero() {
+            let count = self.token_approvals_held.get(approved);
+            if let Some(limit) = T::MAX_TOKEN_APPROVALS_HELD {
+                if count >= U256::from(limit) {
+                    return Err(Erc721Error::TooManyApprovals(TooManyApprovals {
+                        operator: approved,
+                        limit: U256::from(limit),
+                    }));
+                }
+            }
+            self.token_approvals_held
+                .insert(approved, count + U256::from(1));
+        }
+        if previous != approved && !previous.is_zero() {
+            self.decrement_approval_count(previous);
+        }
+        self.token_approvals.insert(token_id, approved);
+        self.approval_deadlines.delete(token_id);
+        Ok(())
+    }
+
+    // 清除 token 级授权并减少原被授权地址的授权计数
+    fn clear_token_approval(&mut self, token_id: U256) {
+        let previous = self.token_approvals.get(token_id);
+        if !previous.is_zero() {
+            self.decrement_approval_count(previous);
+        }
+        self.token_approvals.delete(token_id);
+        self.approval_deadlines.delete(token_id);
+    }
+
+    // 减少地址的授权计数，升级前设置的授权未计数，因此不低于零
+    fn decrement_approval_count(&mut self, operator: Address) {
+        let count = self.token_approvals_held.get(operator);
+        self.token_approvals_held
+            .insert(operator, count.saturating_sub(U256::from(1)));
+    }
+
+    // 不做溢出检查地将余额加一并返回新余额，余额不超过 token 总数，不可能溢出 U256，
+    // 不可用于零地址
+    fn unchecked_inc(&mut self, account: Address) -> U256 {
+        let mut slot = self.balances.setter(account);
+        let balance = slot.get().wrapping_add(U256::from(1));
+        slot.set(balance);
+        balance
+    }
+
+    // 不做下溢检查地将余额减一并返回新余额，调用前已验证 account 持有该 token，余额至少为 1，
+    // 不可用于零地址
+    fn unchecked_dec(&mut self, account: Address) -> U256 {
+        let mut slot = self.balances.setter(account);
+        let balance = slot.get().wrapping_sub(U256::from(1));
+        slot.set(balance);
+        balance
+    }
+
+    // 将 token 加入 to 的枚举列表末尾，index 为 to 当前的余额
+    fn add_token_to_owner_enumeration(&mut self, to: Address, token_id: U256, index: U256) {
+        self.owned_tokens.setter(to).insert(index, token_id);
+        self.owned_tokens_index.insert(token_id, index);
+    }
+
+    // 将 token 从 from 的枚举列表中移除，last_index 为 from 扣减后的余额
+    fn remove_token_from_owner_enumeration(
+        &mut self,
+        from: Address,
+        token_id: U256,
+        last_index: U256,
+    ) {
+        let token_index = self.owned_tokens_index.get(token_id);
+        let mut owned = self.owned_tokens.setter(from);
+        // 用最后一个 token 填补被移除的位置
+        if token_index != last_index {
+            let last_token_id = owned.get(last_index);
+            owned.insert(token_index, last_token_id);
+            self.owned_tokens_index.insert(last_token_id, token_index);
+        }
+        owned.delete(last_index);
+        self.owned_tokens_index.delete(token_id);
+    }
+
+    // 将新铸造的 token 加入全局枚举列表
+    fn add_token_to_all_tokens_enumeration(&mut self, token_id: U256) {
+        self.all_tokens_index
+            .insert(token_id, U256::from(self.all_tokens.len()));
+        self.all_tokens.push(token_id);
+    }
+
+    // 将被销毁的 token 从全局枚举列表中移除
+    fn remove_token_from_all_tokens_enumeration(&mut self, token_id: U256) {
+        let token_index = self.all_tokens_index.get(token_id);
+        let last_index = U256::from(self.all_tokens.len() - 1);
+        // 用最后一个 token 填补被移除的位置
+        if token_index != last_index {
+            let last_token_id = self.all_tokens.get(last_index).unwrap_or_default();
+            if let Some(mut slot) = self.all_tokens.setter(token_index) {
+                slot.set(last_token_id);
+            }
+            self.all_tokens_index.insert(last_token_id, token_index);
+        }
+        self.all_tokens.pop();
+        self.all_tokens_index.delete(token_id);
+    }
+
+    // 如果接收者是合约，调用 onERC721Received 方法
+    fn call_receiver<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        token_id: U256,
+        from: Address,
+        to: Address,
+        data: Vec<u8>,
+    ) -> Result<(), Erc721Error> {
+        let erc721 = storage.borrow_mut();
+        // 接收者不是合约时无需回调
+        if erc721.vm().code_size(to) == 0 {
+            return Ok(());
+        }
+        // 设置了 gas 上限时限制转发给接收者的 gas
+        let gas_limit = erc721.receiver_gas_limit.get().to::<u64>();
+        let gas = if gas_limit == 0 { u64::MAX } else { gas_limit };
+        let refused = |returned: [u8; 4]| {
+            Erc721Error::ReceiverRefused(ReceiverRefused {
+                receiver: to,
+                token_id,
+                returned: FixedBytes(returned),
+            })
+        };
+        // 调用 onERC721Received 方法
+        let call = IERC721TokenReceiver::onERC721ReceivedCall {
+            operator: erc721.vm().msg_sender(),
+            from,
+            token_id,
+            data: data.into(),
+        };
+        let returned = match erc721
+            .vm()
+            .call(&Call::new().gas(gas), to, &call.abi_encode())
+        {
+            Ok(returned) => returned,
+            // 限制 gas 时，耗尽 gas 的接收者不返回回滚数据，视为拒绝接收
+            Err(CallError::Revert(reason)) if gas_limit != 0 && reason.is_empty() => {
+                return Err(refused([0; 4]));
+            }
+            // 调用回滚时透传接收者的回滚数据
+            Err(CallError::Revert(reason)) => {
+                return Err(Erc721Error::ReceiverReverted(ReceiverReverted {
+                    receiver: to,
+                    token_id,
+                    reason: reason.into(),
+                }));
+            }
+            Err(CallError::AbiDecodingFailed(_)) => return Err(refused([0; 4])),
+        };
+        // 返回数据无法解码时视为返回了错误的选择器
+        let received =
+            IERC721TokenReceiver::onERC721ReceivedCall::abi_decode_returns(&returned, true)
+                .map_err(|_| refused([0; 4]))?
+                ._0
+                .0;
+        // 验证返回的选择器是否正确
+        if u32::from_be_bytes(received) != ERC721_TOKEN_RECEIVER_ID {
+            return Err(refused(received));
+        }
+        Ok(())
+    }
+
+    // 执行安全转账并调用 onERC721Received
+    pub fn safe_transfer<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        token_id: U256,
+        from: Address,
+        to: Address,
+        data: Vec<u8>,
+    ) -> Result<(), Erc721Error> {
+        // 执行转账
+        storage.borrow_mut().transfer(token_id, from, to)?;
+        // 关闭接收者检查时不回调接收合约
+        if !T::ENFORCE_RECEIVER {
+            return Ok(());
+        }
+        // 调用接收者检查
+        Self::call_receiver(storage, token_id, from, to, data)
+    }
+
+    // 批量执行安全转账，每转出一个 token 即对其单独调用一次 onERC721Received，
+    // 任一 token 无权限、转账失败或被接收者拒绝时整批回滚
+    pub fn safe_transfer_from_batch<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        from: Address,
+        to: Address,
+        token_ids: Vec<U256>,
+        data: Vec<u8>,
+    ) -> Result<(), Erc721Error> {
+        Self::check_batch_len(token_ids.len())?;
+        for token_id in token_ids {
+            // 禁止转账到零地址
+            if to.is_zero() {
+                return Err(Erc721Error::TransferToZero(TransferToZero { token_id }));
+            }
+            // 检查调用者是否有权限，并扣减操作者的转账额度
+            let erc721 = storage.borrow_mut();
+            erc721.require_authorized_to_spend(from, token_id)?;
+            erc721.consume_operator_budget(from, token_id)?;
+            Self::safe_transfer(storage, token_id, from, to, data.clone())?;
+        }
+        Ok(())
+    }
+
+    // 增加现存供应量，溢出时回滚
+    fn increase_supply(&mut self, amount: U256) -> Result<(), Erc721Error> {
+        let supply = self
+            .total_supply
+            .get()
+            .checked_add(amount)
+            .ok_or(Erc721Error::SupplyOverflow(SupplyOverflow {}))?;
+        self.total_supply.set(supply);
+        Ok(())
+    }
+
+    // 将现存供应量减一，调用前已确认 token 存在，下溢不应发生，发生时回滚而不是 panic
+    fn decrease_supply(&mut self) -> Result<(), Erc721Error> {
+        let supply = self
+            .total_supply
+            .get()
+            .checked_sub(U256::from(1u8))
+            .ok_or(Erc721Error::SupplyUnderflow(SupplyUnderflow {}))?;
+        self.total_supply.set(supply);
+        Ok(())
+    }
+
+    // 铸造新 token 并转账给 to，返回新 token_id
+    pub fn mint(&mut self, to: Address) -> Result<U256, Erc721Error> {
+        // 获取下一个 token_id，销毁不会回收已用过的 id
+        let new_token_id = self.upcoming_token_id();
+        let next_token_id = new_token_id
+            .checked_add(U256::from(1u8))
+            .ok_or(Erc721Error::SupplyOverflow(SupplyOverflow {}))?;
+        self.next_token_id.set(next_token_id);
+        self.mint_id(to, new_token_id)?;
+        Ok(new_token_id)
+    }
+
+    // 按剩余稀有数量与剩余供应量之比抽取当前 token 是否为稀有 token，
+    // 剩余供应量等于剩余稀有数量时必中，因此无论按序还是按指定 id 铸造，
+    // 铸造完毕时都恰好抽出 GOLDEN_COUNT 个
+    // 种子来自区块号、时间戳和接收者，出块者和铸造者可以预测或操纵结果，不适合高价值场景
+    fn draw_golden(&mut self, to: Address, token_id: U256) {
+        let Some(max_supply) = T::MAX_SUPPLY else {
+            return;
+        };
+        // 包含当前 token 在内的剩余供应量
+        let drawn = self.golden_drawn.get();
+        let supply_left = U256::from(max_supply).saturating_sub(drawn);
+        self.golden_drawn.set(drawn + U256::from(1));
+        let golden_left = U256::from(T::GOLDEN_COUNT).saturating_sub(self.golden_assigned.get());
+        if golden_left.is_zero() || supply_left.is_zero() {
+            return;
+        }
+        let seed = keccak(
+            (
+                U256::from(self.vm().block_number()),
+                U256::from(self.vm().block_timestamp()),
+                to,
+                token_id,
+            )
+                .abi_encode(),
+        );
+        if U256::from_be_bytes(seed.0) % supply_left >= golden_left {
+            return;
+        }
+        self.golden.insert(token_id, true);
+        self.golden_assigned
+            .set(self.golden_assigned.get() + U256::from(1));
+        log(self.vm(), GoldenMinted { token_id });
+    }
+
+    // 铸造指定 token_id 给 to
+    pub fn mint_id(&mut self, to: Address, token_id: U256) -> Result<(), Erc721Error> {
+        // 已存在或已销毁的 token 都不可再次铸造
+        if self.is_minted(token_id) {
+            return Err(Erc721Error::AlreadyMinted(AlreadyMinted { token_id }));
+        }
+        // 检查是否超过最大供应量
+        if let Some(max_supply) = T::MAX_SUPPLY {
+            if token_id >= U256::from(max_supply) {
+                return Err(Erc721Error::MaxSupplyReached(MaxSupplyReached {
+                    max_supply: U256::from(max_supply),
+                }));
+            }
+        }
+        // 增加现存供应量
+        self.increase_supply(U256::from(1u8))?;
+        // 执行转账，从零地址到接收者
+        self.transfer_verified(token_id, Address::default(), to)?;
+        // 所有铸造路径都经过这里，保证每个 token 都参与稀有抽取
+        self.draw_golden(to, token_id);
+        Ok(())
+    }
+
+    // 连续铸造 quantity 个 token 给 to，只写入首个 token 的拥有者槽，返回首个 token_id
+    pub fn mint_consecutive(&mut self, to: Address, quantity: U256) -> Result<U256, Erc721Error> {
+        self.require_not_paused(PAUSE_MINT)?;
+        self.require_kyc(to)?;
+        Self::check_batch_len(quantity.saturating_to())?;
+        // 区间长度记录在 uint16 中
+        let run_len: u16 = quantity.try_into().map_err(|_| {
+            Erc721Error::BatchTooLarge(BatchTooLarge {
+                len: quantity,
+                max: U256::from(u16::MAX),
+            })
+        })?;
+        let first = self.next_token_id.get();
+        let end = first
+            .checked_add(quantity)
+            .ok_or(Erc721Error::SupplyOverflow(SupplyOverflow {}))?;
+        // 检查是否超过最大供应量
+        if let Some(max_supply) = T::MAX_SUPPLY {
+            if end > U256::from(max_supply) {
+                return Err(Erc721Error::MaxSupplyReached(MaxSupplyReached {
+                    max_supply: U256::from(max_supply),
+                }));
+            }
+        }
+        // 区间内不能有已按指定 id 铸造的 token
+        let mut token_id = first;
+        while token_id < end {
+            if self.is_minted(token_id) {
+                return Err(Erc721Error::AlreadyMinted(AlreadyMinted { token_id }));
+            }
+            token_id += U256::from(1);
+        }
+        if quantity.is_zero() {
+            return Ok(first);
+        }
+        // 只写入首个 token 的拥有者和区间长度，其余 token 通过向前查找确定
+        let now = U64::from(self.vm().block_timestamp());
+        let mut ownership = self.owners.setter(first);
+        ownership.owner.set(to);
+        ownership.last_transfer.set(now);
+        ownership.run_len.set(U16::from(run_len));
+        // 余额变化前记录快照
+        self.update_balance_snapshot(to);
+        // 逐个加入枚举列表并记录转账事件
+        let mut balance = self.balances.get(to);
+        let mut token_id = first;
+        while token_id < end {
+            self.add_token_to_all_tokens_enumeration(token_id);
+            self.add_token_to_owner_enumeration(to, token_id, balance);
+            self.minted_at
+                .insert(token_id, U256::from(self.vm().block_timestamp()));
+            let mut info = self.last_transfer_info.setter(token_id);
+            info.from.set(Address::ZERO);
+            info.to.set(to);
+            info.timestamp.set(now);
+            log(
+                self.vm(),
+                Transfer {
+                    from: Address::ZERO,
+                    to,
+                    token_id,
+                },
+            );
+            if T::SOULBOUND {
+                log(self.vm(), Locked { token_id });
+            }
+            balance += U256::from(1);
+            token_id += U256::from(1);
+        }
+        // 一次性更新余额、供应量和投票权，to 首次持有时计入持有者数量
+        if self.balances.get(to).is_zero() {
+            self.holder_count
+                .set(self.holder_count.get() + U256::from(1));
+        }
+        self.balances.insert(to, balance);
+        self.increase_supply(quantity)?;
+        self.next_token_id.set(end);
+        self.record_mints(to, quantity);
+        // 与逐个铸造相同，按序为每个 token 抽取稀有标记
+        let mut token_id = first;
+        while token_id < end {
+            self.draw_golden(to, token_id);
+            token_id += U256::from(1);
+        }
+        let to_delegate = self.delegates.get(to);
+        self.move_voting_power(Address::ZERO, to_delegate, quantity);
+        Ok(first)
+    }
+
+    // 检查 URI 长度是否超过上限
+    fn check_uri_len(uri: &str) -> Result<(), Erc721Error> {
+        if let Some(max) = T::MAX_URI_LEN {
+            if uri.len() > max {
+                return Err(Erc721Error::UriTooLong(UriTooLong {
+                    len: U256::from(uri.len()),
+                    max: U256::from(max),
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    // 设置基础 URI，超过长度上限时回滚
+    pub fn set_base_uri(&mut self, uri: String) -> Result<(), Erc721Error> {
+        Self::check_uri_len(&uri)?;
+        self.base_uri.set_str(uri);
+        Ok(())
+    }
+
+    // 根据基础 URI 生成 token 的默认 URI
+    fn default_uri(&self, token_id: U256) -> String {
+        let base_uri = self.base_uri.get_string();
+        if base_uri.is_empty() {
+            return self.with_version(T::token_uri(token_id));
+        }
+        self.with_version(format!("{}{}{}", base_uri, token_id, self.suffix()))
+    }
+
+    // 获取基础 URI 后追加的后缀，未设置时为 .json
+    fn suffix(&self) -> String {
+        if !self.uri_suffix_set.get() {
+            return ".json".into();
+        }
+        self.uri_suffix.get_string()
+    }
+
+    // 设置已销毁 token 返回的墓碑 URI，为空表示关闭，超过长度上限时回滚
+    pub fn set_tombstone_uri(&mut self, uri: String) -> Result<(), Erc721Error> {
+        Self::check_uri_len(&uri)?;
+        self.tombstone_uri.set_str(uri);
+        Ok(())
+    }
+
+    // 设置基础 URI 后追加的后缀，允许为空，并通知索引器刷新全部元数据
+    pub fn set_uri_suffix(&mut self, suffix: String) {
+        self.uri_suffix.set_str(suffix);
+        self.uri_suffix_set.set(true);
+        log(
+            self.vm(),
+            BatchMetadataUpdate {
+                from_token_id: U256::ZERO,
+                to_token_id: U256::MAX,
+            },
+        );
+    }
+
+    // 元数据版本号非零时在 URI 末尾追加 ?v=<版本号>
+    fn with_version(&self, uri: String) -> String {
+        let version = self.metadata_version.get();
+        if version.is_zero() {
+            return uri;
+        }
+        format!("{}?v={}", uri, version)
+    }
+
+    // 递增元数据版本号，并通知索引器刷新全部元数据，返回新版本号
+    pub fn bump_metadata_version(&mut self) -> U256 {
+        let version = self.metadata_version.get() + U256::from(1);
+        self.metadata_version.set(version);
+        log(
+            self.vm(),
+            BatchMetadataUpdate {
+                from_token_id: U256::ZERO,
+                to_token_id: U256::MAX,
+            },
+        );
+        version
+    }
+
+    // 检查所有持有者余额之和是否等于现存供应量，按全局枚举列表统计持有者，开销较大
+    pub fn balances_consistent(&self) -> bool {
+        let supply = self.total_supply.get();
+        if U256::from(self.all_tokens.len()) != supply {
+            return false;
+        }
+        // 收集全部持有者并去重
+        let mut holders: Vec<Address> = (0..self.all_tokens.len())
+            .filter_map(|i| self.all_tokens.get(i))
+            .map(|token_id| self.owner_raw(token_id))
+            .collect();
+        holders.sort();
+        holders.dedup();
+        let total = holders
+            .into_iter()
+            .fold(U256::ZERO, |sum, holder| sum + self.balances.get(holder));
+        total == supply
+    }
+
+    // 批量设置 token 的专属 URI，记录覆盖全部 token 的批量元数据更新事件
+    pub fn set_token_uri_batch(
+        &mut self,
+        token_ids: Vec<U256>,
+        uris: Vec<String>,
+    ) -> Result<(), Erc721Error> {
+        if token_ids.len() != uris.len() {
+            return Err(Erc721Error::LengthMismatch(LengthMismatch {
+                left: U256::from(token_ids.len()),
+                right: U256::from(uris.len()),
+            }));
+        }
+        Self::check_batch_len(token_ids.len())?;
+        let mut min_id = U256::MAX;
+        let mut max_id = U256::ZERO;
+        for (token_id, uri) in token_ids.into_iter().zip(uris) {
+            // 确保 token 存在且元数据未冻结
+            self.owner_of(token_id)?;
+            self.require_not_frozen(token_id)?;
+            Self::check_uri_len(&uri)?;
+            self.token_uris.setter(token_id).set_str(uri);
+            min_id = min_id.min(token_id);
+            max_id = max_id.max(token_id);
+        }
+        if min_id <= max_id {
+            log(
+                self.vm(),
+                BatchMetadataUpdate {
+                    from_token_id: min_id,
+                    to_token_id: max_id,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    // 设置 token 的专属版税
+    pub fn set_token_royalty(&mut self, token_id: U256, bps: u64) {
+        let mut royalty = self.token_royalties.setter(token_id);
+        royalty.set.set(true);
+        royalty.bps.set(Uint::from(bps));
+        log(
+            self.vm(),
+            RoyaltySet {
+                token_id,
+                bps: U256::from(bps),
+            },
+        );
+    }
+
+    // 重置 token 的专属版税，未设置时不记录事件
+    pub fn reset_token_royalty(&mut self, token_id: U256) {
+        if !self.token_royalties.getter(token_id).set.get() {
+            return;
+        }
+        self.token_royalties.delete(token_id);
+        log(self.vm(), RoyaltyReset { token_id });
+    }
+
+    // 获取 token 的专属版税，未设置时返回 None
+    pub fn token_royalty(&self, token_id: U256) -> Option<U256> {
+        let royalty = self.token_royalties.getter(token_id);
+        if !royalty.set.get() {
+            return None;
+        }
+        Some(U256::from(royalty.bps.get()))
+    }
+
+    // 揭示指定 token 区间，使用独立的基础 URI
+    pub fn reveal_range(
+        &mut self,
+        start: U256,
+        end: U256,
+        base_uri: String,
+    ) -> Result<(), Erc721Error> {
+        if end < start {
+            return Err(Erc721Error::InvalidRange(InvalidRange { start, end }));
+        }
+        Self::check_uri_len(&base_uri)?;
+        let mut segment = self.reveal_segments.grow();
+        segment.start.set(start);
+        segment.end.set(end);
+        segment.base_uri.set_str(base_uri);
+        log(
+            self.vm(),
+            BatchMetadataUpdate {
+                from_token_id: start,
+                to_token_id: end,
+            },
+        );
+        Ok(())
+    }
+
+    // 查找包含 token 的揭示区间的基础 URI，多个区间重叠时取最先添加的
+    fn segment_base_uri(&self, token_id: U256) -> Option<String> {
+        for i in 0..self.reveal_segments.len() {
+            let segment = self.reveal_segments.getter(i)?;
+            if segment.start.get() <= token_id && token_id <= segment.end.get() {
+                return Some(segment.base_uri.get_string());
+            }
+        }
+        None
+    }
+
+    // 生成链上 JSON 元数据的 data URI
+    fn onchain_uri(&self, token_id: U256) -> String {
+        // 图像为 render_svg 生成的 SVG data URI
+        let image = base64::data_uri("image/svg+xml", T::render_svg(token_id).as_bytes());
+        // 属性按设置顺序写入 attributes 数组
+        let attributes = self.attributes.getter(token_id);
+        let traits: Vec<String> = (0..attributes.len())
+            .filter_map(|i| attributes.getter(i))
+            .map(|attribute| {
+                format!(
+                    "{{\"trait_type\":\"{}\",\"value\":\"{}\"}}",
+                    json_escape(&attribute.key.get_string()),
+                    json_escape(&attribute.value.get_string())
+                )
+            })
+            .collect();
+        let json = format!(
+            "{{\"name\":\"{} #{}\",\"token_id\":\"{}\",\"image\":\"{}\",\"attributes\":[{}]}}",
+            T::NAME,
+            token_id,
+            token_id,
+            image,
+            traits.join(",")
+        );
+        base64::data_uri("application/json", json.as_bytes())
+    }
+
+    // 设置 token 的属性，已存在同名属性时覆盖其值，元数据已冻结时回滚
+    pub fn set_attribute(
+        &mut self,
+        token_id: U256,
+        key: String,
+        value: String,
+    ) -> Result<(), Erc721Error> {
+        // 确保 token 存在且元数据未冻结
+        self.owner_of(token_id)?;
+        self.require_not_frozen(token_id)?;
+        self.write_attribute(token_id, key, value);
+        log(self.vm(), MetadataUpdate { token_id });
+        Ok(())
+    }
+
+    // 写入 token 的属性，已存在同名属性时覆盖其值
+    fn write_attribute(&mut self, token_id: U256, key: String, value: String) {
+        let mut attributes = self.attributes.setter(token_id);
+        let existing = (0..attributes.len()).find(|&i| {
+            attributes
+                .getter(i)
+                .is_some_and(|attribute| attribute.key.get_string() == key)
+        });
+        match existing {
+            Some(index) => {
+                if let Some(mut attribute) = attributes.setter(index) {
+                    attribute.value.set_str(value);
+                }
+            }
+            None => {
+                let mut attribute = attributes.grow();
+                attribute.key.set_str(key);
+                attribute.value.set_str(value);
+            }
+        }
+    }
+
+    // 设置 URI 模式，并通知索引器刷新全部元数据
+    pub fn set_uri_mode(&mut self, mode: u8) -> Result<(), Erc721Error> {
+        if mode > URI_MODE_ONCHAIN {
+            return Err(Erc721Error::InvalidUriMode(InvalidUriMode { mode }));
+        }
+        self.uri_mode.set(U8::from(mode));
+        log(
+            self.vm(),
+            BatchMetadataUpdate {
+                from_token_id: U256::ZERO,
+                to_token_id: U256::MAX,
+            },
+        );
+        Ok(())
+    }
+
+    // 设置 token 的专属 URI，为空时回退到默认 URI，元数据已冻结或超过长度上限时回滚
+    pub fn set_token_uri(&mut self, token_id: U256, uri: String) -> Result<(), Erc721Error> {
+        // 确保 token 存在且元数据未冻结
+        self.owner_of(token_id)?;
+        self.require_not_frozen(token_id)?;
+        Self::check_uri_len(&uri)?;
+        self.token_uris.setter(token_id).set_str(uri);
+        log(self.vm(), MetadataUpdate { token_id });
+        Ok(())
+    }
+
+    // 设置 token 的二进制 IPFS CID，为空时清除，元数据已冻结时回滚
+    pub fn set_token_cid(&mut self, token_id: U256, cid: Vec<u8>) -> Result<(), Erc721Error> {
+        // 确保 token 存在且元数据未冻结
+        self.owner_of(token_id)?;
+        self.require_not_frozen(token_id)?;
+        self.token_cids.setter(token_id).set_bytes(cid);
+        log(self.vm(), MetadataUpdate { token_id });
+        Ok(())
+    }
+
+    // 检查 token 的元数据是否未冻结
+    fn require_not_frozen(&self, token_id: U256) -> Result<(), Erc721Error> {
+        if self.token_frozen.get(token_id) {
+            return Err(Erc721Error::TokenFrozen(TokenFrozen { token_id }));
+        }
+        Ok(())
+    }
+
+    // 永久冻结 token 的元数据，不影响其他 token，已冻结时不重复记录事件
+    pub fn freeze_token(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        // 确保 token 存在
+        self.owner_of(token_id)?;
+        if self.token_frozen.get(token_id) {
+            return Ok(());
+        }
+        self.token_frozen.insert(token_id, true);
+        log(self.vm(), TokenMetadataFrozen { token_id });
+        Ok(())
+    }
+
+    // 揭示元数据，用随机种子确定一次性的偏移量
+    // 种子来自链上数据，出块者或调用时机可能对其产生影响，不适合高价值场景
+    pub fn reveal(&mut self, seed: U256) -> Result<(), Erc721Error> {
+        if self.revealed.get() {
+            return Err(Erc721Error::AlreadyRevealed(AlreadyRevealed {}));
+        }
+        // 有最大供应量时对全部 id 做偏移，否则只偏移揭示时已铸造的 id
+        let supply = match T::MAX_SUPPLY {
+            Some(max_supply) => U256::from(max_supply),
+            None => self.next_token_id.get(),
+        };
+        let shuffle_offset = if supply.is_zero() {
+            U256::ZERO
+        } else {
+            seed % supply
+        };
+        self.revealed.set(true);
+        self.shuffle_offset.set(shuffle_offset);
+        self.shuffle_supply.set(supply);
+        log(self.vm(), Revealed { shuffle_offset });
+        Ok(())
+    }
+
+    // 计算 token_id 揭示后对应的元数据 id
+    fn metadata_id(&self, token_id: U256) -> U256 {
+        let supply = self.shuffle_supply.get();
+        // 揭示后新铸造的 id 不参与偏移
+        if token_id >= supply {
+            return token_id;
+        }
+        (token_id + self.shuffle_offset.get()) % supply
+    }
+
+    // 设置 token 的可使用次数，调用方需已验证调用者为发行方
+    pub fn set_uses(&mut self, token_id: U256, count: U256) -> Result<(), Erc721Error> {
+        self.owner_of(token_id)?;
+        self.uses_remaining.insert(token_id, count);
+        Ok(())
+    }
+
+    // 设置 token 的有效期，要求结束时间不早于开始时间
+    pub fn set_time(
+        &mut self,
+        token_id: U256,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<(), Erc721Error> {
+        if end_time < start_time {
+            return Err(Erc721Error::InvalidTimeRange(InvalidTimeRange {
+                start_time,
+                end_time,
+            }));
+        }
+        let mut time_info = self.time_info.setter(token_id);
+        time_info.start_time.set(I64::unchecked_from(start_time));
+        time_info.end_time.set(I64::unchecked_from(end_time));
+        Ok(())
+    }
+
+    // 销毁指定 token
+    pub fn burn(&mut self, from: Address, token_id: U256) -> Result<(), Erc721Error> {
+        // 不可销毁的集合拒绝一切销毁
+        if !T::BURNABLE {
+            return Err(Erc721Error::BurnDisabled(BurnDisabled {}));
+        }
+        // 未铸造或已销毁的 token 在修改任何计数前回滚，from 为零地址时也不会被当作铸造处理
+        self.owner_of(token_id)?;
+        // 执行转账到零地址，同时清除授权并移出枚举列表
+        self.transfer(token_id, from, Address::default())?;
+        // 减少现存供应量
+        self.decrease_supply()?;
+        // 清除专属版税以回收存储
+        self.reset_token_royalty(token_id);
+        // 记录销毁事件，便于索引器识别
+        log(
+            self.vm(),
+            Burn {
+                owner: from,
+                token_id,
+            },
+        );
+        Ok(())
+    }
+
+    // 验证拥有者的 EIP-712 销毁签名并消耗其 nonce，销毁由调用方完成
+    pub fn consume_burn_sig(
+        &mut self,
+        owner: Address,
+        token_id: U256,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        // 验证 owner 是否为拥有者
+        let real_owner = self.owner_of(token_id)?;
+        if owner != real_owner {
+            return Err(Erc721Error::NotOwner(NotOwner {
+                from: owner,
+                token_id,
+                real_owner,
+            }));
+        }
+        // 按拥有者当前 nonce 计算签名摘要，只接受拥有者本人的签名
+        let nonce = self.burn_nonces.get(owner);
+        let struct_hash =
+            keccak((keccak(BURN_TYPE), owner, token_id, nonce, deadline).abi_encode());
+        let signer = self.recover_signer(struct_hash, deadline, v, r, s)?;
+        if signer != owner {
+            return Err(Erc721Error::InvalidSignature(InvalidSignature {}));
+        }
+        // 消耗 nonce，防止签名重放
+        self.burn_nonces.insert(owner, nonce + U256::from(1));
+        Ok(())
+    }
+
+    // 强制销毁 token，解除质押和锁定后销毁，返回原拥有者
+    pub fn force_burn(&mut self, token_id: U256) -> Result<Address, Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+        self.staked.delete(token_id);
+        self.staked_at.delete(token_id);
+        self.unlockers.delete(token_id);
+        self.burn(owner, token_id)?;
+        Ok(owner)
+    }
+}
+
+// 实现 ERC-721 外部方法
+#[public]
+impl<T: Erc721Params> Erc721<T> {
+    // 获取 NFT 名称
+    pub fn name() -> Result<String, Erc721Error> {
+        Ok(T::NAME.into())
+    }
+
+    // 获取 NFT 符号
+    pub fn symbol() -> Result<String, Erc721Error> {
+        Ok(T::SYMBOL.into())
+    }
+
+    // 获取指定 token 的 URI
+    #[selector(name = "tokenURI")]
+    pub fn token_uri(&self, token_id: U256) -> Result<String, Erc721Error> {
+        // 已销毁的 token 在设置了墓碑 URI 时返回墓碑，从未铸造的 token 仍回滚
+        if self.owners.getter(token_id).burned.get() {
+            let tombstone = self.tombstone_uri.get_string();
+            if !tombstone.is_empty() {
+                return Ok(tombstone);
+            }
+        }
+        // 确保 token 存在
+        self.owner_of(token_id)?;
+        // 按 URI 模式生成，自动模式继续向下处理
+        match self.uri_mode.get().to::<u8>() {
+            URI_MODE_BASE => {
+                return Ok(self.with_version(format!(
+                    "{}{}{}",
+                    self.base_uri.get_string(),
+                    token_id,
+                    self.suffix()
+                )))
+            }
+            URI_MODE_TOKEN => return Ok(self.token_uris.getter(token_id).get_string()),
+            URI_MODE_ONCHAIN => return Ok(self.onchain_uri(token_id)),
+            _ => {}
+        }
+        // 优先返回 token 的专属 URI
+        let uri = self.token_uris.getter(token_id).get_string();
+        if !uri.is_empty() {
+            return Ok(uri);
+        }
+        // 其次使用基础 URI 加 token 的 IPFS CID
+        let cid = self.token_cids.getter(token_id).get_bytes();
+        if !cid.is_empty() {
+            return Ok(self.with_version(format!(
+                "{}{}{}",
+                self.base_uri.get_string(),
+                base58::encode(&cid),
+                self.suffix()
+            )));
+        }
+        // 已分段揭示的 token 使用所在区间的基础 URI
+        if let Some(base_uri) = self.segment_base_uri(token_id) {
+            return Ok(self.with_version(format!("{}{}{}", base_uri, token_id, self.suffix())));
+        }
+        // 揭示前返回占位 URI
+        if !self.revealed.get() {
+            if let Some(placeholder) = T::PLACEHOLDER_URI {
+                return Ok(placeholder.into());
+            }
+            return Ok(self.default_uri(token_id));
+        }
+        Ok(self.default_uri(self.metadata_id(token_id)))
+    }
+
+    // 批量获取 token 的 URI，未铸造或已销毁的 token 返回空字符串
+    pub fn token_uri_batch(&self, token_ids: Vec<U256>) -> Result<Vec<String>, Erc721Error> {
+        Self::check_batch_len(token_ids.len())?;
+        Ok(token_ids
+            .into_iter()
+            .map(|token_id| self.token_uri(token_id).unwrap_or_default())
+            .collect())
+    }
+
+    // 获取指定地址的 NFT 余额
+    pub fn balance_of(&self, owner: Address) -> Result<U256, Erc721Error> {
+        Ok(self.balances.get(owner))
+    }
+
+    // 批量获取多个地址的 NFT 余额
+    pub fn balance_of_batch(&self, owners: Vec<Address>) -> Result<Vec<U256>, Erc721Error> {
+        Self::check_batch_len(owners.len())?;
+        Ok(owners
+            .into_iter()
+            .map(|owner| self.balances.get(owner))
+            .collect())
+    }
+
+    // 获取指定 token 的拥有者
+    pub fn owner_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        // 获取 token 的拥有者
+        let owner = self.owner_raw(token_id);
+        // 如果拥有者是零地址，token 无效
+        if owner.is_zero() {
+            return Err(Erc721Error::InvalidTokenId(InvalidTokenId { token_id }));
+        }
+        Ok(owner)
+    }
+
+    // 检查 account 是否为 token 的拥有者，token 不存在时回滚
+    pub fn is_owner_of(&self, account: Address, token_id: U256) -> Result<bool, Erc721Error> {
+        Ok(self.owner_of(token_id)? == account)
+    }
+
+    // 批量检查 accounts[i] 是否为 token_ids[i] 的拥有者，任一 token 不存在时回滚
+    pub fn are_owners_of(
+        &self,
+        accounts: Vec<Address>,
+        token_ids: Vec<U256>,
+    ) -> Result<Vec<bool>, Erc721Error> {
+        if accounts.len() != token_ids.len() {
+            return Err(Erc721Error::LengthMismatch(LengthMismatch {
+                left: U256::from(accounts.len()),
+                right: U256::from(token_ids.len()),
+            }));
+        }
+        Self::check_batch_len(token_ids.len())?;
+        accounts
+            .into_iter()
+            .zip(token_ids)
+            .map(|(account, token_id)| self.is_owner_of(account, token_id))
+            .collect()
+    }
+
+    // 获取指定 token 的拥有者，未铸造或已销毁时返回零地址而不回滚
+    pub fn owner_of_or_zero(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        Ok(self.owner_raw(token_id))
+    }
+
+    // 获取最大供应量，不限量时返回 uint256 最大值
+    pub fn max_supply(&self) -> Result<U256, Erc721Error> {
+        Ok(T::MAX_SUPPLY.map_or(U256::MAX, U256::from))
+    }
+
+    // 获取累计已铸造数量，包含已销毁的 token
+    pub fn minted(&self) -> Result<U256, Erc721Error> {
+        Ok(self.next_token_id.get())
+    }
+
+    // 获取下一次铸造将分配的 token_id
+    pub fn next_token_id(&self) -> Result<U256, Erc721Error> {
+        Ok(self.upcoming_token_id())
+    }
+
+    // 获取剩余可铸造数量
+    pub fn remaining(&self) -> Result<U256, Erc721Error> {
+        Ok(self.max_supply()?.saturating_sub(self.next_token_id.get()))
+    }
+
+    // 检查 token 是否存在（已铸造且未销毁）
+    pub fn exists(&self, token_id: U256) -> Result<bool, Erc721Error> {
+        Ok(!self.owner_raw(token_id).is_zero())
+    }
+
+    // 获取 token 的有效期开始时间
+    pub fn start_time(&self, token_id: U256) -> Result<i64, Erc721Error> {
+        Self::require_extension(T::TIMED, IERC5007)?;
+        self.owner_of(token_id)?;
+        Ok(self.time_info.getter(token_id).start_time.get().as_i64())
+    }
+
+    // 获取 token 的有效期结束时间
+    pub fn end_time(&self, token_id: U256) -> Result<i64, Erc721Error> {
+        Self::require_extension(T::TIMED, IERC5007)?;
+        self.owner_of(token_id)?;
+        Ok(self.time_info.getter(token_id).end_time.get().as_i64())
+    }
+
+    // 质押 token，仅拥有者可调用，质押期间不可转移
+    pub fn stake(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+        if self.vm().msg_sender() != owner {
+            return Err(Erc721Error::NotOwner(NotOwner {
+                from: self.vm().msg_sender(),
+                token_id,
+                real_owner: owner,
+            }));
+        }
+        if self.staked.get(token_id) {
+            return Err(Erc721Error::TokenStaked(TokenStaked { token_id }));
+        }
+        // 记录质押状态和开始时间
+        let timestamp = U256::from(self.vm().block_timestamp());
+        self.staked.insert(token_id, true);
+        self.staked_at.insert(token_id, timestamp);
+        log(
+            self.vm(),
+            Staked {
+                owner,
+                token_id,
+                timestamp,
+            },
+        );
+        Ok(())
+    }
+
+    // 解除质押，仅拥有者可调用
+    pub fn unstake(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+        if self.vm().msg_sender() != owner {
+            return Err(Erc721Error::NotOwner(NotOwner {
+                from: self.vm().msg_sender(),
+                token_id,
+                real_owner: owner,
+            }));
+        }
+        if !self.staked.get(token_id) {
+            return Err(Erc721Error::TokenNotStaked(TokenNotStaked { token_id }));
+        }
+        // 清除质押状态
+        self.staked.delete(token_id);
+        self.staked_at.delete(token_id);
+        log(
+            self.vm(),
+            Unstaked {
+                owner,
+                token_id,
+                timestamp: U256::from(self.vm().block_timestamp()),
+            },
+        );
+        Ok(())
+    }
+
+    // 检查 token 是否处于质押状态
+    pub fn is_staked(&self, token_id: U256) -> Result<bool, Erc721Error> {
+        Ok(self.staked.get(token_id))
+    }
+
+    // 获取 token 开始质押的时间戳，未质押时为 0
+    pub fn staked_at(&self, token_id: U256) -> Result<U256, Erc721Error> {
+        Ok(self.staked_at.get(token_id))
+    }
+
+    // 获取 token 距离可再次转账的剩余秒数
+    pub fn cooldown_remaining(&self, token_id: U256) -> Result<U256, Erc721Error> {
+        // 确保 token 存在
+        self.owner_of(token_id)?;
+        Ok(self.cooldown_of(token_id))
+    }
+
+    // 锁定 token 并指定唯一可解锁的地址，仅拥有者或被授权者可调用
+    pub fn lock(&mut self, token_id: U256, unlocker: Address) -> Result<(), Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+        self.require_authorized_to_spend(owner, token_id)?;
+        if !self.unlockers.get(token_id).is_zero() {
+            return Err(Erc721Error::TokenLocked(TokenLocked { token_id }));
+        }
+        // 解锁者不能为零地址，否则 token 无法被识别为已锁定
+        if unlocker.is_zero() {
+            return Err(Erc721Error::NotUnlocker(NotUnlocker {
+                caller: unlocker,
+                token_id,
+            }));
+        }
+        self.unlockers.insert(token_id, unlocker);
+        log(self.vm(), Locked { token_id });
+        Ok(())
+    }
+
+    // 解锁 token，仅锁定时指定的解锁者可调用
+    pub fn unlock(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        self.owner_of(token_id)?;
+        let unlocker = self.unlockers.get(token_id);
+        if unlocker.is_zero() || self.vm().msg_sender() != unlocker {
+            return Err(Erc721Error::NotUnlocker(NotUnlocker {
+                caller: self.vm().msg_sender(),
+                token_id,
+            }));
+        }
+        self.unlockers.delete(token_id);
+        log(self.vm(), Unlocked { token_id });
+        Ok(())
+    }
+
+    // 检查 token 是否处于锁定状态
+    pub fn locked(&self, token_id: U256) -> Result<bool, Erc721Error> {
+        self.owner_of(token_id)?;
+        Ok(T::SOULBOUND || !self.unlockers.get(token_id).is_zero())
+    }
+
+    // 一次性获取 token 的拥有者、当前授权地址、URI 和锁定状态，token 不存在时回滚
+    pub fn token_state(
+        &self,
+        token_id: U256,
+    ) -> Result<(Address, Address, String, bool), Erc721Error> {
+        Ok((
+            self.owner_of(token_id)?,
+            self.approved_of(token_id),
+            self.token_uri(token_id)?,
+            self.locked(token_id)?,
+        ))
+    }
+
+    // 设置 token 的租用者及到期时间（ERC-4907），仅拥有者或被授权者可调用
+    pub fn set_user(
+        &mut self,
+        token_id: U256,
+        user: Address,
+        expires: u64,
+    ) -> Result<(), Erc721Error> {
+        Self::require_extension(T::RENTABLE, IERC4907)?;
+        let owner = self.owner_of(token_id)?;
+        self.require_authorized_to_spend(owner, token_id)?;
+        let mut info = self.users.setter(token_id);
+        info.user.set(user);
+        info.expires.set(U64::from(expires));
+        log(
+            self.vm(),
+            UpdateUser {
+                token_id,
+                user,
+                expires,
+            },
+        );
+        Ok(())
+    }
+
+    // 获取 token 当前的租用者，已过期或未设置时返回零地址，token 不存在时回滚
+    pub fn user_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        Self::require_extension(T::RENTABLE, IERC4907)?;
+        self.owner_of(token_id)?;
+        let info = self.users.getter(token_id);
+        if U256::from(info.expires.get()) < U256::from(self.vm().block_timestamp()) {
+            return Ok(Address::ZERO);
+        }
+        Ok(info.user.get())
+    }
+
+    // 获取 token 租用的到期时间，未设置时为 0
+    pub fn user_expires(&self, token_id: U256) -> Result<U256, Erc721Error> {
+        Self::require_extension(T::RENTABLE, IERC4907)?;
+        Ok(U256::from(self.users.getter(token_id).expires.get()))
+    }
+
+    // 获取 token 的解锁者，未锁定时返回零地址
+    pub fn unlocker_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        Ok(self.unlockers.get(token_id))
+    }
+
+    // 检查 spender 是否有权操作指定 token，token 不存在时回滚
+    pub fn is_approved_or_owner(
+        &self,
+        spender: Address,
+        token_id: U256,
+    ) -> Result<bool, Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+        Ok(self.is_authorized(owner, spender, token_id))
+    }
+
+    // 检查 token 是否设置了专属版税
+    pub fn has_token_royalty(&self, token_id: U256) -> Result<bool, Erc721Error> {
+        Ok(self.token_royalties.getter(token_id).set.get())
+    }
+
+    // 获取接收者 onERC721Received 需返回的选择器
+    pub fn erc721_receiver_selector() -> Result<FixedBytes<4>, Erc721Error> {
+        Ok(FixedBytes(ERC721_TOKEN_RECEIVER_ID.to_be_bytes()))
+    }
+
+    // 获取已暂停操作的位掩码
+    pub fn paused_flags(&self) -> Result<u8, Erc721Error> {
+        Ok(self.paused_flags.get().to::<u8>())
+    }
+
+    // 获取默认操作者
+    pub fn default_operator(&self) -> Result<Address, Erc721Error> {
+        Ok(self.default_operator.get())
+    }
+
+    // 获取 EIP-712 域分隔符
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> Result<FixedBytes<32>, Erc721Error> {
+        Ok(self.current_domain_separator())
+    }
+
+    // 获取全部 token 的累计转账次数，不含铸造和销毁
+    pub fn total_transfers(&self) -> Result<U256, Erc721Error> {
+        Ok(self.total_transfers.get())
+    }
+
+    // 获取 token 的累计转账次数，不含铸造和销毁
+    pub fn transfer_count(&self, token_id: U256) -> Result<U256, Erc721Error> {
+        Ok(self.transfer_count.get(token_id))
+    }
+
+    // 获取 token 的铸造时间戳，已销毁的 token 不可再次铸造，因此不会被重置
+    pub fn minted_at(&self, token_id: U256) -> Result<u64, Erc721Error> {
+        self.owner_of(token_id)?;
+        Ok(self.minted_at.get(token_id).to())
+    }
+
+    // 获取 token 最近一次转移的 (from, to, 时间戳)，批量连续铸造后尚未转移的 token 返回零值
+    pub fn last_transfer_of(&self, token_id: U256) -> Result<(Address, Address, u64), Erc721Error> {
+        let info = self.last_transfer_info.getter(token_id);
+        Ok((info.from.get(), info.to.get(), info.timestamp.get().to()))
+    }
+
+    // 获取当前持有者满足最短持有时间、可以转出 token 的时间
+    pub fn transferable_at(&self, token_id: U256) -> Result<U256, Erc721Error> {
+        // 确保 token 存在
+        self.owner_of(token_id)?;
+        Ok(self.hold_deadline(token_id))
+    }
+
+    // 获取 token 可转让的截止时间，不限制时返回 uint256 最大值
+    pub fn transferable_until(&self, token_id: U256) -> Result<U256, Erc721Error> {
+        // 确保 token 存在
+        self.owner_of(token_id)?;
+        Ok(self.transferable_deadline(token_id))
+    }
+
+    // 消耗 token 的一次使用次数，仅拥有者或被授权者可调用
+    pub fn consume(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+        self.require_authorized_to_spend(owner, token_id)?;
+        let remaining = self.uses_remaining.get(token_id);
+        if remaining.is_zero() {
+            return Err(Erc721Error::NoUsesLeft(NoUsesLeft { token_id }));
+        }
+        let remaining = remaining - U256::from(1);
+        self.uses_remaining.insert(token_id, remaining);
+        log(
+            self.vm(),
+            Consumed {
+                token_id,
+                remaining,
+            },
+        );
+        Ok(())
+    }
+
+    // 获取 token 剩余可使用次数
+    pub fn uses_remaining(&self, token_id: U256) -> Result<U256, Erc721Error> {
+        Ok(self.uses_remaining.get(token_id))
+    }
+
+    // 将调用者的投票权委托给 delegatee
+    pub fn delegate(&mut self, delegatee: Address) -> Result<(), Erc721Error> {
+        let delegator = self.vm().msg_sender();
+        let from_delegate = self.delegates.get(delegator);
+        self.delegates.insert(delegator, delegatee);
+        log(
+            self.vm(),
+            DelegateChanged {
+                delegator,
+                from_delegate,
+                to_delegate: delegatee,
+            },
+        );
+        let balance = self.balances.get(delegator);
+        self.move_voting_power(from_delegate, delegatee, balance);
+        Ok(())
+    }
+
+    // 获取账户的投票权受托人
+    pub fn delegates(&self, account: Address) -> Result<Address, Erc721Error> {
+        Ok(self.delegates.get(account))
+    }
+
+    // 获取账户当前的票数
+    pub fn get_votes(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.current_votes(account))
+    }
+
+    // 获取账户在指定区块结束时的票数，只能查询已产生的区块
+    pub fn get_past_votes(
+        &self,
+        account: Address,
+        block_number: U256,
+    ) -> Result<U256, Erc721Error> {
+        let current_block = U256::from(self.vm().block_number());
+        if block_number >= current_block {
+            return Err(Erc721Error::FutureLookup(FutureLookup {
+                block_number,
+                current_block,
+            }));
+        }
+        // 二分查找最后一个 from_block <= block_number 的检查点
+        let checkpoints = self.checkpoints.getter(account);
+        let mut low = 0;
+        let mut high = checkpoints.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let from_block = checkpoints
+                .getter(mid)
+                .map_or(U64::ZERO, |checkpoint| checkpoint.from_block.get());
+            if U256::from(from_block) > block_number {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        if high == 0 {
+            return Ok(U256::ZERO);
+        }
+        Ok(checkpoints
+            .getter(high - 1)
+            .map_or(U256::ZERO, |checkpoint| checkpoint.votes.get()))
+    }
+
+    // 获取当前快照 ID
+    pub fn current_snapshot_id(&self) -> Result<U256, Erc721Error> {
+        Ok(self.current_snapshot_id.get())
+    }
+
+    // 获取地址在指定快照时的余额
+    pub fn balance_of_at(&self, owner: Address, snapshot_id: U256) -> Result<U256, Erc721Error> {
+        if snapshot_id.is_zero() || snapshot_id > self.current_snapshot_id.get() {
+            return Err(Erc721Error::NonexistentSnapshot(NonexistentSnapshot {
+                snapshot_id,
+            }));
+        }
+        // 二分查找第一个 id >= snapshot_id 的记录
+        let snapshots = self.balance_snapshots.getter(owner);
+        let mut low = 0;
+        let mut high = snapshots.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let id = snapshots
+                .getter(mid)
+                .map_or(U256::ZERO, |snapshot| snapshot.id.get());
+            if id < snapshot_id {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        // 快照之后余额未变化过，当前余额即为快照余额
+        match snapshots.getter(low) {
+            Some(snapshot) => Ok(snapshot.value.get()),
+            None => Ok(self.balances.get(owner)),
+        }
+    }
+
+    // 批量获取 token 的拥有者，零地址表示该 token 未铸造或已销毁
+    pub fn owner_of_batch(&self, token_ids: Vec<U256>) -> Result<Vec<Address>, Erc721Error> {
+        Self::check_batch_len(token_ids.len())?;
+        Ok(token_ids
+            .into_iter()
+            .map(|token_id| self.owner_raw(token_id))
+            .collect())
+    }
+
+    // 获取 [start, end) 区间内各 token 的拥有者，未铸造或已销毁时为零地址，区间长度受 MAX_BATCH 限制
+    pub fn owners_in_range(&self, start: U256, end: U256) -> Result<Vec<Address>, Erc721Error> {
+        let len = end.saturating_sub(start);
+        Self::check_batch_len(len.saturating_to())?;
+        Ok((0..len.to::<usize>())
+            .map(|offset| self.owner_raw(start + U256::from(offset)))
+            .collect())
+    }
+
+    // 执行带数据的安全转账，标记为 payable 以便入口合约覆盖时收取转账手续费
+    #[payable]
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from_with_data<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), Erc721Error> {
+        // 禁止转账到零地址
+        if to.is_zero() {
+            return Err(Erc721Error::TransferToZero(TransferToZero { token_id }));
+        }
+        // 检查调用者是否有权限，并扣减操作者的转账额度
+        let erc721 = storage.borrow_mut();
+        erc721.require_authorized_to_spend(from, token_id)?;
+        erc721.consume_operator_budget(from, token_id)?;
+        // 执行安全转账
+        Self::safe_transfer(storage, token_id, from, to, data.0)
+    }
+
+    // 执行不带数据的安全转账
+    #[payable]
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        // 调用带数据的安全转账，数据为空
+        Self::safe_transfer_from_with_data(storage, from, to, token_id, Bytes(vec![]))
+    }
+
+    // 执行普通转账
+    #[payable]
+    pub fn transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        // 禁止转账到零地址
+        if to.is_zero() {
+            return Err(Erc721Error::TransferToZero(TransferToZero { token_id }));
+        }
+        // 检查调用者是否有权限，同时验证 from 为拥有者
+        let owner = self.require_authorized_to_spend(from, token_id)?;
+        // 以操作者身份转账时扣减其转账额度
+        self.consume_operator_budget(owner, token_id)?;
+        // 执行转账，无需再次读取拥有者
+        self.transfer_verified(token_id, owner, to)
+    }
+
+    // 为指定 token 设置授权
+    pub fn approve(&mut self, approved: Address, token_id: U256) -> Result<(), Erc721Error> {
+        // 获取 token 的拥有者
+        let owner = self.owner_of(token_id)?;
+        // 验证调用者是否有权限
+        if self.vm().msg_sender() != owner && !self.is_operator(owner, self.vm().msg_sender()) {
+            return Err(Erc721Error::NotApproved(NotApproved {
+                owner,
+                spender: self.vm().msg_sender(),
+                token_id,
+            }));
+        }
+        self.require_not_paused(PAUSE_APPROVE)?;
+        // 设置授权，普通授权不设过期时间
+        self.set_token_approval(token_id, approved)?;
+        // 记录授权事件
+        log(
+            self.vm(),
+            Approval {
+                approved,
+                owner,
+                token_id,
+            },
+        );
+        Ok(())
+    }
+
+    // 清除指定 token 的授权，等同于授权给零地址
+    pub fn clear_approval(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        self.approve(Address::ZERO, token_id)
+    }
+
+    // 为指定 token 设置在 deadline 之后失效的授权
+    pub fn approve_until(
+        &mut self,
+        approved: Address,
+        token_id: U256,
+        deadline: U256,
+    ) -> Result<(), Erc721Error> {
+        self.approve(approved, token_id)?;
+        self.approval_deadlines.insert(token_id, deadline);
+        Ok(())
+    }
+
+    // 清除已过期的 token 授权并释放被授权地址的授权计数，任何人都可调用，
+    // 未过期或未设置过期时间的 token 跳过
+    pub fn clear_expired_approvals(&mut self, token_ids: Vec<U256>) -> Result<(), Erc721Error> {
+        Self::check_batch_len(token_ids.len())?;
+        let now = U256::from(self.vm().block_timestamp());
+        for token_id in token_ids {
+            let deadline = self.approval_deadlines.get(token_id);
+            if deadline.is_zero() || now <= deadline {
+                continue;
+            }
+            self.clear_token_approval(token_id);
+        }
+        Ok(())
+    }
+
+    // 获取 token 授权的过期时间，为 0 表示永不过期
+    pub fn approval_deadline(&self, token_id: U256) -> Result<U256, Erc721Error> {
+        Ok(self.approval_deadlines.get(token_id))
+    }
+
+    // 为多个 token 设置同一授权地址，任一 token 无权限时整体回滚
+    pub fn approve_batch(
+        &mut self,
+        approved: Address,
+        token_ids: Vec<U256>,
+    ) -> Result<(), Erc721Error> {
+        Self::check_batch_len(token_ids.len())?;
+        for token_id in token_ids {
+            self.approve(approved, token_id)?;
+        }
+        Ok(())
+    }
+
+    // 设置批量授权
+    pub fn set_approval_for_all(
         &mut self,
         operator: Address,
         approved: bool,
     ) -> Result<(), Erc721Error> {
         // 获取调用者地址
-        let owner = msg::sender();
+        let owner = self.vm().msg_sender();
         // 设置操作者授权
-        self.operator_approvals
-            .setter(owner)
-            .insert(operator, approved);
-        // 记录批量授权事件
-        evm::log(ApprovalForAll {
-            owner,
-            operator,
-            approved,
-        });
+        self.set_operator_approval(owner, operator, approved)
+    }
+
+    // 清除调用者持有的多个 token 的授权，任一 token 不属于调用者时整体回滚，
+    // 撤销不受授权暂停限制
+    pub fn revoke_all_approvals(&mut self, token_ids: Vec<U256>) -> Result<(), Erc721Error> {
+        Self::check_batch_len(token_ids.len())?;
+        let owner = self.vm().msg_sender();
+        for token_id in token_ids {
+            let real_owner = self.owner_of(token_id)?;
+            if real_owner != owner {
+                return Err(Erc721Error::NotOwner(NotOwner {
+                    from: owner,
+                    token_id,
+                    real_owner,
+                }));
+            }
+            self.clear_token_approval(token_id);
+            log(
+                self.vm(),
+                Approval {
+                    owner,
+                    approved: Address::ZERO,
+                    token_id,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    // 撤销调用者对操作者的批量授权，默认操作者同时记为已退出，撤销不受授权暂停限制
+    pub fn revoke_operator(&mut self, operator: Address) -> Result<(), Erc721Error> {
+        self.write_operator_approval(self.vm().msg_sender(), operator, false);
+        Ok(())
+    }
+
+    // 为操作者设置剩余转账次数，设为 uint256 最大值时取消限制，不影响操作者授权本身
+    pub fn set_operator_budget(
+        &mut self,
+        operator: Address,
+        count: U256,
+    ) -> Result<(), Erc721Error> {
+        let owner = self.vm().msg_sender();
+        if count == U256::MAX {
+            self.operator_budgets.setter(owner).delete(operator);
+        } else {
+            let mut budgets = self.operator_budgets.setter(owner);
+            let mut budget = budgets.setter(operator);
+            budget.set.set(true);
+            budget.remaining.set(count);
+        }
+        log(
+            self.vm(),
+            OperatorBudgetSet {
+                owner,
+                operator,
+                budget: count,
+            },
+        );
+        Ok(())
+    }
+
+    // 获取操作者剩余的转账次数，不限制时返回 uint256 最大值
+    pub fn operator_budget(&self, owner: Address, operator: Address) -> Result<U256, Erc721Error> {
+        let budgets = self.operator_budgets.getter(owner);
+        let budget = budgets.getter(operator);
+        if !budget.set.get() {
+            return Ok(U256::MAX);
+        }
+        Ok(budget.remaining.get())
+    }
+
+    // 获取指定拥有者当前的操作者签名授权 nonce
+    pub fn operator_nonces(&self, owner: Address) -> Result<U256, Erc721Error> {
+        Ok(self.operator_nonces.get(owner))
+    }
+
+    // 由中继者提交拥有者的 EIP-712 签名设置批量授权
+    #[allow(clippy::too_many_arguments)]
+    pub fn permit_for_all(
+        &mut self,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        // 按拥有者当前 nonce 计算签名摘要
+        let nonce = self.operator_nonces.get(owner);
+        let struct_hash = keccak(
+            (
+                keccak(PERMIT_FOR_ALL_TYPE),
+                owner,
+                operator,
+                approved,
+                nonce,
+                deadline,
+            )
+                .abi_encode(),
+        );
+        // 只接受拥有者本人的签名
+        let signer = self.recover_signer(struct_hash, deadline, v, r, s)?;
+        if signer != owner {
+            return Err(Erc721Error::InvalidSignature(InvalidSignature {}));
+        }
+        // 消耗 nonce，防止签名重放
+        self.operator_nonces.insert(owner, nonce + U256::from(1));
+        self.set_operator_approval(owner, operator, approved)
+    }
+
+    // 获取 token 当前的签名授权 nonce
+    pub fn nonces(&self, token_id: U256) -> Result<U256, Erc721Error> {
+        Ok(self.permit_nonces.get(token_id))
+    }
+
+    // 由中继者提交拥有者或操作者的 EIP-712 签名为 token 设置授权
+    pub fn permit(
+        &mut self,
+        spender: Address,
+        token_id: U256,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+        // 按 token 当前 nonce 计算签名摘要
+        let nonce = self.permit_nonces.get(token_id);
+        let struct_hash =
+            keccak((keccak(PERMIT_TYPE), spender, token_id, nonce, deadline).abi_encode());
+        // 只接受拥有者或其操作者的签名
+        let signer = self.recover_signer(struct_hash, deadline, v, r, s)?;
+        if signer != owner && !self.is_operator(owner, signer) {
+            return Err(Erc721Error::InvalidSignature(InvalidSignature {}));
+        }
+        self.require_not_paused(PAUSE_APPROVE)?;
+        // 消耗 nonce，防止签名重放
+        self.permit_nonces.insert(token_id, nonce + U256::from(1));
+        self.set_token_approval(token_id, spender)?;
+        log(
+            self.vm(),
+            Approval {
+                owner,
+                approved: spender,
+                token_id,
+            },
+        );
         Ok(())
     }
 
-    // 获取指定 token 的授权地址
+    // 递增 token 的签名授权 nonce，使已签发但未使用的授权签名失效，仅拥有者或被授权者可调用
+    pub fn invalidate_nonce(&mut self, token_id: U256) -> Result<U256, Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+        self.require_authorized_to_spend(owner, token_id)?;
+        let new_nonce = self.permit_nonces.get(token_id) + U256::from(1);
+        self.permit_nonces.insert(token_id, new_nonce);
+        log(
+            self.vm(),
+            NonceInvalidated {
+                token_id,
+                new_nonce,
+            },
+        );
+        Ok(new_nonce)
+    }
+
+    // 获取指定 token 的授权地址，授权过期时返回零地址
     pub fn get_approved(&mut self, token_id: U256) -> Result<Address, Erc721Error> {
-        Ok(self.token_approvals.get(token_id))
+        Ok(self.approved_of(token_id))
     }
 
     // 检查是否为所有者设置了操作者授权
@@ -358,25 +3157,286 @@ impl<T: Erc721Params> Erc721<T> {
         owner: Address,
         operator: Address,
     ) -> Result<bool, Erc721Error> {
-        Ok(self.operator_approvals.getter(owner).get(operator))
+        Ok(self.is_operator(owner, operator))
+    }
+
+    // 批量检查 operator 是否为各拥有者的操作者，按 owners 顺序返回
+    pub fn operators_approved_for(
+        &self,
+        operator: Address,
+        owners: Vec<Address>,
+    ) -> Result<Vec<bool>, Erc721Error> {
+        Self::check_batch_len(owners.len())?;
+        Ok(owners
+            .into_iter()
+            .map(|owner| self.is_operator(owner, operator))
+            .collect())
+    }
+
+    // 获取指定拥有者当前的签名转账 nonce
+    pub fn transfer_nonces(&self, owner: Address) -> Result<U256, Erc721Error> {
+        Ok(self.transfer_nonces.get(owner))
+    }
+
+    // 获取拥有者当前的签名销毁 nonce
+    pub fn burn_nonces(&self, owner: Address) -> Result<U256, Erc721Error> {
+        Ok(self.burn_nonces.get(owner))
+    }
+
+    // 由中继者提交拥有者的 EIP-712 签名完成转账
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_with_sig(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        // 禁止转账到零地址
+        if to.is_zero() {
+            return Err(Erc721Error::TransferToZero(TransferToZero { token_id }));
+        }
+        // 验证 from 是否为拥有者
+        let owner = self.owner_of(token_id)?;
+        if from != owner {
+            return Err(Erc721Error::NotOwner(NotOwner {
+                from,
+                token_id,
+                real_owner: owner,
+            }));
+        }
+        // 按拥有者当前 nonce 计算签名摘要
+        let nonce = self.transfer_nonces.get(owner);
+        let struct_hash =
+            keccak((keccak(TRANSFER_TYPE), from, to, token_id, nonce, deadline).abi_encode());
+        // 恢复签名者，并验证其为拥有者、操作者或被授权者
+        let signer = self.recover_signer(struct_hash, deadline, v, r, s)?;
+        if signer != owner
+            && !self.is_operator(owner, signer)
+            && signer != self.approved_of(token_id)
+        {
+            return Err(Erc721Error::InvalidSignature(InvalidSignature {}));
+        }
+        // 消耗 nonce，防止签名重放
+        self.transfer_nonces.insert(owner, nonce + U256::from(1));
+        // 签名者以操作者身份转账时扣减其转账额度
+        self.consume_budget_of(owner, signer, token_id)?;
+        // 执行转账
+        self.transfer_verified(token_id, owner, to)
+    }
+
+    // 获取拥有者列表中指定索引处的 token_id
+    pub fn token_of_owner_by_index(
+        &self,
+        owner: Address,
+        index: U256,
+    ) -> Result<U256, Erc721Error> {
+        Self::require_extension(T::ENUMERABLE, IERC721_ENUMERABLE)?;
+        if index >= self.balances.get(owner) {
+            return Err(Erc721Error::OwnerIndexOutOfBounds(OwnerIndexOutOfBounds {
+                owner,
+                index,
+            }));
+        }
+        Ok(self.owned_tokens.getter(owner).get(index))
+    }
+
+    // 获取全局列表中的 token 数量，即现存 token 数量
+    pub fn enumerable_total_supply(&self) -> Result<U256, Erc721Error> {
+        Self::require_extension(T::ENUMERABLE, IERC721_ENUMERABLE)?;
+        Ok(U256::from(self.all_tokens.len()))
+    }
+
+    // 获取全局列表中指定索引处的 token_id
+    pub fn token_by_index(&self, index: U256) -> Result<U256, Erc721Error> {
+        Self::require_extension(T::ENUMERABLE, IERC721_ENUMERABLE)?;
+        self.all_tokens
+            .get(index)
+            .ok_or(Erc721Error::GlobalIndexOutOfBounds(
+                GlobalIndexOutOfBounds { index },
+            ))
+    }
+
+    // 获取拥有者持有的全部 token_id，持有量较大时应使用分页版本
+    pub fn tokens_of_owner(&self, owner: Address) -> Result<Vec<U256>, Erc721Error> {
+        Self::require_extension(T::ENUMERABLE, IERC721_ENUMERABLE)?;
+        let balance = self.balances.get(owner);
+        self.tokens_of_owner_paged(owner, U256::ZERO, balance)
+    }
+
+    // 分页获取拥有者持有的 token_id，从 start 开始最多返回 count 个
+    pub fn tokens_of_owner_paged(
+        &self,
+        owner: Address,
+        start: U256,
+        count: U256,
+    ) -> Result<Vec<U256>, Erc721Error> {
+        Self::require_extension(T::ENUMERABLE, IERC721_ENUMERABLE)?;
+        let balance = self.balances.get(owner);
+        // 起始位置超出持有量时返回空列表
+        if start >= balance {
+            return Ok(Vec::new());
+        }
+        let end = start.saturating_add(count).min(balance);
+        let owned = self.owned_tokens.getter(owner);
+        let mut token_ids = Vec::new();
+        let mut index = start;
+        while index < end {
+            token_ids.push(owned.get(index));
+            index += U256::from(1);
+        }
+        Ok(token_ids)
     }
 
     // 检查是否支持指定接口
-    pub fn supports_interface(interface: FixedBytes<4>) -> Result<bool, Erc721Error> {
+    pub fn supports_interface(&self, interface: FixedBytes<4>) -> Result<bool, Erc721Error> {
         // 将接口 ID 转换为字节数组
         let interface_slice_array: [u8; 4] = interface.as_slice().try_into().unwrap();
         // 特殊处理 ERC165 标准中的 0xffffffff
         if u32::from_be_bytes(interface_slice_array) == 0xffffffff {
             return Ok(false);
         }
-        // 定义支持的接口 ID
-        const IERC165: u32 = 0x01ffc9a7;
-        const IERC721: u32 = 0x80ac58cd;
-        const IERC721_METADATA: u32 = 0x5b5e139f;
-        // 检查是否支持指定接口
-        Ok(matches!(
-            u32::from_be_bytes(interface_slice_array),
-            IERC165 | IERC721 | IERC721_METADATA
-        ))
+        // 检查是否支持指定接口，基础接口之外再查询动态注册表
+        let id = u32::from_be_bytes(interface_slice_array);
+        Ok(matches!(id, IERC165 | IERC721 | IERC721_METADATA)
+            || (T::SOULBOUND && id == IERC5192)
+            || self.registered_interfaces.get(interface))
+    }
+
+    // 检查整个集合是否已揭示
+    pub fn is_revealed(&self) -> Result<bool, Erc721Error> {
+        Ok(self.revealed.get())
+    }
+
+    // 检查 [start, end] 区间内的 token 是否均已揭示，整体揭示或被分段揭示区间完全覆盖时为 true
+    pub fn is_range_revealed(&self, start: U256, end: U256) -> Result<bool, Erc721Error> {
+        if end < start {
+            return Err(Erc721Error::InvalidRange(InvalidRange { start, end }));
+        }
+        if self.revealed.get() {
+            return Ok(true);
+        }
+        // 从 start 开始逐段向后推进，找不到覆盖当前位置的区间时即未完全揭示
+        let mut cursor = start;
+        loop {
+            let covering = (0..self.reveal_segments.len())
+                .filter_map(|i| self.reveal_segments.getter(i))
+                .filter(|segment| segment.start.get() <= cursor && cursor <= segment.end.get())
+                .map(|segment| segment.end.get())
+                .max();
+            match covering {
+                Some(segment_end) if segment_end >= end => return Ok(true),
+                Some(segment_end) => cursor = segment_end + U256::from(1),
+                None => return Ok(false),
+            }
+        }
+    }
+
+    // 获取已销毁 token 返回的墓碑 URI
+    pub fn tombstone_uri(&self) -> Result<String, Erc721Error> {
+        Ok(self.tombstone_uri.get_string())
+    }
+
+    // 获取基础 URI 后追加的后缀
+    pub fn uri_suffix(&self) -> Result<String, Erc721Error> {
+        Ok(self.suffix())
+    }
+
+    // 获取持有至少一个 token 的地址数量
+    pub fn holder_count(&self) -> Result<U256, Erc721Error> {
+        Ok(self.holder_count.get())
+    }
+
+    // 获取当前的元数据版本号
+    pub fn metadata_version(&self) -> Result<U256, Erc721Error> {
+        Ok(self.metadata_version.get())
+    }
+
+    // 获取拥有者当前授权的操作者数量，默认操作者不计入
+    pub fn operator_count(&self, owner: Address) -> Result<U256, Erc721Error> {
+        Ok(self.operator_counts.get(owner))
+    }
+
+    // 获取地址当前持有的 token 级授权数量，已过期的授权在清除前仍计入，
+    // 可调用 clear_expired_approvals 释放
+    pub fn token_approvals_held(&self, operator: Address) -> Result<U256, Erc721Error> {
+        Ok(self.token_approvals_held.get(operator))
+    }
+
+    // 获取 token 的全部属性，按设置顺序返回属性名和属性值两个等长列表
+    pub fn get_attributes(
+        &self,
+        token_id: U256,
+    ) -> Result<(Vec<String>, Vec<String>), Erc721Error> {
+        self.owner_of(token_id)?;
+        let attributes = self.attributes.getter(token_id);
+        Ok((0..attributes.len())
+            .filter_map(|i| attributes.getter(i))
+            .map(|attribute| (attribute.key.get_string(), attribute.value.get_string()))
+            .unzip())
+    }
+
+    // 检查地址是否已通过 KYC 验证
+    pub fn is_kyc_verified(&self, account: Address) -> Result<bool, Erc721Error> {
+        Ok(self.kyc_verified.get(account))
+    }
+
+    // 获取是否要求 KYC
+    pub fn kyc_required(&self) -> Result<bool, Erc721Error> {
+        Ok(self.kyc_required.get())
+    }
+
+    // 获取 token 的二进制 IPFS CID，未设置时为空
+    pub fn token_cid(&self, token_id: U256) -> Result<Bytes, Erc721Error> {
+        Ok(Bytes(self.token_cids.getter(token_id).get_bytes()))
+    }
+
+    // 获取地址累计被铸造的 token 数量，包含已转出或销毁的 token
+    pub fn mint_count(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.mint_counts.get(account))
+    }
+
+    // 获取铸造排行榜前 n 名及其累计铸造数量，n 超过榜单长度时返回整个榜单
+    pub fn top_minters(&self, n: U256) -> Result<Vec<(Address, U256)>, Erc721Error> {
+        let len = self.top_minters.len().min(n.saturating_to());
+        Ok((0..len)
+            .filter_map(|i| self.top_minters.get(i))
+            .map(|account| (account, self.mint_counts.get(account)))
+            .collect())
+    }
+
+    // 检查 token 是否为稀有 token
+    pub fn is_golden(&self, token_id: U256) -> Result<bool, Erc721Error> {
+        Ok(self.golden.get(token_id))
+    }
+
+    // 检查 token 的元数据是否已冻结
+    pub fn is_token_frozen(&self, token_id: U256) -> Result<bool, Erc721Error> {
+        Ok(self.token_frozen.get(token_id))
+    }
+
+    // 获取接收者回调的 gas 上限
+    pub fn receiver_gas_limit(&self) -> Result<u64, Erc721Error> {
+        Ok(self.receiver_gas_limit.get().to())
+    }
+
+    // 获取所有受支持的接口 ID，包括基础接口和动态注册的接口
+    pub fn supported_interfaces(&self) -> Result<Vec<FixedBytes<4>>, Erc721Error> {
+        let mut interfaces: Vec<FixedBytes<4>> = [IERC165, IERC721, IERC721_METADATA]
+            .iter()
+            .map(|id| FixedBytes(id.to_be_bytes()))
+            .collect();
+        if T::SOULBOUND {
+            interfaces.push(FixedBytes(IERC5192.to_be_bytes()));
+        }
+        interfaces.extend(
+            (0..self.registered_interface_list.len())
+                .filter_map(|i| self.registered_interface_list.get(i)),
+        );
+        Ok(interfaces)
     }
 }
@@ -3,12 +3,34 @@
 extern crate alloc;
 
 // 引入模块和依赖
+mod base58;
+mod base64;
+mod eip712;
+mod erc6551;
 mod erc721;
+mod merkle;
+mod splitter;
 
-use crate::erc721::{Erc721, Erc721Error, Erc721Params};
-use alloy_primitives::{Address, U256};
+use crate::erc721::{
+    AccountCreationFailed, AllowanceExceeded, AllowlistExhausted, AlreadyClaimed,
+    AlreadyClaimedFree, AlreadyInitialized, AlreadyWaitlisted, BadNonce, BelowReserve,
+    ContractSealed, DestinationNotAllowed, Erc20TransferFailed, Erc721, Erc721Error, Erc721Params,
+    EthTransferFailed, ExternalTransferFailed, FeeRequired, FreeMintEnded, HookFailed,
+    InsufficientAllowance, InsufficientPayment, InvalidAuctionConfig, InvalidPhaseWindow,
+    InvalidProceedsSplit, InvalidProof, InvalidRoyalty, InvalidSignature, InvalidTokenId,
+    MarketplaceNotAllowed, MemoTooLong, MigrationSealed, MintThrottled, MissingRole, NoActivePhase,
+    NoAllowance, NonexistentPhase, NotAllowlisted, NotBurnAuthority, NotContractOwner, NotOwner,
+    NotPendingOwner, NotSaleHook, NotVault, NotWrapped, PaymentFailed, PhaseLimitReached,
+    PhaseSoldOut, ProvenanceAlreadySet, RecoveryModeDisabled, RefundFailed, RefundWindowClosed,
+    SelfReferral, TokenDataTooLong, TransferToContractSelf, TransferToZero, TxLimitExceeded,
+    VaultNotSet, IERC2981,
+};
+use crate::splitter::PaymentSplitter;
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{b256, Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
 // 引入 Stylus SDK 和 alloy 基本类型
-use stylus_sdk::{msg, prelude::*};
+use stylus_sdk::{abi::Bytes, crypto::keccak, prelude::*, stylus_core::calls::context::Call};
 
 // 定义 NFT 参数结构体
 struct StylusNFTParams;
@@ -18,12 +40,65 @@ impl Erc721Params for StylusNFTParams {
     const NAME: &'static str = "DOG";
     // 定义 NFT 符号常量
     const SYMBOL: &'static str = "DOG";
+    // 限制 URI 长度，避免写入过长字符串占用存储
+    const MAX_URI_LEN: Option<usize> = Some(256);
     // 生成指定 token_id 的 URI
     fn token_uri(token_id: U256) -> String {
         format!("{}{}{}", "https://external-magenta-alpaca.myfilebase.com/ipfs/QmY47C6mUFEGPGF5muGTEcSD3MPspCSpT2EGJV8QvQGUnV", token_id, ".json")
     }
+    // 按 token_id 生成链上图像，背景色相由 token_id 决定
+    fn render_svg(token_id: U256) -> String {
+        let hue = token_id % U256::from(360);
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 350 350\"><rect width=\"100%\" height=\"100%\" fill=\"hsl({},70%,50%)\"/><circle cx=\"175\" cy=\"175\" r=\"100\" fill=\"white\"/><text x=\"50%\" y=\"52%\" text-anchor=\"middle\">DOG #{}</text></svg>",
+            hue, token_id
+        )
+    }
 }
 
+// ERC-173 合约所有权接口 ID
+const IERC173: FixedBytes<4> = FixedBytes([0x7f, 0x58, 0x28, 0xd0]);
+
+// 版税比例的分母，royalty_bps 以万分之一为单位
+const ROYALTY_DENOMINATOR: u64 = 10_000;
+
+// 未配置任何版税时回退使用的版税比例，单位为万分之一
+const DEFAULT_ROYALTY_BPS: u64 = 500;
+
+// 管理员角色，可授予和撤销所有角色
+const DEFAULT_ADMIN_ROLE: FixedBytes<32> = FixedBytes::ZERO;
+// 铸造者角色，keccak256("MINTER_ROLE")
+const MINTER_ROLE: FixedBytes<32> =
+    b256!("9f2df0fed2c77648de5860a4cc508cd0818c85b8b8a1ab4ceeef8d981c8956a6");
+
+// 单笔交易最多可铸造的数量
+const MAX_PER_TX: u64 = 10;
+
+// 懒铸造凭证的 EIP-712 类型字符串
+const VOUCHER_TYPE: &str =
+    "Voucher(address to,uint256 tokenId,string uri,uint256 price,uint256 deadline)";
+
+// 按序铸造授权的 EIP-712 类型字符串
+const MINT_AUTHORIZATION_TYPE: &str = "MintAuthorization(address minter,uint256 nonce)";
+
+// 带期限铸造授权的 EIP-712 类型字符串
+const SIGNED_MINT_TYPE: &str = "SignedMint(address minter,uint256 deadline)";
+
+// 游戏服务器授权销毁的 EIP-712 类型字符串
+const GAME_BURN_TYPE: &str = "GameBurn(address player,uint256 tokenId,uint256 nonce)";
+
+// 转账备注的最大字节长度
+const MAX_MEMO_LEN: usize = 256;
+
+// 铸造时附带的 token 数据的最大字节长度
+const MAX_TOKEN_DATA_LEN: usize = 256;
+
+// 每个白名单地址可铸造的数量
+const ALLOWLIST_MINT_LIMIT: u64 = 1;
+
+// 单次提取时最多释放的已过期退款记录数
+const MAX_REFUND_EXPIRIES: u64 = 100;
+
 // 定义合约入口点和存储结构
 sol_storage! {
     #[entrypoint]
@@ -31,39 +106,2729 @@ sol_storage! {
         // 允许 erc721 访问 StylusNFT 的存储并调用方法
         #[borrow]
         Erc721<StylusNFTParams> erc721;
+        // 合约所有者
+        address owner;
+        // 等待接收所有权的地址
+        address pending_owner;
+        // 地址是否在白名单中
+        mapping(address => bool) allowlisted;
+        // 白名单地址已铸造的数量
+        mapping(address => uint256) allowlist_claimed;
+        // 每个 token 的铸造价格
+        uint256 mint_price;
+        // 荷兰式拍卖起始价格
+        uint256 auction_start_price;
+        // 荷兰式拍卖结束价格
+        uint256 auction_end_price;
+        // 荷兰式拍卖开始时间
+        uint256 auction_start_time;
+        // 荷兰式拍卖持续时间，为 0 表示未启用拍卖
+        uint256 auction_duration;
+        // 荷兰式拍卖的保留价，拍卖价格不会低于该值
+        uint256 reserve_price;
+        // 配置是否已永久封存，封存后所有配置类函数回滚
+        bool sealed;
+        // 元数据来源证明哈希，需在铸造前公布
+        bytes32 provenance_hash;
+        // 角色到账户授权的映射
+        mapping(bytes32 => mapping(address => bool)) roles;
+        // 包装 token_id 到其底层外部 token 的映射
+        mapping(uint256 => WrappedToken) wrapped;
+        // 已兑换 token 到兑换者的映射
+        mapping(uint256 => address) redeemers;
+        // ERC-6551 注册表地址
+        address account_registry;
+        // token 绑定账户的实现合约地址
+        address account_implementation;
+        // 是否已完成初始化配置
+        bool initialized;
+        // 联合曲线斜率，每多一个现存 token 价格增加的数额
+        uint256 price_slope;
+        // 版税分账模块
+        #[borrow]
+        PaymentSplitter splitter;
+        // 版税比例，单位为万分之一
+        uint256 royalty_bps;
+        // 所有者是否显式设置过版税比例，包括设置为 0
+        bool royalty_set;
+        // 是否关闭未配置版税时的回退版税
+        bool royalty_fallback_disabled;
+        // 铸造阶段列表
+        MintPhase[] phases;
+        // 阶段索引到地址已铸造数量的映射
+        mapping(uint256 => mapping(address => uint256)) phase_minted;
+        // 地址是否为销毁授权合约
+        mapping(address => bool) burn_authorities;
+        // 地址到下一个按序铸造 nonce 的映射
+        mapping(address => uint256) mint_nonces;
+        // 每次转账需支付的固定手续费
+        uint256 transfer_fee;
+        // 是否启用转账手续费
+        bool transfer_fee_enabled;
+        // 空投领取的默克尔根，叶子为 (地址, 数量)
+        bytes32 claim_root;
+        // 地址到已领取空投数量的映射
+        mapping(address => uint256) claimed;
+        // 是否只允许持有者或白名单交易市场发起转账
+        bool restrict_transfers;
+        // 允许发起转账的交易市场
+        mapping(address => bool) allowed_marketplaces;
+        // 是否只允许销毁授权合约销毁 token
+        bool restricted_burn;
+        // 地址到销毁所得积分的映射，积分不可转让
+        mapping(address => uint256) points;
+        // 每销毁一个 token 奖励的积分
+        uint256 points_per_burn;
+        // 推荐人到成功推荐铸造次数的映射
+        mapping(address => uint256) referrals;
+        // 铸造收益的创作者收款地址
+        address creator;
+        // 铸造收益的平台收款地址
+        address platform;
+        // 平台分成比例，单位为万分之一
+        uint256 platform_bps;
+        // 地址到所有者授予的剩余铸造额度的映射
+        mapping(address => uint256) mint_allowance;
+        // 是否开启恢复模式，开启后所有者可强制转移 token
+        bool recovery_mode;
+        // 代用户托管 token 的托管合约地址
+        address vault;
+        // 托管中的 token 到实际受益人的映射
+        mapping(uint256 => address) beneficial_owners;
+        // 地址是否已使用过带期限的铸造授权
+        mapping(address => bool) signed_minted;
+        // token_id 到铸造时支付价格的映射，退款时按此金额退还
+        mapping(uint256 => uint256) paid_prices;
+        // token_id 到支付铸造价格的铸造者的映射，退款只退给该地址
+        mapping(uint256 => address) paid_minters;
+        // 仍在退款期内、提取时需保留的金额总和
+        uint256 refund_reserved;
+        // 按铸造顺序记录的可退款 token，用于释放已过期的保留金额
+        uint256[] refund_queue;
+        // 退款队列中下一个待检查的位置索引
+        uint256 refund_queue_head;
+        // 铸造后允许退款的秒数，为 0 表示不允许退款
+        uint256 refund_window;
+        // 是否允许本合约持有自身的 token，用于托管类设计
+        bool allow_self_hold;
+        // 按加入顺序排列的候补名单
+        address[] waitlist;
+        // 候补名单中下一个待铸造的位置索引
+        uint256 waitlist_head;
+        // 地址到其候补位置的映射，从 1 开始，为 0 表示未加入
+        mapping(address => uint256) waitlist_positions;
+        // 地址是否为可设置 KYC 状态的验证者
+        mapping(address => bool) kyc_verifiers;
+        // 地址到其最近一次公开铸造所在区块的映射
+        mapping(address => uint256) last_mint_block;
+        // 免费铸造的总量上限，为 0 表示未开放
+        uint256 free_mint_cap;
+        // 已免费铸造的数量
+        uint256 free_minted;
+        // 地址是否已领取免费铸造
+        mapping(address => bool) claimed_free;
+        // 转账后接收 onSecondarySale 回调的合约地址，为零表示不回调
+        address sale_hook;
+        // 回调失败时是否回滚转账
+        bool strict_hook;
+        // 授权代玩家销毁 token 的游戏签名者
+        address game_signer;
+        // 玩家下一个游戏授权销毁 nonce
+        mapping(address => uint256) game_burn_nonces;
+        // token_id 到铸造时附带的应用数据的映射
+        mapping(uint256 => bytes) token_data;
+        // 迁移是否已封存，封存后不可再批量写入操作者授权
+        bool migration_sealed;
+        // 允许接收 token 的合约地址白名单
+        mapping(address => bool) dest_whitelist;
+        // 是否限制只能转入白名单合约，外部账户不受限制
+        bool restrict_contract_dest;
+        // 按回调合约上报的成交价累计的各接收者应得版税，仅用于记账，不强制支付
+        mapping(address => uint256) royalties_accrued;
+        // 用于 ERC-20 铸造的支付代币，为零表示未开启
+        address payment_token;
+        // 以支付代币计价的铸造价格
+        uint256 erc20_mint_price;
+    }
+
+    // 铸造阶段配置，时间窗口为 [start, end)
+    struct MintPhase {
+        // 阶段开始时间
+        uint256 start;
+        // 阶段结束时间
+        uint256 end;
+        // 阶段内单个 token 的价格
+        uint256 price;
+        // 每个地址在该阶段的铸造上限，为 0 表示不限
+        uint256 per_wallet_limit;
+        // 白名单默克尔根，为零表示公开铸造
+        bytes32 merkle_root;
+        // 该阶段可铸造的总量，为 0 表示仅受全局最大供应量限制
+        uint256 max_in_phase;
+        // 该阶段已铸造的数量
+        uint256 minted_in_phase;
+    }
+
+    // 被包装的外部 token
+    #[derive(Erase)]
+    struct WrappedToken {
+        // 外部集合地址
+        address collection;
+        // 外部 token_id
+        uint256 token_id;
+    }
+}
+
+// 定义外部合约接口
+sol! {
+    // 用于转移被包装的外部 NFT
+    interface IERC721 {
+        function transferFrom(address from, address to, uint256 token_id) external;
+        function ownerOf(uint256 token_id) external view returns (address);
+    }
+
+    // 用于转出误转入本合约的 ERC-20 代币
+    interface IERC20 {
+        function transfer(address to, uint256 amount) external returns (bool);
+        function transferFrom(address from, address to, uint256 amount) external returns (bool);
+        function allowance(address owner, address spender) external view returns (uint256);
+    }
+
+    // 用于通知交易市场或版税记账合约的二级转账回调
+    interface ISaleHook {
+        function onSecondarySale(address from, address to, uint256 token_id) external;
+    }
+
+    // 用于创建 token 绑定账户的 ERC-6551 注册表
+    interface IERC6551Registry {
+        function createAccount(address implementation, bytes32 salt, uint256 chain_id, address token_contract, uint256 token_id) external returns (address);
+    }
+}
+
+// 定义合约级事件
+sol! {
+    // 合约所有权变更事件
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+    // 所有权转移发起事件
+    event OwnershipTransferStarted(address indexed previous_owner, address indexed new_owner);
+    // 角色授予事件
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    // 角色撤销事件
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    // 销毁兑换实物事件
+    event Redeemed(address indexed redeemer, uint256 indexed token_id);
+    // 外部 NFT 包装事件
+    event Wrapped(address indexed owner, address indexed collection, uint256 external_id, uint256 wrapper_id);
+    // 外部 NFT 解包事件
+    event Unwrapped(address indexed owner, address indexed collection, uint256 external_id, uint256 wrapper_id);
+    // 误转入的 ERC-20 代币被转出事件
+    event TokenRescued(address indexed token, address indexed to, uint256 amount);
+    // 直接收到 ETH 事件
+    event Received(address indexed sender, uint256 amount);
+    // 合约余额提取事件
+    event Withdrawn(address indexed to, uint256 amount);
+    // 管理员强制销毁事件
+    event AdminBurn(address indexed owner, uint256 indexed token_id);
+    // 合约初始化事件
+    event Initialized(address indexed owner, string base_uri, uint256 mint_price);
+    // 销毁获得积分事件
+    event PointsEarned(address indexed account, uint256 amount, uint256 total);
+    // 推荐铸造事件
+    event Referral(address indexed referrer, address indexed minter, uint256 indexed token_id);
+    // 铸造到托管合约事件
+    event VaultMinted(address indexed vault, address indexed user, uint256 indexed token_id);
+    // 托管合约将 token 释放给受益人事件
+    event VaultReleased(address indexed vault, address indexed user, uint256 indexed token_id);
+    // 恢复模式下所有者强制转移事件
+    event ForcedTransfer(address indexed from, address indexed to, uint256 indexed token_id, address operator);
+    // 恢复模式下所有者代持有者设置操作者授权事件
+    event ForcedApprovalForAll(address indexed owner, address indexed operator, bool approved, address admin);
+    // 恢复模式开关事件
+    event RecoveryModeSet(bool enabled);
+    // 铸造收益按创作者和平台分配事件
+    event ProceedsSplit(address indexed creator, address indexed platform, uint256 creator_amount, uint256 platform_amount);
+    // 退还 token 并获得铸造款退款事件
+    event Refunded(address indexed owner, uint256 indexed token_id, uint256 amount);
+    // 转账备注事件，备注只记录在日志中，不写入存储
+    event TransferMemo(address indexed from, address indexed to, uint256 indexed token_id, string memo);
+    // 加入候补名单事件
+    event WaitlistJoined(address indexed account, uint256 position);
+    // 回调合约上报成交后累计版税事件
+    event RoyaltyAccrued(address indexed receiver, uint256 indexed token_id, uint256 sale_price, uint256 amount);
+    // 配置永久封存事件
+    event Sealed(address indexed owner);
+}
+
+// 实现 StylusNFT 的内部方法
+impl StylusNFT {
+    // 校验并写入铸造阶段配置
+    fn write_phase(
+        &mut self,
+        index: usize,
+        start: U256,
+        end: U256,
+        price: U256,
+        per_wallet_limit: U256,
+        merkle_root: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        if end <= start {
+            return Err(Erc721Error::InvalidPhaseWindow(InvalidPhaseWindow {
+                start,
+                end,
+            }));
+        }
+        let mut phase = self
+            .phases
+            .setter(index)
+            .ok_or(Erc721Error::NonexistentPhase(NonexistentPhase {
+                phase: U256::from(index),
+            }))?;
+        phase.start.set(start);
+        phase.end.set(end);
+        phase.price.set(price);
+        phase.per_wallet_limit.set(per_wallet_limit);
+        phase.merkle_root.set(merkle_root);
+        Ok(())
+    }
+
+    // 启用转账手续费时按转账数量收取手续费，计入版税分账并退还多付金额
+    fn collect_transfer_fee(&mut self, count: usize) -> Result<(), Erc721Error> {
+        // 未启用时退还随调用附带的 ETH
+        if !self.transfer_fee_enabled.get() {
+            return self.collect_payment(U256::ZERO);
+        }
+        let fee = self.transfer_fee.get() * U256::from(count);
+        let paid = self.vm().msg_value();
+        if paid < fee {
+            return Err(Erc721Error::FeeRequired(FeeRequired {
+                required: fee,
+                paid,
+            }));
+        }
+        self.collect_payment(fee)?;
+        self.splitter.deposit(fee);
+        Ok(())
+    }
+
+    // 限制转账时，调用者需为持有者本人或允许的交易市场
+    fn check_marketplace(&self, from: Address) -> Result<(), Erc721Error> {
+        let caller = self.vm().msg_sender();
+        if self.restrict_transfers.get() && caller != from && !self.allowed_marketplaces.get(caller)
+        {
+            return Err(Erc721Error::MarketplaceNotAllowed(MarketplaceNotAllowed {
+                caller,
+            }));
+        }
+        Ok(())
+    }
+
+    // 未允许持有自身 token 时禁止转给本合约，本合约没有 onERC721Received，转入后无法转出
+    fn check_self_hold(&self, to: Address, token_id: U256) -> Result<(), Erc721Error> {
+        if to == self.vm().contract_address() && !self.allow_self_hold.get() {
+            return Err(Erc721Error::TransferToContractSelf(
+                TransferToContractSelf { token_id },
+            ));
+        }
+        Ok(())
+    }
+
+    // 限制合约接收方时，接收方为合约且不在白名单中则拒绝转账
+    fn check_contract_dest(&self, to: Address) -> Result<(), Erc721Error> {
+        if self.restrict_contract_dest.get()
+            && self.vm().code_size(to) > 0
+            && !self.dest_whitelist.get(to)
+        {
+            return Err(Erc721Error::DestinationNotAllowed(DestinationNotAllowed {
+                to,
+            }));
+        }
+        Ok(())
+    }
+
+    // 设置了回调合约时通知其完成了一次转账，严格模式下回调失败会回滚转账
+    fn notify_sale_hook(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        let hook = self.sale_hook.get();
+        if hook.is_zero() {
+            return Ok(());
+        }
+        let call = ISaleHook::onSecondarySaleCall { from, to, token_id };
+        let result = self.vm().call(&Call::new(), hook, &call.abi_encode());
+        if result.is_err() && self.strict_hook.get() {
+            return Err(Erc721Error::HookFailed(HookFailed { hook, token_id }));
+        }
+        Ok(())
+    }
+
+    // 合约余额中可提取的部分，不包含待分账的版税和仍在退款期内的铸造款
+    fn proceeds_balance(&self) -> U256 {
+        self.vm()
+            .balance(self.vm().contract_address())
+            .saturating_sub(self.splitter.unreleased())
+            .saturating_sub(self.reserved_refunds())
+    }
+
+    // 按分配比例将可提取余额转给平台和创作者，未配置分配时回滚
+    fn split_proceeds(&mut self) -> Result<(), Erc721Error> {
+        self.release_expired_refunds();
+        let creator = self.creator.get();
+        let platform = self.platform.get();
+        let platform_bps = self.platform_bps.get();
+        // 未配置分配时不可提取
+        if creator.is_zero() || platform.is_zero() {
+            return Err(Erc721Error::InvalidProceedsSplit(InvalidProceedsSplit {
+                creator,
+                platform,
+                platform_bps,
+            }));
+        }
+        let balance = self.proceeds_balance();
+        let platform_amount = balance * platform_bps / U256::from(ROYALTY_DENOMINATOR);
+        let creator_amount = balance - platform_amount;
+        // 依次转给平台和创作者
+        for (to, amount) in [(platform, platform_amount), (creator, creator_amount)] {
+            if amount.is_zero() {
+                continue;
+            }
+            self.vm()
+                .transfer_eth(to, amount)
+                .map_err(|_| Erc721Error::EthTransferFailed(EthTransferFailed { to, amount }))?;
+        }
+        log(
+            self.vm(),
+            ProceedsSplit {
+                creator,
+                platform,
+                creator_amount,
+                platform_amount,
+            },
+        );
+        Ok(())
+    }
+
+    // 检查调用者是否为合约所有者
+    fn only_owner(&self) -> Result<(), Erc721Error> {
+        if self.vm().msg_sender() != self.owner.get() {
+            return Err(Erc721Error::NotContractOwner(NotContractOwner {
+                caller: self.vm().msg_sender(),
+            }));
+        }
+        Ok(())
+    }
+
+    // 检查调用者是否为合约所有者且配置未封存，用于 init、set_* 等所有配置类函数
+    fn only_owner_unsealed(&self) -> Result<(), Erc721Error> {
+        self.only_owner()?;
+        if self.sealed.get() {
+            return Err(Erc721Error::ContractSealed(ContractSealed {}));
+        }
+        Ok(())
+    }
+
+    // 检查调用者是否拥有指定角色
+    fn only_role(&self, role: FixedBytes<32>) -> Result<(), Erc721Error> {
+        if !self.roles.getter(role).get(self.vm().msg_sender()) {
+            return Err(Erc721Error::MissingRole(MissingRole {
+                account: self.vm().msg_sender(),
+                role,
+            }));
+        }
+        Ok(())
+    }
+
+    // 检查调用者是否为合约所有者或 KYC 验证者
+    fn only_kyc_verifier(&self) -> Result<(), Erc721Error> {
+        if self.vm().msg_sender() != self.owner.get()
+            && !self.kyc_verifiers.get(self.vm().msg_sender())
+        {
+            return Err(Erc721Error::NotContractOwner(NotContractOwner {
+                caller: self.vm().msg_sender(),
+            }));
+        }
+        Ok(())
+    }
+
+    // 受限销毁模式下检查调用者是否为销毁授权合约
+    fn only_burn_authority(&self) -> Result<(), Erc721Error> {
+        if self.restricted_burn.get() && !self.burn_authorities.get(self.vm().msg_sender()) {
+            return Err(Erc721Error::NotBurnAuthority(NotBurnAuthority {
+                caller: self.vm().msg_sender(),
+            }));
+        }
+        Ok(())
+    }
+
+    // 所有由用户发起的销毁共用，受限销毁模式下要求调用者为销毁授权合约，
+    // 调用方需已验证调用者有权销毁 owner 的 token
+    fn guarded_burn(&mut self, owner: Address, token_id: U256) -> Result<(), Erc721Error> {
+        self.only_burn_authority()?;
+        self.erc721.burn(owner, token_id)?;
+        // 已销毁的 token 不再可退款
+        self.clear_paid_mint(token_id);
+        Ok(())
+    }
+
+    // 销毁并为 account 累计积分，签名销毁时 account 为拥有者或玩家而非提交者，
+    // 退款和解包可反复进行，不经过此处计分
+    fn rewarded_burn(
+        &mut self,
+        owner: Address,
+        token_id: U256,
+        account: Address,
+    ) -> Result<(), Erc721Error> {
+        self.guarded_burn(owner, token_id)?;
+        self.credit_burn_points(account, 1);
+        Ok(())
+    }
+
+    // 按销毁数量为 account 累计积分，未设置奖励时不记录
+    fn credit_burn_points(&mut self, account: Address, burned: usize) {
+        let amount = self.points_per_burn.get() * U256::from(burned);
+        if amount.is_zero() {
+            return;
+        }
+        let total = self.points.get(account) + amount;
+        self.points.insert(account, total);
+        log(
+            self.vm(),
+            PointsEarned {
+                account,
+                amount,
+                total,
+            },
+        );
+    }
+
+    // 授予角色，已拥有时不重复记录事件
+    fn grant_role_internal(&mut self, role: FixedBytes<32>, account: Address) {
+        if self.roles.getter(role).get(account) {
+            return;
+        }
+        self.roles.setter(role).insert(account, true);
+        log(
+            self.vm(),
+            RoleGranted {
+                role,
+                account,
+                sender: self.vm().msg_sender(),
+            },
+        );
+    }
+
+    // 限制每个地址每个区块只能公开铸造一次，并记录本次铸造的区块
+    fn throttle_mint(&mut self, minter: Address) -> Result<(), Erc721Error> {
+        let block_number = U256::from(self.vm().block_number());
+        if self.last_mint_block.get(minter) == block_number {
+            return Err(Erc721Error::MintThrottled(MintThrottled {
+                account: minter,
+                block_number,
+            }));
+        }
+        self.last_mint_block.insert(minter, block_number);
+        Ok(())
+    }
+
+    // 铸造 token，开启退款时记录铸造者和支付价格并保留该金额，供退款时使用
+    fn mint_paid(&mut self, to: Address, price: U256) -> Result<U256, Erc721Error> {
+        let token_id = self.erc721.mint(to)?;
+        if !price.is_zero() && !self.refund_window.get().is_zero() {
+            self.paid_prices.insert(token_id, price);
+            self.paid_minters.insert(token_id, to);
+            self.refund_reserved.set(self.refund_reserved.get() + price);
+            self.refund_queue.push(token_id);
+        }
+        Ok(token_id)
+    }
+
+    // 清除 token 的退款记录并释放保留金额，返回铸造者和支付价格
+    fn clear_paid_mint(&mut self, token_id: U256) -> (Address, U256) {
+        let minter = self.paid_minters.get(token_id);
+        let amount = self.paid_prices.get(token_id);
+        if !amount.is_zero() {
+            self.refund_reserved
+                .set(self.refund_reserved.get() - amount);
+        }
+        self.paid_prices.delete(token_id);
+        self.paid_minters.delete(token_id);
+        (minter, amount)
+    }
+
+    // 从队首起查找已过期的退款记录，返回新的队首位置和可释放的金额，
+    // 铸造时间按队列顺序递增，遇到仍在退款期内的记录即可停止
+    fn expired_refunds(&self) -> (U256, U256) {
+        let window = self.refund_window.get();
+        let now = U256::from(self.vm().block_timestamp());
+        let len = U256::from(self.refund_queue.len());
+        let mut head = self.refund_queue_head.get();
+        let mut released = U256::ZERO;
+        let mut scanned = 0;
+        while head < len && scanned < MAX_REFUND_EXPIRIES {
+            let token_id = self.refund_queue.get(head).unwrap_or_default();
+            let amount = self.paid_prices.get(token_id);
+            let deadline = self.erc721.minted_at.get(token_id).saturating_add(window);
+            if !amount.is_zero() && !window.is_zero() && now <= deadline {
+                break;
+            }
+            released += amount;
+            head += U256::from(1);
+            scanned += 1;
+        }
+        (head, released)
+    }
+
+    // 清除已过期的退款记录并释放其保留金额
+    fn release_expired_refunds(&mut self) {
+        let (end, _) = self.expired_refunds();
+        let mut head = self.refund_queue_head.get();
+        while head < end {
+            let token_id = self.refund_queue.get(head).unwrap_or_default();
+            self.clear_paid_mint(token_id);
+            head += U256::from(1);
+        }
+        self.refund_queue_head.set(end);
+    }
+
+    // 提取时需保留的退款金额，关闭退款时不保留
+    fn reserved_refunds(&self) -> U256 {
+        if self.refund_window.get().is_zero() {
+            return U256::ZERO;
+        }
+        self.refund_reserved.get() - self.expired_refunds().1
+    }
+
+    // 收取铸造费用，多付部分退还给调用者
+    fn collect_payment(&mut self, price: U256) -> Result<(), Erc721Error> {
+        let paid = self.vm().msg_value();
+        if paid < price {
+            return Err(Erc721Error::InsufficientPayment(InsufficientPayment {
+                required: price,
+                paid,
+            }));
+        }
+        // 使用底层调用退还多付金额
+        let excess = paid - price;
+        if excess > U256::ZERO {
+            let to = self.vm().msg_sender();
+            self.vm()
+                .transfer_eth(to, excess)
+                .map_err(|_| Erc721Error::RefundFailed(RefundFailed { to, amount: excess }))?;
+        }
+        Ok(())
     }
 }
 
 // 实现 StylusNFT 的外部方法
 #[public]
-#[inherit(Erc721<StylusNFTParams>)]
+#[inherit(Erc721<StylusNFTParams>, PaymentSplitter)]
 impl StylusNFT {
-    // 铸造 NFT 给调用者
+    // 部署时设置合约所有者
+    #[constructor]
+    pub fn constructor(&mut self, initial_owner: Address) {
+        self.owner.set(initial_owner);
+        // 部署者默认拥有管理员和铸造者角色
+        self.grant_role_internal(DEFAULT_ADMIN_ROLE, initial_owner);
+        self.grant_role_internal(MINTER_ROLE, initial_owner);
+        // 注册已启用扩展的接口
+        self.erc721.register_extensions();
+        self.erc721.register_interface(IERC173);
+        // 缓存部署链上的签名域分隔符
+        self.erc721.cache_domain_separator();
+        log(
+            self.vm(),
+            OwnershipTransferred {
+                previous_owner: Address::ZERO,
+                new_owner: initial_owner,
+            },
+        );
+    }
+
+    // 一次性设置所有者与核心配置，已有所有者时仅所有者可调用，
+    // 未经构造函数部署（所有者为零地址）时由首个调用者完成
+    pub fn init(
+        &mut self,
+        owner: Address,
+        base_uri: String,
+        mint_price: U256,
+    ) -> Result<(), Erc721Error> {
+        let previous_owner = self.owner.get();
+        if !previous_owner.is_zero() {
+            self.only_owner_unsealed()?;
+        }
+        if self.initialized.get() {
+            return Err(Erc721Error::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.erc721.set_base_uri(base_uri.clone())?;
+        self.mint_price.set(mint_price);
+        // 新所有者默认拥有管理员和铸造者角色
+        self.owner.set(owner);
+        self.grant_role_internal(DEFAULT_ADMIN_ROLE, owner);
+        self.grant_role_internal(MINTER_ROLE, owner);
+        if previous_owner != owner {
+            log(
+                self.vm(),
+                OwnershipTransferred {
+                    previous_owner,
+                    new_owner: owner,
+                },
+            );
+        }
+        log(
+            self.vm(),
+            Initialized {
+                owner,
+                base_uri,
+                mint_price,
+            },
+        );
+        Ok(())
+    }
+
+    // 获取合约是否已初始化
+    pub fn initialized(&self) -> Result<bool, Erc721Error> {
+        Ok(self.initialized.get())
+    }
+
+    // 获取合约所有者
+    pub fn owner(&self) -> Result<Address, Erc721Error> {
+        Ok(self.owner.get())
+    }
+
+    // 获取等待接收所有权的地址
+    pub fn pending_owner(&self) -> Result<Address, Erc721Error> {
+        Ok(self.pending_owner.get())
+    }
+
+    // 发起所有权转移，需由新所有者调用 accept_ownership 完成
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), Erc721Error> {
+        self.only_owner()?;
+        self.pending_owner.set(new_owner);
+        log(
+            self.vm(),
+            OwnershipTransferStarted {
+                previous_owner: self.owner.get(),
+                new_owner,
+            },
+        );
+        Ok(())
+    }
+
+    // 待接收地址确认接收所有权
+    pub fn accept_ownership(&mut self) -> Result<(), Erc721Error> {
+        let new_owner = self.vm().msg_sender();
+        if new_owner != self.pending_owner.get() {
+            return Err(Erc721Error::NotPendingOwner(NotPendingOwner {
+                caller: new_owner,
+            }));
+        }
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        self.pending_owner.set(Address::ZERO);
+        log(
+            self.vm(),
+            OwnershipTransferred {
+                previous_owner,
+                new_owner,
+            },
+        );
+        Ok(())
+    }
+
+    // 获取管理员角色 ID
+    #[selector(name = "DEFAULT_ADMIN_ROLE")]
+    pub fn default_admin_role(&self) -> Result<FixedBytes<32>, Erc721Error> {
+        Ok(DEFAULT_ADMIN_ROLE)
+    }
+
+    // 获取铸造者角色 ID
+    #[selector(name = "MINTER_ROLE")]
+    pub fn minter_role(&self) -> Result<FixedBytes<32>, Erc721Error> {
+        Ok(MINTER_ROLE)
+    }
+
+    // 检查账户是否拥有指定角色
+    pub fn has_role(&self, role: FixedBytes<32>, account: Address) -> Result<bool, Erc721Error> {
+        Ok(self.roles.getter(role).get(account))
+    }
+
+    // 授予角色，仅管理员可调用
+    pub fn grant_role(
+        &mut self,
+        role: FixedBytes<32>,
+        account: Address,
+    ) -> Result<(), Erc721Error> {
+        self.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.grant_role_internal(role, account);
+        Ok(())
+    }
+
+    // 撤销角色，仅管理员可调用
+    pub fn revoke_role(
+        &mut self,
+        role: FixedBytes<32>,
+        account: Address,
+    ) -> Result<(), Erc721Error> {
+        self.only_role(DEFAULT_ADMIN_ROLE)?;
+        if self.roles.getter(role).get(account) {
+            self.roles.setter(role).insert(account, false);
+            log(
+                self.vm(),
+                RoleRevoked {
+                    role,
+                    account,
+                    sender: self.vm().msg_sender(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    // 铸造 NFT 给调用者，需支付铸造价格
+    #[payable]
     pub fn mint(&mut self) -> Result<(), Erc721Error> {
         // 获取调用者地址
-        let minter = msg::sender();
+        let minter = self.vm().msg_sender();
+        self.throttle_mint(minter)?;
+        // 按铸造前的供应量确定价格
+        let price = self.current_price()?;
         // 调用 erc721 的 mint 方法
+        self.mint_paid(minter, price)?;
+        // 收取费用并退还多付金额
+        self.collect_payment(price)
+    }
+
+    // 使用已配置的 ERC-20 代币支付铸造 NFT 给调用者，调用者需预先授权本合约扣款
+    pub fn mint_with_erc20(&mut self) -> Result<(), Erc721Error> {
+        let minter = self.vm().msg_sender();
+        self.throttle_mint(minter)?;
+        let token = self.payment_token.get();
+        let price = self.erc20_mint_price.get();
+        if token.is_zero() {
+            return Err(Erc721Error::PaymentFailed(PaymentFailed {
+                token,
+                amount: price,
+            }));
+        }
+        // 授权额度不足时提前回滚，给出明确错误
+        let call = IERC20::allowanceCall {
+            owner: minter,
+            spender: self.vm().contract_address(),
+        };
+        let allowance = self
+            .vm()
+            .static_call(&Call::new(), token, &call.abi_encode())
+            .ok()
+            .and_then(|returned| IERC20::allowanceCall::abi_decode_returns(&returned, true).ok())
+            .map_or(U256::ZERO, |returned| returned._0);
+        if allowance < price {
+            return Err(Erc721Error::InsufficientAllowance(InsufficientAllowance {
+                allowance,
+                required: price,
+            }));
+        }
+        // 先扣款再铸造
+        let call = IERC20::transferFromCall {
+            from: minter,
+            to: self.vm().contract_address(),
+            amount: price,
+        };
+        let success = self
+            .vm()
+            .call(&Call::new(), token, &call.abi_encode())
+            .ok()
+            .and_then(|returned| IERC20::transferFromCall::abi_decode_returns(&returned, true).ok())
+            .is_some_and(|returned| returned._0);
+        if !success {
+            return Err(Erc721Error::PaymentFailed(PaymentFailed {
+                token,
+                amount: price,
+            }));
+        }
         self.erc721.mint(minter)?;
         Ok(())
     }
 
-    // 铸造 NFT 给指定地址
+    // 设置 ERC-20 铸造的支付代币和价格，代币为零地址表示关闭，仅合约所有者可调用
+    pub fn set_erc20_payment(&mut self, token: Address, price: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.payment_token.set(token);
+        self.erc20_mint_price.set(price);
+        Ok(())
+    }
+
+    // 获取 ERC-20 铸造的支付代币
+    pub fn payment_token(&self) -> Result<Address, Erc721Error> {
+        Ok(self.payment_token.get())
+    }
+
+    // 获取以支付代币计价的铸造价格
+    pub fn erc20_mint_price(&self) -> Result<U256, Erc721Error> {
+        Ok(self.erc20_mint_price.get())
+    }
+
+    // 通过推荐人铸造 NFT 给调用者，需支付铸造价格，推荐人累计推荐次数
+    #[payable]
+    pub fn mint_with_referrer(&mut self, referrer: Address) -> Result<(), Erc721Error> {
+        let minter = self.vm().msg_sender();
+        // 禁止推荐自己
+        if referrer == minter {
+            return Err(Erc721Error::SelfReferral(SelfReferral { account: minter }));
+        }
+        self.throttle_mint(minter)?;
+        let price = self.current_price()?;
+        let token_id = self.mint_paid(minter, price)?;
+        // 零地址推荐人不计入推荐次数
+        if !referrer.is_zero() {
+            self.referrals
+                .insert(referrer, self.referrals.get(referrer) + U256::from(1));
+            log(
+                self.vm(),
+                Referral {
+                    referrer,
+                    minter,
+                    token_id,
+                },
+            );
+        }
+        self.collect_payment(price)
+    }
+
+    // 获取地址最近一次公开铸造所在的区块号
+    pub fn last_mint_block(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.last_mint_block.get(account))
+    }
+
+    // 获取推荐人成功推荐铸造的次数
+    pub fn referrals_of(&self, referrer: Address) -> Result<U256, Erc721Error> {
+        Ok(self.referrals.get(referrer))
+    }
+
+    // 批量铸造 count 个 NFT 给调用者，需支付 count 倍铸造价格
+    #[payable]
+    pub fn mint_batch(&mut self, count: U256) -> Result<(), Erc721Error> {
+        // 限制单笔交易的铸造数量
+        if count > U256::from(MAX_PER_TX) {
+            return Err(Erc721Error::TxLimitExceeded(TxLimitExceeded {
+                requested: count,
+                limit: U256::from(MAX_PER_TX),
+            }));
+        }
+        Erc721::<StylusNFTParams>::check_batch_len(count.saturating_to())?;
+        let minter = self.vm().msg_sender();
+        self.throttle_mint(minter)?;
+        let mut minted = U256::ZERO;
+        let mut price = U256::ZERO;
+        while minted < count {
+            // 逐个累加价格，联合曲线下每次铸造都会抬高下一个价格
+            let unit_price = self.current_price()?;
+            price = price.saturating_add(unit_price);
+            self.mint_paid(minter, unit_price)?;
+            minted += U256::from(1);
+        }
+        // 收取费用并退还多付金额
+        self.collect_payment(price)
+    }
+
+    // 使用合约所有者签名的按序授权铸造，nonce 必须等于调用者当前的 nonce
+    #[payable]
+    pub fn mint_with_nonce(
+        &mut self,
+        nonce: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        let minter = self.vm().msg_sender();
+        // 验证 nonce 顺序，防止重放和乱序
+        let expected = self.mint_nonces.get(minter);
+        if nonce != expected {
+            return Err(Erc721Error::BadNonce(BadNonce {
+                account: minter,
+                expected,
+                provided: nonce,
+            }));
+        }
+        // 验证授权由合约所有者签名
+        let struct_hash = keccak((keccak(MINT_AUTHORIZATION_TYPE), minter, nonce).abi_encode());
+        let signer = self.erc721.recover_typed_signer(struct_hash, v, r, s)?;
+        if signer != self.owner.get() {
+            return Err(Erc721Error::InvalidSignature(InvalidSignature {}));
+        }
+        self.mint_nonces.insert(minter, nonce + U256::from(1));
+        // 按铸造前的供应量确定价格
+        let price = self.current_price()?;
+        self.mint_paid(minter, price)?;
+        // 收取费用并退还多付金额
+        self.collect_payment(price)
+    }
+
+    // 使用合约所有者签名的带期限授权铸造，过期后授权失效，每个地址只能使用一次
+    #[payable]
+    pub fn mint_signed(
+        &mut self,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        let minter = self.vm().msg_sender();
+        if self.signed_minted.get(minter) {
+            return Err(Erc721Error::AlreadyClaimed(AlreadyClaimed {
+                account: minter,
+            }));
+        }
+        // 验证授权未过期且由合约所有者签名
+        let struct_hash = keccak((keccak(SIGNED_MINT_TYPE), minter, deadline).abi_encode());
+        let signer = self.erc721.recover_signer(struct_hash, deadline, v, r, s)?;
+        if signer != self.owner.get() {
+            return Err(Erc721Error::InvalidSignature(InvalidSignature {}));
+        }
+        self.signed_minted.insert(minter, true);
+        // 按铸造前的供应量确定价格
+        let price = self.current_price()?;
+        self.mint_paid(minter, price)?;
+        // 收取费用并退还多付金额
+        self.collect_payment(price)
+    }
+
+    // 检查地址是否已使用过带期限的铸造授权
+    pub fn has_signed_minted(&self, account: Address) -> Result<bool, Erc721Error> {
+        Ok(self.signed_minted.get(account))
+    }
+
+    // 获取地址下一个按序铸造 nonce
+    pub fn mint_nonce(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.mint_nonces.get(account))
+    }
+
+    // 获取铸造价格
+    pub fn mint_price(&self) -> Result<U256, Erc721Error> {
+        Ok(self.mint_price.get())
+    }
+
+    // 获取联合曲线的斜率
+    pub fn price_slope(&self) -> Result<U256, Erc721Error> {
+        Ok(self.price_slope.get())
+    }
+
+    // 获取当前单个 token 的铸造价格
+    // 启用拍卖时按时间线性递减，否则为基础价格加斜率乘以现存供应量
+    pub fn current_price(&self) -> Result<U256, Erc721Error> {
+        let duration = self.auction_duration.get();
+        if duration.is_zero() {
+            let supply = self.erc721.total_supply.get();
+            return Ok(self
+                .mint_price
+                .get()
+                .saturating_add(self.price_slope.get().saturating_mul(supply)));
+        }
+        let start_price = self.auction_start_price.get();
+        let end_price = self.auction_end_price.get();
+        let start_time = self.auction_start_time.get();
+        let now = U256::from(self.vm().block_timestamp());
+        // 拍卖开始前按起始价格，结束后保持结束价格
+        if now <= start_time {
+            return Ok(start_price);
+        }
+        let elapsed = now - start_time;
+        let price = if elapsed >= duration {
+            end_price
+        } else {
+            start_price - (start_price - end_price) * elapsed / duration
+        };
+        // 价格不低于保留价
+        Ok(price.max(self.reserve_price.get()))
+    }
+
+    // 设置荷兰式拍卖参数，仅所有者可调用，duration 为 0 时关闭拍卖
+    pub fn set_auction(
+        &mut self,
+        start_price: U256,
+        end_price: U256,
+        start_time: U256,
+        duration: U256,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        if end_price > start_price {
+            return Err(Erc721Error::InvalidAuctionConfig(InvalidAuctionConfig {
+                start_price,
+                end_price,
+            }));
+        }
+        let reserve_price = self.reserve_price.get();
+        if end_price < reserve_price {
+            return Err(Erc721Error::BelowReserve(BelowReserve {
+                end_price,
+                reserve_price,
+            }));
+        }
+        self.auction_start_price.set(start_price);
+        self.auction_end_price.set(end_price);
+        self.auction_start_time.set(start_time);
+        self.auction_duration.set(duration);
+        Ok(())
+    }
+
+    // 设置荷兰式拍卖的保留价，已启用的拍卖结束价格低于保留价时回滚，仅所有者可调用
+    pub fn set_reserve_price(&mut self, reserve_price: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        let end_price = self.auction_end_price.get();
+        if !self.auction_duration.get().is_zero() && end_price < reserve_price {
+            return Err(Erc721Error::BelowReserve(BelowReserve {
+                end_price,
+                reserve_price,
+            }));
+        }
+        self.reserve_price.set(reserve_price);
+        Ok(())
+    }
+
+    // 获取荷兰式拍卖的保留价
+    pub fn reserve_price(&self) -> Result<U256, Erc721Error> {
+        Ok(self.reserve_price.get())
+    }
+
+    // 揭示元数据，仅所有者可调用
+    // 种子由区块号、时间戳和已铸造数量混合而成，只能防止提前预知，无法抵抗出块者操纵
+    pub fn reveal(&mut self) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        let seed = keccak(
+            (
+                U256::from(self.vm().block_number()),
+                U256::from(self.vm().block_timestamp()),
+                self.erc721.next_token_id.get(),
+            )
+                .abi_encode(),
+        );
+        self.erc721.reveal(U256::from_be_bytes(seed.0))
+    }
+
+    // 获取元数据来源证明哈希
+    pub fn provenance_hash(&self) -> Result<FixedBytes<32>, Erc721Error> {
+        Ok(self.provenance_hash.get())
+    }
+
+    // 设置元数据来源证明哈希，仅所有者可调用且只能设置一次
+    pub fn set_provenance_hash(&mut self, hash: FixedBytes<32>) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        if !self.provenance_hash.get().is_zero() {
+            return Err(Erc721Error::ProvenanceAlreadySet(ProvenanceAlreadySet {}));
+        }
+        self.provenance_hash.set(hash);
+        Ok(())
+    }
+
+    // 创建余额快照，仅所有者可调用
+    pub fn snapshot(&mut self) -> Result<U256, Erc721Error> {
+        self.only_owner()?;
+        Ok(self.erc721.snapshot())
+    }
+
+    // 设置铸造价格，仅所有者可调用
+    pub fn set_mint_price(&mut self, price: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.mint_price.set(price);
+        Ok(())
+    }
+
+    // 设置联合曲线的基础价格和斜率，仅所有者可调用，slope 为 0 时为固定价格
+    pub fn set_bonding_curve(&mut self, base_price: U256, slope: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.mint_price.set(base_price);
+        self.price_slope.set(slope);
+        Ok(())
+    }
+
+    // 铸造 NFT 给指定地址，仅铸造者角色可调用
     pub fn mint_to(&mut self, to: Address) -> Result<(), Erc721Error> {
+        self.only_role(MINTER_ROLE)?;
         // 调用 erc721 的 mint 方法
         self.erc721.mint(to)?;
         Ok(())
     }
 
+    // 铸造 NFT 给指定地址并附带应用数据，仅铸造者角色可调用
+    pub fn mint_with_data(&mut self, to: Address, data: Bytes) -> Result<U256, Erc721Error> {
+        self.only_role(MINTER_ROLE)?;
+        if data.len() > MAX_TOKEN_DATA_LEN {
+            return Err(Erc721Error::TokenDataTooLong(TokenDataTooLong {
+                len: U256::from(data.len()),
+                max: U256::from(MAX_TOKEN_DATA_LEN),
+            }));
+        }
+        let token_id = self.erc721.mint(to)?;
+        self.token_data.setter(token_id).set_bytes(data.0);
+        Ok(token_id)
+    }
+
+    // 获取 token 铸造时附带的应用数据，未附带时返回空
+    pub fn token_data(&self, token_id: U256) -> Result<Bytes, Erc721Error> {
+        Ok(Bytes(self.token_data.getter(token_id).get_bytes()))
+    }
+
+    // 连续铸造 quantity 个 NFT 给指定地址，仅铸造者角色可调用
+    pub fn mint_consecutive(&mut self, to: Address, quantity: U256) -> Result<(), Erc721Error> {
+        self.only_role(MINTER_ROLE)?;
+        self.erc721.mint_consecutive(to, quantity)?;
+        Ok(())
+    }
+
+    // 设置托管合约地址，仅所有者可调用
+    pub fn set_vault(&mut self, vault: Address) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.vault.set(vault);
+        Ok(())
+    }
+
+    // 获取托管合约地址
+    pub fn vault(&self) -> Result<Address, Erc721Error> {
+        Ok(self.vault.get())
+    }
+
+    // 铸造 NFT 到托管合约并记录实际受益人，仅铸造者角色可调用
+    pub fn mint_to_vault(&mut self, user: Address) -> Result<U256, Erc721Error> {
+        self.only_role(MINTER_ROLE)?;
+        let vault = self.vault.get();
+        if vault.is_zero() {
+            return Err(Erc721Error::VaultNotSet(VaultNotSet {}));
+        }
+        let token_id = self.erc721.mint(vault)?;
+        self.beneficial_owners.insert(token_id, user);
+        log(
+            self.vm(),
+            VaultMinted {
+                vault,
+                user,
+                token_id,
+            },
+        );
+        Ok(token_id)
+    }
+
+    // 获取托管中 token 的实际受益人，未托管时返回零地址
+    pub fn beneficial_owner(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        Ok(self.beneficial_owners.get(token_id))
+    }
+
+    // 将托管中的 token 转给实际受益人，仅托管合约可调用
+    pub fn release(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        let vault = self.vault.get();
+        let user = self.beneficial_owners.get(token_id);
+        if vault.is_zero() || self.vm().msg_sender() != vault {
+            return Err(Erc721Error::NotVault(NotVault {
+                caller: self.vm().msg_sender(),
+            }));
+        }
+        // 不在托管中的 token 无受益人可释放
+        if user.is_zero() {
+            return Err(Erc721Error::InvalidTokenId(InvalidTokenId { token_id }));
+        }
+        self.beneficial_owners.delete(token_id);
+        self.erc721.transfer(token_id, vault, user)?;
+        log(
+            self.vm(),
+            VaultReleased {
+                vault,
+                user,
+                token_id,
+            },
+        );
+        Ok(())
+    }
+
+    // 设置默认操作者，持有者可通过 set_approval_for_all 退出，仅所有者可调用
+    pub fn set_default_operator(&mut self, operator: Address) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.set_default_operator(operator);
+        Ok(())
+    }
+
+    // 设置 KYC 验证者，仅所有者可调用
+    pub fn set_kyc_verifier(
+        &mut self,
+        verifier: Address,
+        allowed: bool,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.kyc_verifiers.insert(verifier, allowed);
+        Ok(())
+    }
+
+    // 检查地址是否为 KYC 验证者
+    pub fn is_kyc_verifier(&self, account: Address) -> Result<bool, Erc721Error> {
+        Ok(self.kyc_verifiers.get(account))
+    }
+
+    // 设置地址的 KYC 验证状态，所有者或 KYC 验证者可调用
+    pub fn set_kyc(&mut self, account: Address, verified: bool) -> Result<(), Erc721Error> {
+        self.only_kyc_verifier()?;
+        self.erc721.set_kyc(account, verified);
+        Ok(())
+    }
+
+    // 开启或关闭 KYC 要求，开启后铸造和转账的双方均需已验证，仅所有者可调用
+    pub fn set_kyc_required(&mut self, required: bool) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.set_kyc_required(required);
+        Ok(())
+    }
+
+    // 设置已暂停操作的位掩码，可单独暂停铸造、转账、授权或销毁，仅所有者可调用
+    pub fn set_paused(&mut self, flags: u8) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.set_paused(flags);
+        Ok(())
+    }
+
+    // 分段揭示指定 token 区间，仅所有者可调用
+    pub fn reveal_range(
+        &mut self,
+        start: U256,
+        end: U256,
+        base_uri: String,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.reveal_range(start, end, base_uri)
+    }
+
+    // 批量设置 token 的专属 URI，仅所有者可调用
+    pub fn set_token_uri_batch(
+        &mut self,
+        token_ids: Vec<U256>,
+        uris: Vec<String>,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.set_token_uri_batch(token_ids, uris)
+    }
+
+    // 冻结单个 token 的元数据，其他 token 仍可修改，仅所有者可调用
+    pub fn freeze_token(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.freeze_token(token_id)
+    }
+
+    // 以二进制形式设置 token 的 IPFS CID，URI 由基础 URI 加 base58 编码的 CID 组成，
+    // 为空时清除，仅所有者可调用
+    pub fn set_token_cid(&mut self, token_id: U256, cid: Bytes) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.set_token_cid(token_id, cid.0)
+    }
+
+    // 设置 token 的链上属性，链上模式下写入 JSON 元数据，仅所有者可调用
+    pub fn set_attribute(
+        &mut self,
+        token_id: U256,
+        key: String,
+        value: String,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.set_attribute(token_id, key, value)
+    }
+
+    // 设置基础 URI 后追加的后缀，可为空，仅所有者可调用
+    pub fn set_uri_suffix(&mut self, suffix: String) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.set_uri_suffix(suffix);
+        Ok(())
+    }
+
+    // 设置已销毁 token 返回的墓碑 URI，为空表示关闭，仅所有者可调用
+    pub fn set_tombstone_uri(&mut self, uri: String) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.set_tombstone_uri(uri)
+    }
+
+    // 递增元数据版本号，使生成的 URI 变化以刷新网关缓存，仅所有者可调用
+    pub fn bump_metadata_version(&mut self) -> Result<U256, Erc721Error> {
+        self.only_owner_unsealed()?;
+        Ok(self.erc721.bump_metadata_version())
+    }
+
+    // 设置 token URI 的生成方式，仅所有者可调用
+    pub fn set_uri_mode(&mut self, mode: u8) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.set_uri_mode(mode)
+    }
+
+    // 铸造 NFT 给指定地址并设置专属 URI，仅所有者可调用
+    pub fn mint_to_with_uri(&mut self, to: Address, uri: String) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        let token_id = self.erc721.mint(to)?;
+        self.erc721.set_token_uri(token_id, uri)
+    }
+
+    // 设置 token 的可使用次数，仅铸造者角色可调用，持有者不能自行补充次数
+    pub fn set_uses(&mut self, token_id: U256, count: U256) -> Result<(), Erc721Error> {
+        self.only_role(MINTER_ROLE)?;
+        self.erc721.set_uses(token_id, count)
+    }
+
+    // 铸造带有效期的 NFT 给指定地址，仅所有者可调用
+    pub fn mint_timed(&mut self, to: Address, start: i64, end: i64) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        let token_id = self.erc721.mint(to)?;
+        self.erc721.set_time(token_id, start, end)?;
+        Ok(())
+    }
+
+    // 执行普通转账，启用手续费时需随调用支付
+    #[payable]
+    pub fn transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        self.check_self_hold(to, token_id)?;
+        self.check_contract_dest(to)?;
+        self.check_marketplace(from)?;
+        self.collect_transfer_fee(1)?;
+        self.erc721.transfer_from(from, to, token_id)?;
+        self.notify_sale_hook(from, to, token_id)
+    }
+
+    // 代他人提交签名转账，与普通转账经过相同的检查，启用手续费时需随调用支付
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_with_sig(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        self.check_self_hold(to, token_id)?;
+        self.check_contract_dest(to)?;
+        self.check_marketplace(from)?;
+        self.collect_transfer_fee(1)?;
+        self.erc721
+            .transfer_with_sig(from, to, token_id, deadline, v, r, s)?;
+        self.notify_sale_hook(from, to, token_id)
+    }
+
+    // 执行普通转账并记录备注事件，启用手续费时需随调用支付
+    #[payable]
+    pub fn transfer_from_with_memo(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        memo: String,
+    ) -> Result<(), Erc721Error> {
+        if memo.len() > MAX_MEMO_LEN {
+            return Err(Erc721Error::MemoTooLong(MemoTooLong {
+                len: U256::from(memo.len()),
+                max: U256::from(MAX_MEMO_LEN),
+            }));
+        }
+        self.transfer_from(from, to, token_id)?;
+        log(
+            self.vm(),
+            TransferMemo {
+                from,
+                to,
+                token_id,
+                memo,
+            },
+        );
+        Ok(())
+    }
+
+    // 执行带数据的安全转账，启用手续费时需随调用支付
+    #[payable]
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from_with_data(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), Erc721Error> {
+        self.check_self_hold(to, token_id)?;
+        self.check_contract_dest(to)?;
+        self.check_marketplace(from)?;
+        self.collect_transfer_fee(1)?;
+        Erc721::<StylusNFTParams>::safe_transfer_from_with_data(self, from, to, token_id, data)?;
+        self.notify_sale_hook(from, to, token_id)
+    }
+
+    // 执行不带数据的安全转账，启用手续费时需随调用支付
+    #[payable]
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        self.check_self_hold(to, token_id)?;
+        self.check_contract_dest(to)?;
+        self.check_marketplace(from)?;
+        self.collect_transfer_fee(1)?;
+        Erc721::<StylusNFTParams>::safe_transfer_from_with_data(
+            self,
+            from,
+            to,
+            token_id,
+            Bytes(Vec::new()),
+        )?;
+        self.notify_sale_hook(from, to, token_id)
+    }
+
+    // 批量执行安全转账，每个 token 单独回调 onERC721Received，
+    // 启用手续费时需按 token 数量支付
+    #[payable]
+    pub fn safe_transfer_from_batch(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_ids: Vec<U256>,
+        data: Bytes,
+    ) -> Result<(), Erc721Error> {
+        if let Some(&token_id) = token_ids.first() {
+            self.check_self_hold(to, token_id)?;
+        }
+        self.check_contract_dest(to)?;
+        self.check_marketplace(from)?;
+        self.collect_transfer_fee(token_ids.len())?;
+        Erc721::<StylusNFTParams>::safe_transfer_from_batch(
+            self,
+            from,
+            to,
+            token_ids.clone(),
+            data.0,
+        )?;
+        for token_id in token_ids {
+            self.notify_sale_hook(from, to, token_id)?;
+        }
+        Ok(())
+    }
+
+    // 设置转账回调合约及是否严格模式，零地址表示关闭回调，仅所有者可调用
+    pub fn set_sale_hook(&mut self, hook: Address, strict: bool) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.sale_hook.set(hook);
+        self.strict_hook.set(strict);
+        Ok(())
+    }
+
+    // 获取转账回调合约及是否严格模式
+    pub fn sale_hook(&self) -> Result<(Address, bool), Erc721Error> {
+        Ok((self.sale_hook.get(), self.strict_hook.get()))
+    }
+
+    // 设置是否允许本合约持有自身的 token，仅所有者可调用
+    pub fn set_allow_self_hold(&mut self, allowed: bool) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.allow_self_hold.set(allowed);
+        Ok(())
+    }
+
+    // 获取是否允许本合约持有自身的 token
+    pub fn allow_self_hold(&self) -> Result<bool, Erc721Error> {
+        Ok(self.allow_self_hold.get())
+    }
+
+    // 设置合约地址是否可接收 token，仅所有者可调用
+    pub fn set_dest_whitelisted(
+        &mut self,
+        dest: Address,
+        allowed: bool,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.dest_whitelist.insert(dest, allowed);
+        Ok(())
+    }
+
+    // 开启或关闭合约接收方限制，开启后只能转入白名单合约，仅所有者可调用
+    pub fn set_restrict_contract_dest(&mut self, restricted: bool) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.restrict_contract_dest.set(restricted);
+        Ok(())
+    }
+
+    // 检查合约地址是否在接收方白名单中
+    pub fn is_dest_whitelisted(&self, dest: Address) -> Result<bool, Erc721Error> {
+        Ok(self.dest_whitelist.get(dest))
+    }
+
+    // 获取是否限制合约接收方
+    pub fn restrict_contract_dest(&self) -> Result<bool, Erc721Error> {
+        Ok(self.restrict_contract_dest.get())
+    }
+
+    // 设置调用 onERC721Received 时转发的 gas 上限，为 0 表示转发全部剩余 gas，仅所有者可调用
+    pub fn set_receiver_gas_limit(&mut self, gas_limit: u64) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.set_receiver_gas_limit(gas_limit);
+        Ok(())
+    }
+
+    // 开启或关闭交易市场限制，仅所有者可调用
+    pub fn set_restrict_transfers(&mut self, restricted: bool) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.restrict_transfers.set(restricted);
+        Ok(())
+    }
+
+    // 设置交易市场是否允许发起转账，仅所有者可调用
+    pub fn set_allowed_marketplace(
+        &mut self,
+        marketplace: Address,
+        allowed: bool,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.allowed_marketplaces.insert(marketplace, allowed);
+        Ok(())
+    }
+
+    // 检查交易市场是否允许发起转账
+    pub fn is_allowed_marketplace(&self, marketplace: Address) -> Result<bool, Erc721Error> {
+        Ok(self.allowed_marketplaces.get(marketplace))
+    }
+
+    // 设置转账手续费及是否启用，仅所有者可调用
+    pub fn set_transfer_fee(&mut self, fee: U256, enabled: bool) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.transfer_fee.set(fee);
+        self.transfer_fee_enabled.set(enabled);
+        Ok(())
+    }
+
+    // 获取当前每次转账需支付的手续费，未启用时为 0
+    pub fn transfer_fee(&self) -> Result<U256, Erc721Error> {
+        if !self.transfer_fee_enabled.get() {
+            return Ok(U256::ZERO);
+        }
+        Ok(self.transfer_fee.get())
+    }
+
     // 销毁指定 NFT
     pub fn burn(&mut self, token_id: U256) -> Result<(), Erc721Error> {
-        // 调用 erc721 的 burn 方法，验证调用者是否拥有 token
-        self.erc721.burn(msg::sender(), token_id)?;
+        // 销毁时验证调用者是否拥有 token
+        let owner = self.vm().msg_sender();
+        self.rewarded_burn(owner, token_id, owner)
+    }
+
+    // 凭拥有者签名销毁 token，由中继者代为提交，受限销毁模式下提交者需为销毁授权合约
+    pub fn burn_with_sig(
+        &mut self,
+        owner: Address,
+        token_id: U256,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        self.erc721
+            .consume_burn_sig(owner, token_id, deadline, v, r, s)?;
+        // 积分记给签名的拥有者而非中继者
+        self.rewarded_burn(owner, token_id, owner)
+    }
+
+    // 凭游戏签名者的授权代玩家销毁 token，nonce 必须等于玩家当前的 nonce，玩家无需支付 gas
+    pub fn burn_authorized(
+        &mut self,
+        player: Address,
+        token_id: U256,
+        nonce: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        // 验证 nonce 顺序，防止重放和乱序
+        let expected = self.game_burn_nonces.get(player);
+        if nonce != expected {
+            return Err(Erc721Error::BadNonce(BadNonce {
+                account: player,
+                expected,
+                provided: nonce,
+            }));
+        }
+        // 验证授权由已配置的游戏签名者签名
+        let struct_hash = keccak((keccak(GAME_BURN_TYPE), player, token_id, nonce).abi_encode());
+        let signer = self.erc721.recover_typed_signer(struct_hash, v, r, s)?;
+        let game_signer = self.game_signer.get();
+        if game_signer.is_zero() || signer != game_signer {
+            return Err(Erc721Error::InvalidSignature(InvalidSignature {}));
+        }
+        self.game_burn_nonces.insert(player, nonce + U256::from(1));
+        // 销毁时验证玩家确为拥有者，积分记给玩家而非提交者
+        self.rewarded_burn(player, token_id, player)
+    }
+
+    // 设置授权代玩家销毁的游戏签名者，零地址表示关闭，仅合约所有者可调用
+    pub fn set_game_signer(&mut self, signer: Address) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.game_signer.set(signer);
         Ok(())
     }
 
+    // 获取游戏签名者
+    pub fn game_signer(&self) -> Result<Address, Erc721Error> {
+        Ok(self.game_signer.get())
+    }
+
+    // 获取玩家下一个游戏授权销毁 nonce
+    pub fn game_burn_nonce(&self, player: Address) -> Result<U256, Erc721Error> {
+        Ok(self.game_burn_nonces.get(player))
+    }
+
+    // 批量销毁 token，调用者需为每个 token 的拥有者或被授权者（OWNER_ONLY_BURN 时需为拥有者），任一失败则整批回滚
+    pub fn burn_batch(&mut self, token_ids: Vec<U256>) -> Result<(), Erc721Error> {
+        Erc721::<StylusNFTParams>::check_batch_len(token_ids.len())?;
+        for token_id in token_ids {
+            // 重复的 token_id 在第一次销毁后已不存在，此处会返回 InvalidTokenId
+            let owner = self.erc721.require_authorized_to_burn(token_id)?;
+            self.rewarded_burn(owner, token_id, self.vm().msg_sender())?;
+        }
+        Ok(())
+    }
+
+    // 开启或关闭恢复模式，仅合约所有者可调用
+    pub fn set_recovery_mode(&mut self, enabled: bool) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.recovery_mode.set(enabled);
+        log(self.vm(), RecoveryModeSet { enabled });
+        Ok(())
+    }
+
+    // 获取是否处于恢复模式
+    pub fn recovery_mode(&self) -> Result<bool, Erc721Error> {
+        Ok(self.recovery_mode.get())
+    }
+
+    // 恢复模式下跳过授权检查强制转移 token，用于私钥丢失后的找回，仅合约所有者可调用
+    pub fn force_transfer(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        if !self.recovery_mode.get() {
+            return Err(Erc721Error::RecoveryModeDisabled(RecoveryModeDisabled {}));
+        }
+        // 禁止转移到零地址，销毁需使用 admin_burn
+        if to.is_zero() {
+            return Err(Erc721Error::TransferToZero(TransferToZero { token_id }));
+        }
+        self.erc721.transfer(token_id, from, to)?;
+        log(
+            self.vm(),
+            ForcedTransfer {
+                from,
+                to,
+                token_id,
+                operator: self.vm().msg_sender(),
+            },
+        );
+        Ok(())
+    }
+
+    // 恢复模式下代持有者设置操作者授权，用于在强制转移前为丢失私钥的持有者指定新操作者，
+    // 不受授权暂停限制，仅合约所有者可调用
+    pub fn force_set_approval_for_all(
+        &mut self,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        if !self.recovery_mode.get() {
+            return Err(Erc721Error::RecoveryModeDisabled(RecoveryModeDisabled {}));
+        }
+        self.erc721
+            .write_operator_approval(owner, operator, approved);
+        log(
+            self.vm(),
+            ForcedApprovalForAll {
+                owner,
+                operator,
+                approved,
+                admin: self.vm().msg_sender(),
+            },
+        );
+        Ok(())
+    }
+
+    // 从旧合约迁移时为多个持有者批量写入同一操作者授权，封存迁移后不可调用，仅合约所有者可调用
+    pub fn seed_operator_approvals(
+        &mut self,
+        owners: Vec<Address>,
+        operator: Address,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        if self.migration_sealed.get() {
+            return Err(Erc721Error::MigrationSealed(MigrationSealed {}));
+        }
+        Erc721::<StylusNFTParams>::check_batch_len(owners.len())?;
+        for owner in owners {
+            self.erc721.write_operator_approval(owner, operator, true);
+        }
+        Ok(())
+    }
+
+    // 永久封存迁移，之后不可再批量写入操作者授权，仅合约所有者可调用
+    pub fn seal_migration(&mut self) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.migration_sealed.set(true);
+        Ok(())
+    }
+
+    // 获取迁移是否已封存
+    pub fn migration_sealed(&self) -> Result<bool, Erc721Error> {
+        Ok(self.migration_sealed.get())
+    }
+
+    // 永久封存配置，之后所有配置类函数回滚，铸造、转账和查询不受影响，不可撤销，仅合约所有者可调用
+    pub fn seal(&mut self) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.sealed.set(true);
+        log(
+            self.vm(),
+            Sealed {
+                owner: self.vm().msg_sender(),
+            },
+        );
+        Ok(())
+    }
+
+    // 获取配置是否已封存
+    pub fn sealed(&self) -> Result<bool, Erc721Error> {
+        Ok(self.sealed.get())
+    }
+
+    // 强制销毁任意 token，仅合约所有者可调用，不受持有关系、质押和锁定限制
+    pub fn admin_burn(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        let owner = self.erc721.force_burn(token_id)?;
+        self.clear_paid_mint(token_id);
+        log(self.vm(), AdminBurn { owner, token_id });
+        Ok(())
+    }
+
+    // 设置每销毁一个 token 奖励的积分，仅合约所有者可调用
+    pub fn set_points_per_burn(&mut self, points: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.points_per_burn.set(points);
+        Ok(())
+    }
+
+    // 获取每销毁一个 token 奖励的积分
+    pub fn points_per_burn(&self) -> Result<U256, Erc721Error> {
+        Ok(self.points_per_burn.get())
+    }
+
+    // 获取地址累计的销毁积分
+    pub fn points_of(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.points.get(account))
+    }
+
+    // 设置销毁授权合约，仅合约所有者可调用
+    pub fn set_burn_authority(
+        &mut self,
+        account: Address,
+        allowed: bool,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.burn_authorities.insert(account, allowed);
+        Ok(())
+    }
+
+    // 开启或关闭受限销毁模式，仅合约所有者可调用
+    pub fn set_restricted_burn(&mut self, restricted: bool) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.restricted_burn.set(restricted);
+        Ok(())
+    }
+
+    // 检查地址是否为销毁授权合约
+    pub fn is_burn_authority(&self, account: Address) -> Result<bool, Erc721Error> {
+        Ok(self.burn_authorities.get(account))
+    }
+
+    // 获取是否处于受限销毁模式
+    pub fn restricted_burn(&self) -> Result<bool, Erc721Error> {
+        Ok(self.restricted_burn.get())
+    }
+
+    // 销毁 token 兑换实物，调用者需为拥有者或被授权者（OWNER_ONLY_BURN 时需为拥有者）
+    pub fn redeem(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.erc721.require_authorized_to_burn(token_id)?;
+        self.rewarded_burn(owner, token_id, self.vm().msg_sender())?;
+        // 记录兑换者，供链下履约服务核对
+        let redeemer = self.vm().msg_sender();
+        self.redeemers.insert(token_id, redeemer);
+        log(self.vm(), Redeemed { redeemer, token_id });
+        Ok(())
+    }
+
+    // 设置铸造后允许退款的秒数，为 0 时关闭退款，仅合约所有者可调用
+    pub fn set_refund_window(&mut self, window: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.refund_window.set(window);
+        Ok(())
+    }
+
+    // 获取铸造后允许退款的秒数
+    pub fn refund_window(&self) -> Result<U256, Erc721Error> {
+        Ok(self.refund_window.get())
+    }
+
+    // 在退款期限内退还新铸造的 token，仅铸造者仍持有时可退，销毁后将铸造时支付的价格退给铸造者，
+    // 调用者需为拥有者或被授权者（OWNER_ONLY_BURN 时需为拥有者）
+    pub fn refund(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.erc721.require_authorized_to_burn(token_id)?;
+        // 未记录付费铸造、未开启退款或已超过期限时不可退款
+        let minter = self.paid_minters.get(token_id);
+        let window = self.refund_window.get();
+        let deadline = self.erc721.minted_at.get(token_id).saturating_add(window);
+        if minter.is_zero()
+            || window.is_zero()
+            || U256::from(self.vm().block_timestamp()) > deadline
+        {
+            return Err(Erc721Error::RefundWindowClosed(RefundWindowClosed {
+                token_id,
+                deadline,
+            }));
+        }
+        // token 已转给他人时不可退款
+        if owner != minter {
+            return Err(Erc721Error::NotOwner(NotOwner {
+                from: minter,
+                token_id,
+                real_owner: owner,
+            }));
+        }
+        // 先销毁并清除退款记录，再转出退款
+        let (_, amount) = self.clear_paid_mint(token_id);
+        self.guarded_burn(owner, token_id)?;
+        self.vm()
+            .transfer_eth(minter, amount)
+            .map_err(|_| Erc721Error::RefundFailed(RefundFailed { to: minter, amount }))?;
+        log(
+            self.vm(),
+            Refunded {
+                owner,
+                token_id,
+                amount,
+            },
+        );
+        Ok(())
+    }
+
+    // 获取已兑换 token 的兑换者，未兑换时返回零地址
+    pub fn redeemer_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        Ok(self.redeemers.get(token_id))
+    }
+
     // 获取总供应量
     pub fn total_supply(&mut self) -> Result<U256, Erc721Error> {
         // 获取 erc721 的总供应量
         Ok(self.erc721.total_supply.get())
     }
+
+    // 检查余额记账是否与现存供应量一致，开销较大，仅所有者可调用
+    pub fn verify_invariants(&self) -> Result<bool, Erc721Error> {
+        self.only_owner()?;
+        Ok(self.erc721.balances_consistent())
+    }
+
+    // 空投：按顺序为每个接收者铸造一个新 token，仅所有者可调用
+    // 任一铸造超过最大供应量都会使整个批次回滚
+    pub fn airdrop(&mut self, recipients: Vec<Address>) -> Result<(), Erc721Error> {
+        self.only_owner()?;
+        Erc721::<StylusNFTParams>::check_batch_len(recipients.len())?;
+        for recipient in recipients {
+            self.erc721.mint(recipient)?;
+        }
+        Ok(())
+    }
+
+    // 批量设置白名单地址，仅所有者可调用
+    pub fn set_allowlist(&mut self, addrs: Vec<Address>, allowed: bool) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        Erc721::<StylusNFTParams>::check_batch_len(addrs.len())?;
+        for addr in addrs {
+            self.allowlisted.insert(addr, allowed);
+        }
+        Ok(())
+    }
+
+    // 调用者加入候补名单，每个地址只能加入一次
+    pub fn join_waitlist(&mut self) -> Result<U256, Erc721Error> {
+        let account = self.vm().msg_sender();
+        let position = self.waitlist_positions.get(account);
+        if !position.is_zero() {
+            return Err(Erc721Error::AlreadyWaitlisted(AlreadyWaitlisted {
+                account,
+                position,
+            }));
+        }
+        self.waitlist.push(account);
+        let position = U256::from(self.waitlist.len());
+        self.waitlist_positions.insert(account, position);
+        log(self.vm(), WaitlistJoined { account, position });
+        Ok(position)
+    }
+
+    // 按加入顺序为候补名单中接下来的 count 个地址各铸造一个 token，不足时全部处理，
+    // 返回实际处理的数量，仅所有者可调用
+    pub fn process_waitlist(&mut self, count: U256) -> Result<U256, Erc721Error> {
+        self.only_owner()?;
+        Erc721::<StylusNFTParams>::check_batch_len(count.saturating_to())?;
+        let head = self.waitlist_head.get();
+        let end = head
+            .saturating_add(count)
+            .min(U256::from(self.waitlist.len()));
+        let mut index = head;
+        while index < end {
+            let account = self.waitlist.get(index).unwrap_or_default();
+            self.erc721.mint(account)?;
+            index += U256::from(1);
+        }
+        self.waitlist_head.set(end);
+        Ok(end - head)
+    }
+
+    // 获取地址在候补名单中的位置，从 1 开始，未加入时为 0，已处理的地址保留原位置
+    pub fn waitlist_position(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.waitlist_positions.get(account))
+    }
+
+    // 获取候补名单中尚未处理的地址数量
+    pub fn waitlist_length(&self) -> Result<U256, Erc721Error> {
+        Ok(U256::from(self.waitlist.len()) - self.waitlist_head.get())
+    }
+
+    // 设置免费铸造的总量上限，为 0 时关闭免费铸造，仅所有者可调用
+    pub fn set_free_mint_cap(&mut self, cap: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.free_mint_cap.set(cap);
+        Ok(())
+    }
+
+    // 先到先得地免费铸造一个 NFT 给调用者，每个地址只能领取一次
+    pub fn free_mint(&mut self) -> Result<(), Erc721Error> {
+        let minter = self.vm().msg_sender();
+        if self.claimed_free.get(minter) {
+            return Err(Erc721Error::AlreadyClaimedFree(AlreadyClaimedFree {
+                account: minter,
+            }));
+        }
+        let cap = self.free_mint_cap.get();
+        let minted = self.free_minted.get();
+        if minted >= cap {
+            return Err(Erc721Error::FreeMintEnded(FreeMintEnded { cap }));
+        }
+        self.claimed_free.insert(minter, true);
+        self.free_minted.set(minted + U256::from(1));
+        self.erc721.mint(minter)?;
+        Ok(())
+    }
+
+    // 获取剩余的免费铸造数量
+    pub fn free_mint_remaining(&self) -> Result<U256, Erc721Error> {
+        Ok(self
+            .free_mint_cap
+            .get()
+            .saturating_sub(self.free_minted.get()))
+    }
+
+    // 检查地址是否已领取免费铸造
+    pub fn has_claimed_free(&self, account: Address) -> Result<bool, Erc721Error> {
+        Ok(self.claimed_free.get(account))
+    }
+
+    // 白名单地址铸造 NFT 给自己
+    pub fn mint_allowlisted(&mut self) -> Result<(), Erc721Error> {
+        let minter = self.vm().msg_sender();
+        // 验证调用者在白名单中
+        if !self.allowlisted.get(minter) {
+            return Err(Erc721Error::NotAllowlisted(NotAllowlisted {
+                account: minter,
+            }));
+        }
+        // 验证调用者仍有剩余额度
+        let claimed = self.allowlist_claimed.get(minter);
+        if claimed >= U256::from(ALLOWLIST_MINT_LIMIT) {
+            return Err(Erc721Error::AllowlistExhausted(AllowlistExhausted {
+                account: minter,
+            }));
+        }
+        // 扣减额度后铸造
+        self.allowlist_claimed
+            .insert(minter, claimed + U256::from(1));
+        self.erc721.mint(minter)?;
+        Ok(())
+    }
+
+    // 获取指定地址剩余的白名单铸造额度
+    pub fn allowlist_remaining(&self, account: Address) -> Result<U256, Erc721Error> {
+        if !self.allowlisted.get(account) {
+            return Ok(U256::ZERO);
+        }
+        Ok(U256::from(ALLOWLIST_MINT_LIMIT).saturating_sub(self.allowlist_claimed.get(account)))
+    }
+
+    // 为地址设置铸造额度，覆盖原有额度，仅所有者可调用
+    pub fn grant_mint_allowance(
+        &mut self,
+        account: Address,
+        allowance: U256,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.mint_allowance.insert(account, allowance);
+        Ok(())
+    }
+
+    // 获取地址剩余的铸造额度
+    pub fn mint_allowance_of(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.mint_allowance.get(account))
+    }
+
+    // 使用所有者授予的额度铸造 quantity 个 NFT 给调用者
+    pub fn mint_from_allowance(&mut self, quantity: U256) -> Result<(), Erc721Error> {
+        let minter = self.vm().msg_sender();
+        let allowance = self.mint_allowance.get(minter);
+        // 验证调用者有足够的额度
+        if allowance.is_zero() {
+            return Err(Erc721Error::NoAllowance(NoAllowance { account: minter }));
+        }
+        if quantity > allowance {
+            return Err(Erc721Error::AllowanceExceeded(AllowanceExceeded {
+                account: minter,
+                requested: quantity,
+                allowance,
+            }));
+        }
+        Erc721::<StylusNFTParams>::check_batch_len(quantity.saturating_to())?;
+        // 扣减额度后铸造
+        self.mint_allowance.insert(minter, allowance - quantity);
+        for _ in 0..quantity.to::<usize>() {
+            self.erc721.mint(minter)?;
+        }
+        Ok(())
+    }
+
+    // 存入外部集合的 NFT，并铸造对应的包装 token 给调用者
+    pub fn wrap(
+        &mut self,
+        external_collection: Address,
+        external_id: U256,
+    ) -> Result<(), Erc721Error> {
+        let owner = self.vm().msg_sender();
+        let transfer_failed = Erc721Error::ExternalTransferFailed(ExternalTransferFailed {
+            collection: external_collection,
+            token_id: external_id,
+        });
+        // 将外部 token 转入本合约托管
+        let call = IERC721::transferFromCall {
+            from: owner,
+            to: self.vm().contract_address(),
+            token_id: external_id,
+        };
+        if self
+            .vm()
+            .call(&Call::new(), external_collection, &call.abi_encode())
+            .is_err()
+        {
+            return Err(transfer_failed);
+        }
+        // 转入后确认本合约已成为拥有者，防止空实现或伪造的集合铸造出无抵押的包装 token
+        let call = IERC721::ownerOfCall {
+            token_id: external_id,
+        };
+        let custodian = self
+            .vm()
+            .static_call(&Call::new(), external_collection, &call.abi_encode())
+            .ok()
+            .and_then(|data| IERC721::ownerOfCall::abi_decode_returns(&data, true).ok())
+            .map(|ret| ret._0);
+        if custodian != Some(self.vm().contract_address()) {
+            return Err(transfer_failed);
+        }
+        // 铸造包装 token 并记录对应关系
+        let wrapper_id = self.erc721.mint(owner)?;
+        let mut wrapped = self.wrapped.setter(wrapper_id);
+        wrapped.collection.set(external_collection);
+        wrapped.token_id.set(external_id);
+        log(
+            self.vm(),
+            Wrapped {
+                owner,
+                collection: external_collection,
+                external_id,
+                wrapper_id,
+            },
+        );
+        Ok(())
+    }
+
+    // 销毁包装 token，并将底层外部 NFT 归还给调用者
+    pub fn unwrap(&mut self, wrapper_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.vm().msg_sender();
+        let collection = self.wrapped.getter(wrapper_id).collection.get();
+        if collection.is_zero() {
+            return Err(Erc721Error::NotWrapped(NotWrapped { wrapper_id }));
+        }
+        let external_id = self.wrapped.getter(wrapper_id).token_id.get();
+        // 销毁时会验证调用者是否为包装 token 的拥有者
+        self.guarded_burn(owner, wrapper_id)?;
+        self.wrapped.delete(wrapper_id);
+        // 归还外部 token
+        let call = IERC721::transferFromCall {
+            from: self.vm().contract_address(),
+            to: owner,
+            token_id: external_id,
+        };
+        self.vm()
+            .call(&Call::new(), collection, &call.abi_encode())
+            .map_err(|_| {
+                Erc721Error::ExternalTransferFailed(ExternalTransferFailed {
+                    collection,
+                    token_id: external_id,
+                })
+            })?;
+        log(
+            self.vm(),
+            Unwrapped {
+                owner,
+                collection,
+                external_id,
+                wrapper_id,
+            },
+        );
+        Ok(())
+    }
+
+    // 获取包装 token 对应的外部集合地址和 token_id
+    pub fn wrapped_token(&self, wrapper_id: U256) -> Result<(Address, U256), Erc721Error> {
+        let wrapped = self.wrapped.getter(wrapper_id);
+        Ok((wrapped.collection.get(), wrapped.token_id.get()))
+    }
+
+    // 兑换创作者签名的懒铸造凭证，铸造指定 token 并将款项转给创作者
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn redeem_voucher(
+        &mut self,
+        to: Address,
+        token_id: U256,
+        uri: String,
+        price: U256,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        // 验证凭证由拥有铸造者角色的创作者签名
+        let struct_hash = keccak(
+            (
+                keccak(VOUCHER_TYPE),
+                to,
+                token_id,
+                keccak(uri.as_bytes()),
+                price,
+                deadline,
+            )
+                .abi_encode(),
+        );
+        let creator = self.erc721.recover_signer(struct_hash, deadline, v, r, s)?;
+        if !self.roles.getter(MINTER_ROLE).get(creator) {
+            return Err(Erc721Error::InvalidSignature(InvalidSignature {}));
+        }
+        // 铸造指定 token，已铸造时回滚，防止凭证重复使用
+        self.erc721.mint_id(to, token_id)?;
+        self.erc721.set_token_uri(token_id, uri)?;
+        // 收取款项并转给创作者
+        self.collect_payment(price)?;
+        if price > U256::ZERO {
+            self.vm().transfer_eth(creator, price).map_err(|_| {
+                Erc721Error::EthTransferFailed(EthTransferFailed {
+                    to: creator,
+                    amount: price,
+                })
+            })?;
+        }
+        Ok(())
+    }
+
+    // 设置 ERC-6551 注册表和账户实现地址，仅合约所有者可调用
+    pub fn set_account_registry(
+        &mut self,
+        registry: Address,
+        implementation: Address,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.account_registry.set(registry);
+        self.account_implementation.set(implementation);
+        Ok(())
+    }
+
+    // 获取 ERC-6551 注册表和账户实现地址
+    pub fn account_registry(&self) -> Result<(Address, Address), Erc721Error> {
+        Ok((
+            self.account_registry.get(),
+            self.account_implementation.get(),
+        ))
+    }
+
+    // 计算 token 的确定性绑定账户地址
+    pub fn account_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        Ok(erc6551::account_address(
+            self.account_registry.get(),
+            self.account_implementation.get(),
+            FixedBytes::ZERO,
+            U256::from(self.vm().chain_id()),
+            self.vm().contract_address(),
+            token_id,
+        ))
+    }
+
+    // 通过注册表创建 token 绑定账户，返回账户地址
+    pub fn create_account(&mut self, token_id: U256) -> Result<Address, Erc721Error> {
+        // 确保 token 存在
+        self.erc721.owner_of(token_id)?;
+        let registry = self.account_registry.get();
+        let implementation = self.account_implementation.get();
+        let call = IERC6551Registry::createAccountCall {
+            implementation,
+            salt: FixedBytes::ZERO,
+            chain_id: U256::from(self.vm().chain_id()),
+            token_contract: self.vm().contract_address(),
+            token_id,
+        };
+        self.vm()
+            .call(&Call::new(), registry, &call.abi_encode())
+            .ok()
+            .and_then(|returned| {
+                IERC6551Registry::createAccountCall::abi_decode_returns(&returned, true).ok()
+            })
+            .map(|returned| returned._0)
+            .ok_or(Erc721Error::AccountCreationFailed(AccountCreationFailed {
+                registry,
+                token_id,
+            }))
+    }
+
+    // 转出误转入本合约的 ERC-20 代币，仅合约所有者可调用
+    pub fn rescue_erc20(
+        &mut self,
+        token: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner()?;
+        // 本合约自身不是 ERC-20，不允许作为转出目标
+        let call = IERC20::transferCall { to, amount };
+        let success = token != self.vm().contract_address()
+            && self
+                .vm()
+                .call(&Call::new(), token, &call.abi_encode())
+                .ok()
+                .and_then(|returned| IERC20::transferCall::abi_decode_returns(&returned, true).ok())
+                .is_some_and(|returned| returned._0);
+        if !success {
+            return Err(Erc721Error::Erc20TransferFailed(Erc20TransferFailed {
+                token,
+                to,
+                amount,
+            }));
+        }
+        log(self.vm(), TokenRescued { token, to, amount });
+        Ok(())
+    }
+
+    // 接收不带调用数据的 ETH 转账并记录，带未知调用数据的调用仍会回滚
+    #[receive]
+    #[payable]
+    pub fn receive(&mut self) -> Result<(), Vec<u8>> {
+        // 设置收款人后，直接转入的 ETH 视为版税计入分账
+        self.splitter.deposit(self.vm().msg_value());
+        log(
+            self.vm(),
+            Received {
+                sender: self.vm().msg_sender(),
+                amount: self.vm().msg_value(),
+            },
+        );
+        Ok(())
+    }
+
+    // 提取合约 ETH 余额到指定地址，仅合约所有者可调用，不包含待分账的版税，
+    // 配置收益分配后忽略 to，按分配比例转给平台和创作者
+    pub fn withdraw(&mut self, to: Address) -> Result<(), Erc721Error> {
+        self.only_owner()?;
+        if !self.creator.get().is_zero() && !self.platform.get().is_zero() {
+            return self.split_proceeds();
+        }
+        self.release_expired_refunds();
+        let amount = self.proceeds_balance();
+        self.vm()
+            .transfer_eth(to, amount)
+            .map_err(|_| Erc721Error::EthTransferFailed(EthTransferFailed { to, amount }))?;
+        log(self.vm(), Withdrawn { to, amount });
+        Ok(())
+    }
+
+    // 获取合约当前的 ETH 余额，包含待分账的版税
+    pub fn contract_balance(&self) -> Result<U256, Erc721Error> {
+        Ok(self.vm().balance(self.vm().contract_address()))
+    }
+
+    // 获取地址当前可提取的金额，包括版税分账中的应得款项，
+    // 以及配置收益分配时创作者或平台按比例应得的合约余额
+    pub fn withdrawable(&self, account: Address) -> Result<U256, Erc721Error> {
+        let mut amount = self.splitter.releasable(account)?;
+        let creator = self.creator.get();
+        let platform = self.platform.get();
+        if account.is_zero() || creator.is_zero() || platform.is_zero() {
+            return Ok(amount);
+        }
+        // 与 withdraw_split 的计算方式一致，平台分成向下取整，零头归创作者
+        let balance = self.proceeds_balance();
+        let platform_amount = balance * self.platform_bps.get() / U256::from(ROYALTY_DENOMINATOR);
+        if account == platform {
+            amount += platform_amount;
+        }
+        if account == creator {
+            amount += balance - platform_amount;
+        }
+        Ok(amount)
+    }
+
+    // 设置创作者与平台的收益分配，仅合约所有者可调用
+    pub fn set_proceeds_split(
+        &mut self,
+        creator: Address,
+        platform: Address,
+        platform_bps: U256,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        if creator.is_zero() || platform.is_zero() || platform_bps > U256::from(ROYALTY_DENOMINATOR)
+        {
+            return Err(Erc721Error::InvalidProceedsSplit(InvalidProceedsSplit {
+                creator,
+                platform,
+                platform_bps,
+            }));
+        }
+        self.creator.set(creator);
+        self.platform.set(platform);
+        self.platform_bps.set(platform_bps);
+        Ok(())
+    }
+
+    // 获取创作者与平台的收益分配配置
+    pub fn proceeds_split(&self) -> Result<(Address, Address, U256), Erc721Error> {
+        Ok((
+            self.creator.get(),
+            self.platform.get(),
+            self.platform_bps.get(),
+        ))
+    }
+
+    // 按分配比例将合约余额一次性转给平台和创作者，不包含待分账的版税，
+    // 收款地址由所有者设定，任何人均可触发
+    pub fn withdraw_split(&mut self) -> Result<(), Erc721Error> {
+        self.split_proceeds()
+    }
+
+    // 设置版税收款人和份额，仅合约所有者可调用
+    pub fn set_payees(
+        &mut self,
+        payees: Vec<Address>,
+        shares: Vec<U256>,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.splitter.set_payees(payees, shares)
+    }
+
+    // 设置版税比例，仅合约所有者可调用
+    pub fn set_royalty(&mut self, bps: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        if bps > U256::from(ROYALTY_DENOMINATOR) {
+            return Err(Erc721Error::InvalidRoyalty(InvalidRoyalty { bps }));
+        }
+        self.royalty_bps.set(bps);
+        self.royalty_set.set(true);
+        Ok(())
+    }
+
+    // 开启或关闭未配置版税时的回退版税，仅合约所有者可调用
+    pub fn set_royalty_fallback(&mut self, enabled: bool) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.royalty_fallback_disabled.set(!enabled);
+        Ok(())
+    }
+
+    // 获取是否启用未配置版税时的回退版税
+    pub fn royalty_fallback(&self) -> Result<bool, Erc721Error> {
+        Ok(!self.royalty_fallback_disabled.get())
+    }
+
+    // ERC-2981 版税查询，收款方为本合约，由分账模块分配给各收款人，
+    // 专属版税和集合版税均未设置时，按回退比例直接付给创作者，未设置创作者时付给所有者
+    pub fn royalty_info(
+        &self,
+        token_id: U256,
+        sale_price: U256,
+    ) -> Result<(Address, U256), Erc721Error> {
+        Erc721::<StylusNFTParams>::require_extension(StylusNFTParams::ROYALTIES, IERC2981)?;
+        // 优先使用 token 的专属版税
+        let (receiver, bps) = match self.erc721.token_royalty(token_id) {
+            Some(bps) => (self.vm().contract_address(), bps),
+            None if self.royalty_set.get() || self.royalty_fallback_disabled.get() => {
+                (self.vm().contract_address(), self.royalty_bps.get())
+            }
+            None => {
+                let creator = self.creator.get();
+                let receiver = if creator.is_zero() {
+                    self.owner.get()
+                } else {
+                    creator
+                };
+                (receiver, U256::from(DEFAULT_ROYALTY_BPS))
+            }
+        };
+        let amount = sale_price.saturating_mul(bps) / U256::from(ROYALTY_DENOMINATOR);
+        Ok((receiver, amount))
+    }
+
+    // 由转账回调合约上报成交价，按 royalty_info 计算版税并计入接收者的累计应得额
+    pub fn report_sale(&mut self, token_id: U256, sale_price: U256) -> Result<(), Erc721Error> {
+        let hook = self.sale_hook.get();
+        if hook.is_zero() || self.vm().msg_sender() != hook {
+            return Err(Erc721Error::NotSaleHook(NotSaleHook {
+                caller: self.vm().msg_sender(),
+            }));
+        }
+        let (receiver, amount) = self.royalty_info(token_id, sale_price)?;
+        self.royalties_accrued.insert(
+            receiver,
+            self.royalties_accrued.get(receiver).saturating_add(amount),
+        );
+        log(
+            self.vm(),
+            RoyaltyAccrued {
+                receiver,
+                token_id,
+                sale_price,
+                amount,
+            },
+        );
+        Ok(())
+    }
+
+    // 获取接收者累计应得的版税，仅为记账信息
+    pub fn royalties_accrued(&self, receiver: Address) -> Result<U256, Erc721Error> {
+        Ok(self.royalties_accrued.get(receiver))
+    }
+
+    // 设置 token 的专属版税，仅合约所有者可调用
+    pub fn set_token_royalty(&mut self, token_id: U256, bps: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.owner_of(token_id)?;
+        if bps > U256::from(ROYALTY_DENOMINATOR) {
+            return Err(Erc721Error::InvalidRoyalty(InvalidRoyalty { bps }));
+        }
+        self.erc721.set_token_royalty(token_id, bps.to());
+        Ok(())
+    }
+
+    // 将 token 的版税重置为集合默认版税，仅合约所有者可调用
+    pub fn reset_token_royalty(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.erc721.reset_token_royalty(token_id);
+        Ok(())
+    }
+
+    // 设置空投领取的默克尔根，仅合约所有者可调用
+    pub fn set_claim_root(&mut self, root: FixedBytes<32>) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        self.claim_root.set(root);
+        Ok(())
+    }
+
+    // 获取空投领取的默克尔根
+    pub fn claim_root(&self) -> Result<FixedBytes<32>, Erc721Error> {
+        Ok(self.claim_root.get())
+    }
+
+    // 获取地址已领取的空投数量
+    pub fn claimed(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.claimed.get(account))
+    }
+
+    // 凭默克尔证明一次性领取全部空投额度
+    pub fn claim(&mut self, amount: U256, proof: Vec<FixedBytes<32>>) -> Result<(), Erc721Error> {
+        let account = self.vm().msg_sender();
+        if !self.claimed.get(account).is_zero() {
+            return Err(Erc721Error::AlreadyClaimed(AlreadyClaimed { account }));
+        }
+        // 验证 (调用者, 数量) 在默克尔树中
+        let root = self.claim_root.get();
+        if root.is_zero() || !merkle::verify(&proof, root, merkle::amount_leaf(account, amount)) {
+            return Err(Erc721Error::InvalidProof(InvalidProof { account }));
+        }
+        // 记录领取数量后连续铸造，超过最大供应量时回滚
+        self.claimed.insert(account, amount);
+        self.erc721.mint_consecutive(account, amount)?;
+        Ok(())
+    }
+
+    // 添加铸造阶段，仅合约所有者可调用
+    pub fn add_phase(
+        &mut self,
+        start: U256,
+        end: U256,
+        price: U256,
+        per_wallet_limit: U256,
+        merkle_root: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        let index = self.phases.len();
+        self.phases.grow();
+        self.write_phase(index, start, end, price, per_wallet_limit, merkle_root)
+    }
+
+    // 更新已有铸造阶段，仅合约所有者可调用
+    pub fn update_phase(
+        &mut self,
+        phase: U256,
+        start: U256,
+        end: U256,
+        price: U256,
+        per_wallet_limit: U256,
+        merkle_root: FixedBytes<32>,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        let index = usize::try_from(phase).unwrap_or(usize::MAX);
+        self.write_phase(index, start, end, price, per_wallet_limit, merkle_root)
+    }
+
+    // 获取铸造阶段数量
+    pub fn phase_count(&self) -> Result<U256, Erc721Error> {
+        Ok(U256::from(self.phases.len()))
+    }
+
+    // 设置铸造阶段的总配额，为 0 表示仅受全局最大供应量限制，仅合约所有者可调用
+    pub fn set_phase_max_supply(
+        &mut self,
+        phase: U256,
+        max_in_phase: U256,
+    ) -> Result<(), Erc721Error> {
+        self.only_owner_unsealed()?;
+        let index = usize::try_from(phase).unwrap_or(usize::MAX);
+        let mut config = self
+            .phases
+            .setter(index)
+            .ok_or(Erc721Error::NonexistentPhase(NonexistentPhase { phase }))?;
+        config.max_in_phase.set(max_in_phase);
+        Ok(())
+    }
+
+    // 获取铸造阶段剩余可铸造数量，不限配额时返回 uint256 最大值
+    pub fn phase_remaining(&self, phase: U256) -> Result<U256, Erc721Error> {
+        let index = usize::try_from(phase).unwrap_or(usize::MAX);
+        let config = self
+            .phases
+            .getter(index)
+            .ok_or(Erc721Error::NonexistentPhase(NonexistentPhase { phase }))?;
+        let max_in_phase = config.max_in_phase.get();
+        if max_in_phase.is_zero() {
+            return Ok(U256::MAX);
+        }
+        Ok(max_in_phase.saturating_sub(config.minted_in_phase.get()))
+    }
+
+    // 获取铸造阶段配置 (start, end, price, per_wallet_limit, merkle_root)
+    pub fn phase(
+        &self,
+        phase: U256,
+    ) -> Result<(U256, U256, U256, U256, FixedBytes<32>), Erc721Error> {
+        let index = usize::try_from(phase).unwrap_or(usize::MAX);
+        let config = self
+            .phases
+            .getter(index)
+            .ok_or(Erc721Error::NonexistentPhase(NonexistentPhase { phase }))?;
+        Ok((
+            config.start.get(),
+            config.end.get(),
+            config.price.get(),
+            config.per_wallet_limit.get(),
+            config.merkle_root.get(),
+        ))
+    }
+
+    // 按当前时间查找进行中的铸造阶段，多个阶段重叠时取最先添加的
+    pub fn active_phase(&self) -> Result<U256, Erc721Error> {
+        let now = U256::from(self.vm().block_timestamp());
+        for i in 0..self.phases.len() {
+            if let Some(phase) = self.phases.getter(i) {
+                if phase.start.get() <= now && now < phase.end.get() {
+                    return Ok(U256::from(i));
+                }
+            }
+        }
+        Err(Erc721Error::NoActivePhase(NoActivePhase {}))
+    }
+
+    // 在当前铸造阶段铸造 NFT，需满足阶段白名单、价格和单地址上限
+    #[payable]
+    #[selector(name = "mint")]
+    pub fn mint_in_phase(&mut self, proof: Vec<FixedBytes<32>>) -> Result<(), Erc721Error> {
+        let minter = self.vm().msg_sender();
+        self.throttle_mint(minter)?;
+        let phase = self.active_phase()?;
+        let config = self.phases.getter(phase.to::<usize>()).unwrap();
+        let price = config.price.get();
+        let limit = config.per_wallet_limit.get();
+        let root = config.merkle_root.get();
+        let max_in_phase = config.max_in_phase.get();
+        let minted_in_phase = config.minted_in_phase.get();
+        // 该阶段的配额已售罄
+        if !max_in_phase.is_zero() && minted_in_phase >= max_in_phase {
+            return Err(Erc721Error::PhaseSoldOut(PhaseSoldOut {
+                phase,
+                max_in_phase,
+            }));
+        }
+        // 设置了默克尔根时验证调用者在白名单中
+        if !root.is_zero() && !merkle::verify(&proof, root, merkle::leaf(minter)) {
+            return Err(Erc721Error::NotAllowlisted(NotAllowlisted {
+                account: minter,
+            }));
+        }
+        // 验证调用者在该阶段仍有剩余额度
+        let minted = self.phase_minted.getter(phase).get(minter);
+        if !limit.is_zero() && minted >= limit {
+            return Err(Erc721Error::PhaseLimitReached(PhaseLimitReached {
+                phase,
+                account: minter,
+                limit,
+            }));
+        }
+        self.phase_minted
+            .setter(phase)
+            .insert(minter, minted + U256::from(1));
+        if let Some(mut config) = self.phases.setter(phase.to::<usize>()) {
+            config.minted_in_phase.set(minted_in_phase + U256::from(1));
+        }
+        self.mint_paid(minter, price)?;
+        // 收取费用并退还多付金额
+        self.collect_payment(price)
+    }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,30 @@
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::SolValue;
+use stylus_sdk::crypto::keccak;
+
+// 计算地址对应的默克尔叶子节点
+pub fn leaf(account: Address) -> FixedBytes<32> {
+    keccak(account.as_slice())
+}
+
+// 计算 (地址, 数量) 对应的默克尔叶子节点
+pub fn amount_leaf(account: Address, amount: U256) -> FixedBytes<32> {
+    keccak((account, amount).abi_encode())
+}
+
+// 验证叶子节点在默克尔树中，兄弟节点按大小排序后拼接哈希
+pub fn verify(proof: &[FixedBytes<32>], root: FixedBytes<32>, leaf: FixedBytes<32>) -> bool {
+    let mut computed = leaf;
+    for &node in proof {
+        let (a, b) = if computed <= node {
+            (computed, node)
+        } else {
+            (node, computed)
+        };
+        let mut data = [0u8; 64];
+        data[..32].copy_from_slice(a.as_slice());
+        data[32..].copy_from_slice(b.as_slice());
+        computed = keccak(data);
+    }
+    computed == root
+}
@@ -0,0 +1,178 @@
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::prelude::*;
+
+use crate::erc721::{
+    Erc721Error, EthTransferFailed, InvalidPayees, NothingToRelease, PendingPayments,
+};
+
+// 定义版税分账的存储结构
+sol_storage! {
+    pub struct PaymentSplitter {
+        // 所有收款人的份额总和
+        uint256 total_shares;
+        // 累计收到的待分账 ETH
+        uint256 total_received;
+        // 累计已提取的 ETH
+        uint256 total_released;
+        // 收款人列表
+        address[] payees;
+        // 收款人到份额的映射
+        mapping(address => uint256) shares;
+        // 收款人到已提取金额的映射
+        mapping(address => uint256) released;
+    }
+}
+
+// 定义分账事件
+sol! {
+    // 收款人添加事件
+    event PayeeAdded(address indexed account, uint256 shares);
+    // 收款人提取款项事件
+    event PaymentReleased(address indexed to, uint256 amount);
+}
+
+// 实现分账内部方法
+impl PaymentSplitter {
+    // 重新设置收款人和份额，旧收款人需已提取全部应得款项
+    pub fn set_payees(
+        &mut self,
+        payees: Vec<Address>,
+        shares: Vec<U256>,
+    ) -> Result<(), Erc721Error> {
+        if payees.len() != shares.len() {
+            return Err(Self::invalid_payees(&payees, &shares));
+        }
+        // 清除旧收款人，存在未提取款项时回滚
+        for i in 0..self.payees.len() {
+            let account = self.payees.get(i).unwrap_or_default();
+            let amount = self.releasable_amount(account);
+            if !amount.is_zero() {
+                return Err(Erc721Error::PendingPayments(PendingPayments {
+                    account,
+                    amount,
+                }));
+            }
+            self.shares.delete(account);
+            self.released.delete(account);
+        }
+        self.payees.erase();
+        // 舍入剩余的零头计入新一轮分账
+        let dust = self.total_received.get() - self.total_released.get();
+        self.total_received.set(dust);
+        self.total_released.set(U256::ZERO);
+        // 登记新收款人，不允许零地址、零份额或重复地址
+        let mut total_shares = U256::ZERO;
+        for (&account, &share) in payees.iter().zip(shares.iter()) {
+            if account.is_zero() || share.is_zero() || !self.shares.get(account).is_zero() {
+                return Err(Self::invalid_payees(&payees, &shares));
+            }
+            self.payees.push(account);
+            self.shares.insert(account, share);
+            total_shares += share;
+            log(
+                self.vm(),
+                PayeeAdded {
+                    account,
+                    shares: share,
+                },
+            );
+        }
+        self.total_shares.set(total_shares);
+        Ok(())
+    }
+
+    // 记录收到的款项，未设置收款人时不计入分账
+    pub fn deposit(&mut self, amount: U256) {
+        if self.total_shares.get().is_zero() {
+            return;
+        }
+        self.total_received.set(self.total_received.get() + amount);
+    }
+
+    // 获取尚未被收款人提取的款项总额
+    pub fn unreleased(&self) -> U256 {
+        self.total_received.get() - self.total_released.get()
+    }
+
+    // 计算收款人当前可提取的金额
+    fn releasable_amount(&self, account: Address) -> U256 {
+        let total_shares = self.total_shares.get();
+        if total_shares.is_zero() {
+            return U256::ZERO;
+        }
+        let entitled = self.total_received.get() * self.shares.get(account) / total_shares;
+        entitled.saturating_sub(self.released.get(account))
+    }
+
+    // 构造收款人配置无效的错误
+    fn invalid_payees(payees: &[Address], shares: &[U256]) -> Erc721Error {
+        Erc721Error::InvalidPayees(InvalidPayees {
+            payees: U256::from(payees.len()),
+            shares: U256::from(shares.len()),
+        })
+    }
+}
+
+// 实现分账外部方法
+#[public]
+impl PaymentSplitter {
+    // 获取所有收款人
+    pub fn payees(&self) -> Result<Vec<Address>, Erc721Error> {
+        Ok((0..self.payees.len())
+            .filter_map(|i| self.payees.get(i))
+            .collect())
+    }
+
+    // 获取收款人的份额
+    pub fn shares(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.shares.get(account))
+    }
+
+    // 获取份额总和
+    pub fn total_shares(&self) -> Result<U256, Erc721Error> {
+        Ok(self.total_shares.get())
+    }
+
+    // 获取收款人已提取的金额
+    pub fn released(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.released.get(account))
+    }
+
+    // 获取累计已提取的金额
+    pub fn total_released(&self) -> Result<U256, Erc721Error> {
+        Ok(self.total_released.get())
+    }
+
+    // 获取收款人当前可提取的金额
+    pub fn releasable(&self, account: Address) -> Result<U256, Erc721Error> {
+        Ok(self.releasable_amount(account))
+    }
+
+    // 将收款人应得的款项转给收款人，任何人均可调用
+    pub fn release(&mut self, account: Address) -> Result<(), Erc721Error> {
+        let amount = self.releasable_amount(account);
+        if amount.is_zero() {
+            return Err(Erc721Error::NothingToRelease(NothingToRelease { account }));
+        }
+        // 先更新记账再转账
+        self.released
+            .insert(account, self.released.get(account) + amount);
+        self.total_released.set(self.total_released.get() + amount);
+        self.vm().transfer_eth(account, amount).map_err(|_| {
+            Erc721Error::EthTransferFailed(EthTransferFailed {
+                to: account,
+                amount,
+            })
+        })?;
+        log(
+            self.vm(),
+            PaymentReleased {
+                to: account,
+                amount,
+            },
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use super::*;
+use crate::erc721::{AccountCreationFailed, Erc721Error};
+use crate::IERC6551Registry;
+use alloy_sol_types::SolCall;
+
+const REGISTRY: Address = address!("000000000000000000000000000000000000a6c0");
+const IMPLEMENTATION: Address = address!("0000000000000000000000000000000000001e41");
+
+// 部署合约，为 ALICE 铸造 token 0 并配置注册表
+fn configured() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.set_account_registry(REGISTRY, IMPLEMENTATION).unwrap();
+    (vm, c)
+}
+
+fn create_call(vm: &TestVM, token_id: U256) -> Vec<u8> {
+    IERC6551Registry::createAccountCall {
+        implementation: IMPLEMENTATION,
+        salt: FixedBytes::ZERO,
+        chain_id: U256::from(vm.chain_id()),
+        token_contract: vm.contract_address(),
+        token_id,
+    }
+    .abi_encode()
+}
+
+#[test]
+fn account_of_follows_create2() {
+    let (vm, c) = configured();
+    // ERC-1167 代理字节码加 token 信息
+    let mut bytecode = hex::decode("3d60ad80600a3d3981f3363d3d373d3d3d363d73").unwrap();
+    bytecode.extend_from_slice(IMPLEMENTATION.as_slice());
+    bytecode.extend_from_slice(&hex::decode("5af43d82803e903d91602b57fd5bf3").unwrap());
+    bytecode.extend_from_slice(
+        &(
+            FixedBytes::<32>::ZERO,
+            U256::from(vm.chain_id()),
+            vm.contract_address(),
+            U256::ZERO,
+        )
+            .abi_encode(),
+    );
+    let expected = REGISTRY.create2(FixedBytes::<32>::ZERO, keccak(bytecode));
+    assert_eq!(c.account_of(U256::ZERO).unwrap(), expected);
+    assert_ne!(c.account_of(U256::from(1)).unwrap(), expected);
+}
+
+#[test]
+fn create_account_matches_computed_address() {
+    let (vm, mut c) = configured();
+    let account = c.account_of(U256::ZERO).unwrap();
+    vm.mock_call(
+        REGISTRY,
+        create_call(&vm, U256::ZERO),
+        Ok(account.abi_encode()),
+    );
+    assert_eq!(c.create_account(U256::ZERO).unwrap(), account);
+}
+
+#[test]
+fn failed_registry_call_reverts() {
+    let (vm, mut c) = configured();
+    vm.mock_call(REGISTRY, create_call(&vm, U256::ZERO), Err(Vec::new()));
+    assert_eq!(
+        c.create_account(U256::ZERO).unwrap_err(),
+        Erc721Error::AccountCreationFailed(AccountCreationFailed {
+            registry: REGISTRY,
+            token_id: U256::ZERO,
+        })
+    );
+}
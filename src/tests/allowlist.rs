@@ -0,0 +1,180 @@
+use super::*;
+use crate::erc721::{
+    AllowanceExceeded, AllowlistExhausted, AlreadyClaimed, AlreadyWaitlisted, Erc721Error,
+    InvalidProof, NoAllowance, NotAllowlisted,
+};
+use crate::merkle;
+
+#[test]
+fn batch_add_grants_allowance() {
+    let (_vm, mut c) = deploy();
+    c.set_allowlist(vec![ALICE, BOB], true).unwrap();
+    assert_eq!(c.allowlist_remaining(ALICE).unwrap(), U256::from(1));
+    assert_eq!(c.allowlist_remaining(BOB).unwrap(), U256::from(1));
+    assert_eq!(c.allowlist_remaining(RELAYER).unwrap(), U256::ZERO);
+    // 批量移除
+    c.set_allowlist(vec![BOB], false).unwrap();
+    assert_eq!(c.allowlist_remaining(BOB).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn allowlisted_claim_mints() {
+    let (vm, mut c) = deploy();
+    c.set_allowlist(vec![ALICE], true).unwrap();
+    vm.set_sender(ALICE);
+    c.mint_allowlisted().unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(c.allowlist_remaining(ALICE).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn claim_requires_allowlist() {
+    let (vm, mut c) = deploy();
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.mint_allowlisted().unwrap_err(),
+        Erc721Error::NotAllowlisted(NotAllowlisted { account: BOB })
+    );
+}
+
+#[test]
+fn exhausted_allowance_reverts() {
+    let (vm, mut c) = deploy();
+    c.set_allowlist(vec![ALICE], true).unwrap();
+    vm.set_sender(ALICE);
+    c.mint_allowlisted().unwrap();
+    assert_eq!(
+        c.mint_allowlisted().unwrap_err(),
+        Erc721Error::AllowlistExhausted(AllowlistExhausted { account: ALICE })
+    );
+}
+
+#[test]
+fn set_allowlist_is_owner_only() {
+    let (vm, mut c) = deploy();
+    vm.set_sender(ALICE);
+    assert!(c.set_allowlist(vec![ALICE], true).is_err());
+}
+
+// ALICE 可领取 3 个、BOB 可领取 1 个的两叶默克尔树，返回根及 ALICE 和 BOB 的证明
+fn claim_tree() -> (FixedBytes<32>, Vec<FixedBytes<32>>, Vec<FixedBytes<32>>) {
+    let alice = merkle::amount_leaf(ALICE, U256::from(3));
+    let bob = merkle::amount_leaf(BOB, U256::from(1));
+    let (lo, hi) = if alice <= bob {
+        (alice, bob)
+    } else {
+        (bob, alice)
+    };
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(lo.as_slice());
+    data[32..].copy_from_slice(hi.as_slice());
+    (keccak(data), vec![bob], vec![alice])
+}
+
+#[test]
+fn valid_claim_mints_allocation() {
+    let (vm, mut c) = deploy();
+    let (root, alice_proof, bob_proof) = claim_tree();
+    c.set_claim_root(root).unwrap();
+    vm.set_sender(ALICE);
+    c.claim(U256::from(3), alice_proof).unwrap();
+    vm.set_sender(BOB);
+    c.claim(U256::from(1), bob_proof).unwrap();
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(3));
+    assert_eq!(c.erc721.balance_of(BOB).unwrap(), U256::from(1));
+}
+
+#[test]
+fn double_claim_reverts() {
+    let (vm, mut c) = deploy();
+    let (root, alice_proof, _) = claim_tree();
+    c.set_claim_root(root).unwrap();
+    vm.set_sender(ALICE);
+    c.claim(U256::from(3), alice_proof.clone()).unwrap();
+    assert_eq!(
+        c.claim(U256::from(3), alice_proof).unwrap_err(),
+        Erc721Error::AlreadyClaimed(AlreadyClaimed { account: ALICE })
+    );
+}
+
+#[test]
+fn bad_proof_reverts() {
+    let (vm, mut c) = deploy();
+    let (root, alice_proof, bob_proof) = claim_tree();
+    c.set_claim_root(root).unwrap();
+    vm.set_sender(ALICE);
+    // 虚报数量或使用他人的证明均无效
+    for (amount, proof) in [(4, alice_proof), (3, bob_proof)] {
+        assert_eq!(
+            c.claim(U256::from(amount), proof).unwrap_err(),
+            Erc721Error::InvalidProof(InvalidProof { account: ALICE })
+        );
+    }
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn granted_allowance_is_consumed() {
+    let (vm, mut c) = deploy();
+    c.grant_mint_allowance(ALICE, U256::from(3)).unwrap();
+    vm.set_sender(ALICE);
+    c.mint_from_allowance(U256::from(2)).unwrap();
+    assert_eq!(c.mint_allowance_of(ALICE).unwrap(), U256::from(1));
+    assert_eq!(
+        c.mint_from_allowance(U256::from(2)).unwrap_err(),
+        Erc721Error::AllowanceExceeded(AllowanceExceeded {
+            account: ALICE,
+            requested: U256::from(2),
+            allowance: U256::from(1),
+        })
+    );
+    c.mint_from_allowance(U256::from(1)).unwrap();
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(3));
+    assert_eq!(
+        c.mint_from_allowance(U256::from(1)).unwrap_err(),
+        Erc721Error::NoAllowance(NoAllowance { account: ALICE })
+    );
+}
+
+#[test]
+fn waitlist_records_positions_in_join_order() {
+    let (vm, mut c) = deploy();
+    for (account, position) in [(ALICE, 1u64), (BOB, 2), (RELAYER, 3)] {
+        vm.set_sender(account);
+        assert_eq!(c.join_waitlist().unwrap(), U256::from(position));
+        assert_eq!(c.waitlist_position(account).unwrap(), U256::from(position));
+    }
+    assert_eq!(c.waitlist_length().unwrap(), U256::from(3));
+    assert_eq!(c.waitlist_position(OWNER).unwrap(), U256::ZERO);
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.join_waitlist().unwrap_err(),
+        Erc721Error::AlreadyWaitlisted(AlreadyWaitlisted {
+            account: BOB,
+            position: U256::from(2),
+        })
+    );
+}
+
+#[test]
+fn process_waitlist_mints_a_prefix() {
+    let (vm, mut c) = deploy();
+    for account in [ALICE, BOB, RELAYER] {
+        vm.set_sender(account);
+        c.join_waitlist().unwrap();
+    }
+    vm.set_sender(OWNER);
+    assert_eq!(c.process_waitlist(U256::from(2)).unwrap(), U256::from(2));
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(c.erc721.owner_of(U256::from(1)).unwrap(), BOB);
+    assert_eq!(c.erc721.balance_of(RELAYER).unwrap(), U256::ZERO);
+    assert_eq!(c.waitlist_length().unwrap(), U256::from(1));
+    // 不足 count 时处理剩余全部
+    assert_eq!(c.process_waitlist(U256::from(5)).unwrap(), U256::from(1));
+    assert_eq!(c.erc721.owner_of(U256::from(2)).unwrap(), RELAYER);
+    assert_eq!(c.waitlist_length().unwrap(), U256::ZERO);
+    // 已处理的地址保留原位置
+    assert_eq!(c.waitlist_position(BOB).unwrap(), U256::from(2));
+    vm.set_sender(ALICE);
+    assert!(c.process_waitlist(U256::from(1)).is_err());
+}
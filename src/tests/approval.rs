@@ -0,0 +1,445 @@
+use super::*;
+use crate::erc721::{
+    Approval, ApprovalForAll, BudgetExhausted, DefaultOperatorChanged, Erc721, Erc721Error,
+    InvalidTokenId, NotApproved, NotOwner, RecoveryModeDisabled, TooManyApprovals,
+    TooManyOperators,
+};
+use crate::ForcedApprovalForAll;
+
+// 单个地址最多持有 2 个 token 级授权的集合
+struct CappedParams;
+impl Erc721Params for CappedParams {
+    const NAME: &'static str = "CAP";
+    const SYMBOL: &'static str = "CAP";
+    const MAX_TOKEN_APPROVALS_HELD: Option<u64> = Some(2);
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+// 为 ALICE 铸造 token 0..3，并以 ALICE 作为调用者
+fn capped() -> (TestVM, Erc721<CappedParams>) {
+    let vm = TestVM::default();
+    vm.set_block_timestamp(1000);
+    let mut token = Erc721::<CappedParams>::from(&vm);
+    for _ in 0..3 {
+        token.mint(ALICE).unwrap();
+    }
+    vm.set_sender(ALICE);
+    (vm, token)
+}
+
+fn held(token: &Erc721<CappedParams>) -> U256 {
+    token.token_approvals_held(BOB).unwrap()
+}
+
+#[test]
+fn cap_rejects_extra_approval() {
+    let (_vm, mut token) = capped();
+    token.approve(BOB, U256::from(0)).unwrap();
+    token.approve(BOB, U256::from(1)).unwrap();
+    assert_eq!(
+        token.approve(BOB, U256::from(2)).unwrap_err(),
+        Erc721Error::TooManyApprovals(TooManyApprovals {
+            operator: BOB,
+            limit: U256::from(2),
+        })
+    );
+}
+
+#[test]
+fn transfer_releases_held_approval() {
+    let (_vm, mut token) = capped();
+    token.approve(BOB, U256::from(0)).unwrap();
+    token.approve(BOB, U256::from(1)).unwrap();
+    token.transfer_from(ALICE, RELAYER, U256::from(0)).unwrap();
+    assert_eq!(held(&token), U256::from(1));
+    token.approve(BOB, U256::from(2)).unwrap();
+    assert_eq!(held(&token), U256::from(2));
+}
+
+#[test]
+fn expired_approvals_can_be_cleared() {
+    let (vm, mut token) = capped();
+    token
+        .approve_until(BOB, U256::from(0), U256::from(1100))
+        .unwrap();
+    token
+        .approve_until(BOB, U256::from(1), U256::from(1100))
+        .unwrap();
+    vm.set_block_timestamp(1101);
+    assert_eq!(token.get_approved(U256::from(0)).unwrap(), Address::ZERO);
+    assert!(token.approve(BOB, U256::from(2)).is_err());
+    token
+        .clear_expired_approvals(vec![U256::from(0), U256::from(1)])
+        .unwrap();
+    assert_eq!(held(&token), U256::ZERO);
+    token.approve(BOB, U256::from(2)).unwrap();
+    assert_eq!(held(&token), U256::from(1));
+}
+
+#[test]
+fn clearing_skips_live_approvals() {
+    let (vm, mut token) = capped();
+    token
+        .approve_until(BOB, U256::from(0), U256::from(1100))
+        .unwrap();
+    token.approve(BOB, U256::from(1)).unwrap();
+    vm.set_block_timestamp(1100);
+    // 任何人都可清除，未过期与永不过期的授权保留
+    vm.set_sender(RELAYER);
+    token
+        .clear_expired_approvals(vec![U256::from(0), U256::from(1)])
+        .unwrap();
+    assert_eq!(held(&token), U256::from(2));
+    assert_eq!(token.get_approved(U256::from(0)).unwrap(), BOB);
+}
+
+#[test]
+fn approve_batch_sets_each_approval() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.erc721
+        .approve_batch(BOB, vec![U256::ZERO, U256::from(1)])
+        .unwrap();
+    assert_eq!(c.erc721.get_approved(U256::ZERO).unwrap(), BOB);
+    assert_eq!(c.erc721.get_approved(U256::from(1)).unwrap(), BOB);
+    assert_eq!(count_logs::<Approval>(&vm), 2);
+}
+
+#[test]
+fn approve_batch_rejects_unowned_token() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(BOB).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.erc721
+            .approve_batch(RELAYER, vec![U256::ZERO, U256::from(1)])
+            .unwrap_err(),
+        Erc721Error::NotApproved(NotApproved {
+            owner: BOB,
+            spender: ALICE,
+            token_id: U256::from(1),
+        })
+    );
+}
+
+// 为 ALICE 铸造 token 0，并授权 BOB 在时间 1100 前转移
+fn expiring() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_block_timestamp(1000);
+    vm.set_sender(ALICE);
+    c.erc721
+        .approve_until(BOB, U256::ZERO, U256::from(1100))
+        .unwrap();
+    vm.set_sender(BOB);
+    (vm, c)
+}
+
+#[test]
+fn approval_works_before_deadline() {
+    let (vm, mut c) = expiring();
+    assert_eq!(
+        c.erc721.approval_deadline(U256::ZERO).unwrap(),
+        U256::from(1100)
+    );
+    vm.set_block_timestamp(1100);
+    assert_eq!(c.erc721.get_approved(U256::ZERO).unwrap(), BOB);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+}
+
+#[test]
+fn approval_lapses_after_deadline() {
+    let (vm, mut c) = expiring();
+    vm.set_block_timestamp(1101);
+    assert_eq!(c.erc721.get_approved(U256::ZERO).unwrap(), Address::ZERO);
+    assert_eq!(
+        c.transfer_from(ALICE, BOB, U256::ZERO).unwrap_err(),
+        Erc721Error::NotApproved(NotApproved {
+            owner: ALICE,
+            spender: BOB,
+            token_id: U256::ZERO,
+        })
+    );
+}
+
+#[test]
+fn clear_approval_resets_spender() {
+    let (vm, mut token) = capped();
+    token.approve(BOB, U256::ZERO).unwrap();
+    token.clear_approval(U256::ZERO).unwrap();
+    assert_eq!(token.get_approved(U256::ZERO).unwrap(), Address::ZERO);
+    assert_eq!(held(&token), U256::ZERO);
+    // 最后一条 Approval 事件为 (ALICE, 0, 0)
+    let logs = vm.get_emitted_logs();
+    let (topics, _) = logs.last().unwrap();
+    assert_eq!(topics[0], Approval::SIGNATURE_HASH);
+    assert_eq!(topics[1], ALICE.into_word());
+    assert_eq!(topics[2], Address::ZERO.into_word());
+    assert_eq!(topics[3], FixedBytes::<32>::ZERO);
+}
+
+#[test]
+fn is_approved_or_owner_covers_each_role() {
+    let (_vm, mut token) = capped();
+    token.approve(BOB, U256::ZERO).unwrap();
+    token.set_approval_for_all(RELAYER, true).unwrap();
+    let allowed = |spender| token.is_approved_or_owner(spender, U256::ZERO).unwrap();
+    assert!(allowed(ALICE));
+    assert!(allowed(BOB));
+    assert!(allowed(RELAYER));
+    assert!(!allowed(OWNER));
+    assert_eq!(
+        token
+            .is_approved_or_owner(ALICE, U256::from(9))
+            .unwrap_err(),
+        Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::from(9)
+        })
+    );
+}
+
+// 设置 RELAYER 为默认操作者，为 ALICE 铸造 token 0、为 BOB 铸造 token 1
+fn marketplace() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.set_default_operator(RELAYER).unwrap();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(BOB).unwrap();
+    (vm, c)
+}
+
+#[test]
+fn default_operator_is_approved() {
+    let (vm, mut c) = marketplace();
+    assert!(c.erc721.is_approved_for_all(ALICE, RELAYER).unwrap());
+    vm.set_sender(RELAYER);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+}
+
+#[test]
+fn holder_can_opt_out_of_default_operator() {
+    let (vm, mut c) = marketplace();
+    vm.set_sender(ALICE);
+    c.erc721.set_approval_for_all(RELAYER, false).unwrap();
+    assert!(!c.erc721.is_approved_for_all(ALICE, RELAYER).unwrap());
+    // 其他持有者不受影响
+    assert!(c.erc721.is_approved_for_all(BOB, RELAYER).unwrap());
+    vm.set_sender(RELAYER);
+    assert_eq!(
+        c.transfer_from(ALICE, BOB, U256::ZERO),
+        Err(Erc721Error::NotApproved(NotApproved {
+            owner: ALICE,
+            spender: RELAYER,
+            token_id: U256::ZERO,
+        }))
+    );
+}
+
+#[test]
+fn transfer_keeps_operator_and_clears_token_approval() {
+    let (_vm, mut token) = capped();
+    token.approve(BOB, U256::ZERO).unwrap();
+    token.set_approval_for_all(RELAYER, true).unwrap();
+    token.transfer_from(ALICE, OWNER, U256::ZERO).unwrap();
+    assert_eq!(token.get_approved(U256::ZERO).unwrap(), Address::ZERO);
+    // 原拥有者的操作者授权不受转账影响，且不延伸到新拥有者
+    assert!(token.is_approved_for_all(ALICE, RELAYER).unwrap());
+    assert!(!token.is_approved_for_all(OWNER, RELAYER).unwrap());
+    assert!(token.is_approved_or_owner(RELAYER, U256::from(1)).unwrap());
+    assert!(!token.is_approved_or_owner(RELAYER, U256::ZERO).unwrap());
+}
+
+#[test]
+fn changing_default_operator_emits_event() {
+    let (vm, mut c) = marketplace();
+    assert_eq!(c.erc721.default_operator().unwrap(), RELAYER);
+    c.set_default_operator(BOB).unwrap();
+    assert_eq!(c.erc721.default_operator().unwrap(), BOB);
+    let logs = vm.get_emitted_logs();
+    let (topics, _) = logs.last().unwrap();
+    assert_eq!(topics[0], DefaultOperatorChanged::SIGNATURE_HASH);
+    assert_eq!(topics[1], RELAYER.into_word());
+    assert_eq!(topics[2], BOB.into_word());
+    // 旧操作者的默认授权随之失效
+    assert!(!c.erc721.is_approved_for_all(ALICE, RELAYER).unwrap());
+    // 设置为相同地址不重复发出事件
+    c.set_default_operator(BOB).unwrap();
+    assert_eq!(count_logs::<DefaultOperatorChanged>(&vm), 2);
+}
+
+#[test]
+fn revoke_clears_token_approvals_and_operator() {
+    let (vm, mut c) = deploy();
+    for _ in 0..3 {
+        c.mint_to(ALICE).unwrap();
+    }
+    c.mint_to(BOB).unwrap();
+    let ids = [0u64, 1, 2].map(U256::from).to_vec();
+    vm.set_sender(ALICE);
+    for token_id in &ids {
+        c.erc721.approve(BOB, *token_id).unwrap();
+    }
+    c.erc721.set_approval_for_all(RELAYER, true).unwrap();
+    let approvals = count_logs::<Approval>(&vm);
+
+    c.erc721.revoke_all_approvals(ids.clone()).unwrap();
+    for token_id in &ids {
+        assert_eq!(c.erc721.get_approved(*token_id).unwrap(), Address::ZERO);
+    }
+    assert_eq!(count_logs::<Approval>(&vm), approvals + ids.len());
+    c.erc721.revoke_operator(RELAYER).unwrap();
+    assert!(!c.erc721.is_approved_for_all(ALICE, RELAYER).unwrap());
+    assert_eq!(count_logs::<ApprovalForAll>(&vm), 2);
+    // 不属于调用者的 token 整体回滚
+    assert_eq!(
+        c.erc721.revoke_all_approvals(vec![U256::from(3)]),
+        Err(Erc721Error::NotOwner(NotOwner {
+            from: ALICE,
+            token_id: U256::from(3),
+            real_owner: BOB,
+        }))
+    );
+}
+
+#[test]
+fn operator_budget_caps_operator_transfers() {
+    let (vm, mut c) = deploy();
+    for _ in 0..4 {
+        c.mint_to(ALICE).unwrap();
+    }
+    vm.set_sender(ALICE);
+    c.erc721.set_approval_for_all(RELAYER, true).unwrap();
+    c.erc721
+        .set_operator_budget(RELAYER, U256::from(2))
+        .unwrap();
+    assert_eq!(
+        c.erc721.operator_budget(ALICE, RELAYER).unwrap(),
+        U256::from(2)
+    );
+    // 拥有者本人转账不消耗额度
+    c.transfer_from(ALICE, BOB, U256::from(3)).unwrap();
+
+    vm.set_sender(RELAYER);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    c.transfer_from(ALICE, BOB, U256::from(1)).unwrap();
+    assert_eq!(
+        c.erc721.operator_budget(ALICE, RELAYER).unwrap(),
+        U256::ZERO
+    );
+    assert_eq!(
+        c.transfer_from(ALICE, BOB, U256::from(2)),
+        Err(Erc721Error::BudgetExhausted(BudgetExhausted {
+            owner: ALICE,
+            operator: RELAYER,
+        }))
+    );
+    // 单个 token 的被授权者不受额度限制
+    vm.set_sender(ALICE);
+    c.erc721.approve(RELAYER, U256::from(2)).unwrap();
+    vm.set_sender(RELAYER);
+    c.transfer_from(ALICE, BOB, U256::from(2)).unwrap();
+    assert_eq!(c.erc721.balance_of(BOB).unwrap(), U256::from(4));
+}
+
+#[test]
+fn unlimited_budget_is_the_default() {
+    let (_vm, mut c) = deploy();
+    assert_eq!(c.erc721.operator_budget(ALICE, RELAYER).unwrap(), U256::MAX);
+    c.erc721
+        .set_operator_budget(RELAYER, U256::from(1))
+        .unwrap();
+    c.erc721.set_operator_budget(RELAYER, U256::MAX).unwrap();
+    assert_eq!(c.erc721.operator_budget(OWNER, RELAYER).unwrap(), U256::MAX);
+}
+
+#[test]
+fn operators_approved_for_reports_each_owner() {
+    let (vm, mut c) = deploy();
+    for owner in [ALICE, RELAYER] {
+        vm.set_sender(owner);
+        c.erc721.set_approval_for_all(BOB, true).unwrap();
+    }
+    assert_eq!(
+        c.erc721
+            .operators_approved_for(BOB, vec![ALICE, OWNER, RELAYER, ALICE])
+            .unwrap(),
+        vec![true, false, true, true]
+    );
+    assert!(c
+        .erc721
+        .operators_approved_for(BOB, Vec::new())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn forced_operator_approval_requires_recovery_mode() {
+    let (vm, mut c) = marketplace();
+    assert_eq!(
+        c.force_set_approval_for_all(ALICE, BOB, true),
+        Err(Erc721Error::RecoveryModeDisabled(RecoveryModeDisabled {}))
+    );
+    c.set_recovery_mode(true).unwrap();
+    c.force_set_approval_for_all(ALICE, BOB, true).unwrap();
+    assert!(c.erc721.is_approved_for_all(ALICE, BOB).unwrap());
+    assert_eq!(count_logs::<ApprovalForAll>(&vm), 1);
+    assert_eq!(count_logs::<ForcedApprovalForAll>(&vm), 1);
+    // 新操作者可代失去私钥的持有者转出
+    vm.set_sender(BOB);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    vm.set_sender(OWNER);
+    c.set_recovery_mode(false).unwrap();
+    assert!(c.force_set_approval_for_all(ALICE, BOB, false).is_err());
+    vm.set_sender(ALICE);
+    assert!(c.force_set_approval_for_all(ALICE, RELAYER, true).is_err());
+}
+
+// 每个拥有者最多同时授权 2 个操作者的集合
+struct OperatorCapParams;
+impl Erc721Params for OperatorCapParams {
+    const NAME: &'static str = "OPS";
+    const SYMBOL: &'static str = "OPS";
+    const MAX_OPERATORS_PER_OWNER: Option<u64> = Some(2);
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+#[test]
+fn operator_cap_rejects_extra_operator() {
+    let vm = TestVM::default();
+    let mut token = Erc721::<OperatorCapParams>::from(&vm);
+    vm.set_sender(ALICE);
+    token.set_approval_for_all(BOB, true).unwrap();
+    token.set_approval_for_all(RELAYER, true).unwrap();
+    // 重复授权不占用新名额
+    token.set_approval_for_all(BOB, true).unwrap();
+    assert_eq!(token.operator_count(ALICE).unwrap(), U256::from(2));
+    assert_eq!(
+        token.set_approval_for_all(OWNER, true).unwrap_err(),
+        Erc721Error::TooManyOperators(TooManyOperators {
+            owner: ALICE,
+            limit: U256::from(2),
+        })
+    );
+}
+
+#[test]
+fn revoking_operator_frees_a_slot() {
+    let vm = TestVM::default();
+    let mut token = Erc721::<OperatorCapParams>::from(&vm);
+    vm.set_sender(ALICE);
+    token.set_approval_for_all(BOB, true).unwrap();
+    token.set_approval_for_all(RELAYER, true).unwrap();
+    token.set_approval_for_all(BOB, false).unwrap();
+    assert_eq!(token.operator_count(ALICE).unwrap(), U256::from(1));
+    token.set_approval_for_all(OWNER, true).unwrap();
+    assert!(token.is_approved_for_all(ALICE, OWNER).unwrap());
+    assert!(!token.is_approved_for_all(ALICE, BOB).unwrap());
+}
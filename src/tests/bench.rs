@@ -0,0 +1,432 @@
+// 存储操作计量：包装 TestVM 记录每次调用的 SLOAD/SSTORE，按 EIP-2929/2200 估算 gas，
+// 只统计存储操作，不包含计算和日志开销
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::B256;
+use stylus_sdk::stylus_core::{
+    calls::{errors::Error as CallError, *},
+    deploy::*,
+};
+
+use super::*;
+
+// 冷读取、热读取、新写入非零值、修改已有值的 gas
+const COLD_SLOAD: u64 = 2_100;
+const WARM_SLOAD: u64 = 100;
+const SSTORE_SET: u64 = 20_000;
+const SSTORE_RESET: u64 = 2_900;
+
+// 一次调用中的存储操作统计
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(super) struct Usage {
+    pub loads: u64,
+    pub stores: u64,
+    pub gas: u64,
+}
+
+// 正在计量的调用中访问过的槽及其原始值
+#[derive(Default)]
+struct Meter {
+    loads: u64,
+    stores: u64,
+    warm: HashSet<U256>,
+    original: HashMap<U256, B256>,
+}
+
+// 记录存储访问的测试虚拟机，其余宿主调用直接转发给 TestVM
+#[derive(Clone)]
+pub(super) struct MeteredVM {
+    pub vm: TestVM,
+    meter: Rc<RefCell<Meter>>,
+}
+
+impl MeteredVM {
+    pub fn new(vm: TestVM) -> Self {
+        Self {
+            vm,
+            meter: Rc::default(),
+        }
+    }
+
+    // 执行 f 并返回期间的存储操作统计
+    pub fn measure<R>(&self, f: impl FnOnce() -> R) -> (R, Usage) {
+        *self.meter.borrow_mut() = Meter::default();
+        let result = f();
+        let meter = self.meter.borrow();
+        // 每个槽首次访问按冷读取计，其后的读取按热读取计
+        let cold = meter.warm.len() as u64;
+        let mut gas = cold * COLD_SLOAD + meter.loads.saturating_sub(cold) * WARM_SLOAD;
+        for (key, original) in &meter.original {
+            let current = self.vm.get_storage(*key);
+            if current == *original {
+                continue;
+            }
+            gas += if original.is_zero() {
+                SSTORE_SET
+            } else {
+                SSTORE_RESET
+            };
+        }
+        let usage = Usage {
+            loads: meter.loads,
+            stores: meter.stores,
+            gas,
+        };
+        (result, usage)
+    }
+
+    // 记录槽的首次访问及其原始值
+    fn touch(&self, key: U256) {
+        let mut meter = self.meter.borrow_mut();
+        if meter.warm.insert(key) {
+            let original = self.vm.get_storage(key);
+            meter.original.insert(key, original);
+        }
+    }
+}
+
+impl StorageAccess for MeteredVM {
+    fn storage_load_bytes32(&self, key: U256) -> B256 {
+        self.touch(key);
+        self.meter.borrow_mut().loads += 1;
+        self.vm.storage_load_bytes32(key)
+    }
+    unsafe fn storage_cache_bytes32(&self, key: U256, value: B256) {
+        self.touch(key);
+        self.meter.borrow_mut().stores += 1;
+        self.vm.storage_cache_bytes32(key, value)
+    }
+    fn flush_cache(&self, clear: bool) {
+        self.vm.flush_cache(clear)
+    }
+}
+
+impl CryptographyAccess for MeteredVM {
+    fn native_keccak256(&self, input: &[u8]) -> B256 {
+        self.vm.native_keccak256(input)
+    }
+}
+
+impl CalldataAccess for MeteredVM {
+    fn read_args(&self, len: usize) -> Vec<u8> {
+        self.vm.read_args(len)
+    }
+    fn read_return_data(&self, offset: usize, size: Option<usize>) -> Vec<u8> {
+        self.vm.read_return_data(offset, size)
+    }
+    fn return_data_size(&self) -> usize {
+        self.vm.return_data_size()
+    }
+    fn write_result(&self, data: &[u8]) {
+        self.vm.write_result(data)
+    }
+}
+
+unsafe impl UnsafeDeploymentAccess for MeteredVM {
+    unsafe fn create1(
+        &self,
+        code: *const u8,
+        code_len: usize,
+        endowment: *const u8,
+        contract: *mut u8,
+        revert_data_len: *mut usize,
+    ) {
+        self.vm
+            .create1(code, code_len, endowment, contract, revert_data_len)
+    }
+    unsafe fn create2(
+        &self,
+        code: *const u8,
+        code_len: usize,
+        endowment: *const u8,
+        salt: *const u8,
+        contract: *mut u8,
+        revert_data_len: *mut usize,
+    ) {
+        self.vm
+            .create2(code, code_len, endowment, salt, contract, revert_data_len)
+    }
+}
+
+unsafe impl UnsafeCallAccess for MeteredVM {
+    unsafe fn call_contract(
+        &self,
+        to: *const u8,
+        data: *const u8,
+        data_len: usize,
+        value: *const u8,
+        gas: u64,
+        outs_len: &mut usize,
+    ) -> u8 {
+        self.vm
+            .call_contract(to, data, data_len, value, gas, outs_len)
+    }
+    unsafe fn static_call_contract(
+        &self,
+        to: *const u8,
+        data: *const u8,
+        data_len: usize,
+        gas: u64,
+        outs_len: &mut usize,
+    ) -> u8 {
+        self.vm
+            .static_call_contract(to, data, data_len, gas, outs_len)
+    }
+    unsafe fn delegate_call_contract(
+        &self,
+        to: *const u8,
+        data: *const u8,
+        data_len: usize,
+        gas: u64,
+        outs_len: &mut usize,
+    ) -> u8 {
+        self.vm
+            .delegate_call_contract(to, data, data_len, gas, outs_len)
+    }
+}
+
+impl BlockAccess for MeteredVM {
+    fn block_basefee(&self) -> U256 {
+        self.vm.block_basefee()
+    }
+    fn block_coinbase(&self) -> Address {
+        self.vm.block_coinbase()
+    }
+    fn block_number(&self) -> u64 {
+        self.vm.block_number()
+    }
+    fn block_timestamp(&self) -> u64 {
+        self.vm.block_timestamp()
+    }
+    fn block_gas_limit(&self) -> u64 {
+        self.vm.block_gas_limit()
+    }
+}
+
+impl ChainAccess for MeteredVM {
+    fn chain_id(&self) -> u64 {
+        self.vm.chain_id()
+    }
+}
+
+impl AccountAccess for MeteredVM {
+    fn balance(&self, account: Address) -> U256 {
+        self.vm.balance(account)
+    }
+    fn contract_address(&self) -> Address {
+        self.vm.contract_address()
+    }
+    fn code(&self, account: Address) -> Vec<u8> {
+        self.vm.code(account)
+    }
+    fn code_size(&self, account: Address) -> usize {
+        self.vm.code_size(account)
+    }
+    fn code_hash(&self, account: Address) -> B256 {
+        self.vm.code_hash(account)
+    }
+}
+
+impl MemoryAccess for MeteredVM {
+    fn pay_for_memory_grow(&self, pages: u16) {
+        self.vm.pay_for_memory_grow(pages)
+    }
+}
+
+impl MessageAccess for MeteredVM {
+    fn msg_sender(&self) -> Address {
+        self.vm.msg_sender()
+    }
+    fn msg_reentrant(&self) -> bool {
+        self.vm.msg_reentrant()
+    }
+    fn msg_value(&self) -> U256 {
+        self.vm.msg_value()
+    }
+    fn tx_origin(&self) -> Address {
+        self.vm.tx_origin()
+    }
+}
+
+impl MeteringAccess for MeteredVM {
+    fn evm_gas_left(&self) -> u64 {
+        self.vm.evm_gas_left()
+    }
+    fn evm_ink_left(&self) -> u64 {
+        self.vm.evm_ink_left()
+    }
+    fn tx_gas_price(&self) -> U256 {
+        self.vm.tx_gas_price()
+    }
+    fn tx_ink_price(&self) -> u32 {
+        self.vm.tx_ink_price()
+    }
+}
+
+impl CallAccess for MeteredVM {
+    fn static_call(
+        &self,
+        context: &dyn StaticCallContext,
+        to: Address,
+        data: &[u8],
+    ) -> Result<Vec<u8>, CallError> {
+        self.vm.static_call(context, to, data)
+    }
+    unsafe fn delegate_call(
+        &self,
+        context: &dyn MutatingCallContext,
+        to: Address,
+        data: &[u8],
+    ) -> Result<Vec<u8>, CallError> {
+        self.vm.delegate_call(context, to, data)
+    }
+    fn call(
+        &self,
+        context: &dyn MutatingCallContext,
+        to: Address,
+        data: &[u8],
+    ) -> Result<Vec<u8>, CallError> {
+        self.vm.call(context, to, data)
+    }
+}
+
+impl DeploymentAccess for MeteredVM {
+    unsafe fn deploy(
+        &self,
+        code: &[u8],
+        endowment: U256,
+        salt: Option<B256>,
+    ) -> Result<Address, Vec<u8>> {
+        self.vm.deploy(code, endowment, salt)
+    }
+}
+
+impl LogAccess for MeteredVM {
+    fn emit_log(&self, input: &[u8], num_topics: usize) {
+        self.vm.emit_log(input, num_topics)
+    }
+    fn raw_log(&self, topics: &[B256], data: &[u8]) -> Result<(), &'static str> {
+        self.vm.raw_log(topics, data)
+    }
+}
+
+impl ValueTransfer for MeteredVM {
+    fn transfer_eth(&self, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.vm.transfer_eth(to, amount)
+    }
+}
+
+impl Host for MeteredVM {}
+
+// 以 OWNER 部署由计量虚拟机驱动的合约
+fn deploy_metered() -> (MeteredVM, StylusNFT) {
+    let vm = MeteredVM::new(TestVM::default());
+    vm.vm.set_sender(OWNER);
+    let mut contract = StylusNFT::from(&vm);
+    contract.constructor(OWNER);
+    (vm, contract)
+}
+
+// Erc721 中 owners 和 balances 映射的基准槽，StylusNFT 的 erc721 字段位于槽 0
+const OWNERS_SLOT: u64 = 0;
+const BALANCES_SLOT: u64 = 1;
+
+// 计算映射中 key 对应的存储槽
+fn mapping_slot(key: B256, base: u64) -> U256 {
+    U256::from_be_bytes(keccak((key, U256::from(base)).abi_encode()).0)
+}
+
+// 计算 balances[account] 的存储槽
+fn balance_slot(account: Address) -> U256 {
+    mapping_slot(account.into_word(), BALANCES_SLOT)
+}
+
+// 计算 owners[token_id] 的存储槽
+fn ownership_slot(token_id: U256) -> U256 {
+    mapping_slot(token_id.into(), OWNERS_SLOT)
+}
+
+#[test]
+fn mint_and_burn_skip_zero_address_balance() {
+    let (vm, mut c) = deploy_metered();
+    let (_, mint) = vm.measure(|| c.mint_to(ALICE).unwrap());
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(1));
+    vm.vm.set_sender(ALICE);
+    let (_, burn) = vm.measure(|| c.burn(U256::ZERO).unwrap());
+    // 零地址的余额槽从未写入
+    assert!(vm.vm.get_storage(balance_slot(Address::ZERO)).is_zero());
+    assert_eq!(c.erc721.balance_of(Address::ZERO).unwrap(), U256::ZERO);
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::ZERO);
+    println!("mint: {mint:?}\nburn: {burn:?}");
+}
+
+#[test]
+fn transfer_balance_updates_are_exact() {
+    let (vm, mut c) = deploy_metered();
+    c.mint_to(ALICE).unwrap();
+    vm.vm.set_sender(ALICE);
+    let (_, transfer) = vm.measure(|| c.transfer_from(ALICE, BOB, U256::ZERO).unwrap());
+    assert_eq!(
+        vm.vm.get_storage(balance_slot(ALICE)),
+        B256::ZERO,
+        "ALICE 的余额减为 0"
+    );
+    assert_eq!(
+        U256::from_be_bytes(vm.vm.get_storage(balance_slot(BOB)).0),
+        U256::from(1)
+    );
+    println!("transfer: {transfer:?}");
+}
+
+#[test]
+fn ownership_packs_owner_and_timestamp_into_one_slot() {
+    let (vm, mut c) = deploy_metered();
+    vm.vm.set_block_timestamp(1_000);
+    let (_, mint) = vm.measure(|| c.mint_to(ALICE).unwrap());
+    vm.vm.set_block_timestamp(2_000);
+    vm.vm.set_sender(ALICE);
+    let (_, transfer) = vm.measure(|| c.transfer_from(ALICE, BOB, U256::ZERO).unwrap());
+    // 拥有者位于低 20 字节，转移时间紧随其后，结构体的下一个槽未被使用
+    let slot = ownership_slot(U256::ZERO);
+    let word = vm.vm.get_storage(slot);
+    assert_eq!(Address::from_slice(&word[12..]), BOB);
+    assert_eq!(u64::from_be_bytes(word[4..12].try_into().unwrap()), 2_000);
+    assert!(vm.vm.get_storage(slot + U256::from(1)).is_zero());
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+    // 拥有者与转移时间分开存储时，铸造多一次新写入，转账多一次修改
+    println!(
+        "mint: {} gas packed, {} gas unpacked",
+        mint.gas,
+        mint.gas + COLD_SLOAD + SSTORE_SET
+    );
+    println!(
+        "transfer: {} gas packed, {} gas unpacked",
+        transfer.gas,
+        transfer.gas + COLD_SLOAD + SSTORE_RESET
+    );
+}
+
+#[test]
+fn transfer_from_reads_owner_once() {
+    // 两份状态相同的部署，分别走重构后的路径和重构前的路径
+    let (new_vm, mut new) = deploy_metered();
+    let (old_vm, mut old) = deploy_metered();
+    new.mint_to(ALICE).unwrap();
+    old.mint_to(ALICE).unwrap();
+    new_vm.vm.set_sender(ALICE);
+    old_vm.vm.set_sender(ALICE);
+    let (_, after) = new_vm.measure(|| new.erc721.transfer_from(ALICE, BOB, U256::ZERO).unwrap());
+    // 重构前：授权检查读取一次拥有者，transfer 再读取一次
+    let (_, before) = old_vm.measure(|| {
+        old.erc721
+            .require_authorized_to_spend(ALICE, U256::ZERO)
+            .unwrap();
+        old.erc721.transfer(U256::ZERO, ALICE, BOB).unwrap();
+    });
+    assert!(after.loads < before.loads);
+    assert_eq!(new.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+    assert_eq!(old.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+    println!("transfer_from: {after:?}\nauthorize + transfer: {before:?}");
+}
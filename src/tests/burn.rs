@@ -0,0 +1,505 @@
+use super::*;
+use crate::erc721::{
+    BadNonce, Burn, BurnDisabled, Erc721, Erc721Error, ExpiredSignature, InvalidSignature,
+    InvalidTokenId, NotApproved, NotBurnAuthority, NotContractOwner, NotOwner, Transfer,
+    UpdateUser,
+};
+use crate::{AdminBurn, PointsEarned, Redeemed};
+use alloy_sol_types::SolEvent;
+
+// 销毁授权合约
+const AUTHORITY: Address = address!("000000000000000000000000000000000000a070");
+
+// 部署合约，为 ALICE 铸造 token 0 并开启受限销毁
+fn restricted() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.set_restricted_burn(true).unwrap();
+    c.set_burn_authority(AUTHORITY, true).unwrap();
+    vm.set_sender(ALICE);
+    (vm, c)
+}
+
+fn not_authority(caller: Address) -> Result<(), Erc721Error> {
+    Err(Erc721Error::NotBurnAuthority(NotBurnAuthority { caller }))
+}
+
+#[test]
+fn holder_burns_are_restricted() {
+    let (_vm, mut c) = restricted();
+    let id = U256::ZERO;
+    assert_eq!(c.burn(id), not_authority(ALICE));
+    assert_eq!(c.burn_batch(vec![id]), not_authority(ALICE));
+    assert_eq!(c.redeem(id), not_authority(ALICE));
+    assert!(c.erc721.exists(id).unwrap());
+}
+
+#[test]
+fn unwrap_is_restricted() {
+    let (vm, mut c) = restricted();
+    super::wrap::mock_collection(&vm, vm.contract_address());
+    c.wrap(super::wrap::COLLECTION, super::wrap::EXTERNAL_ID)
+        .unwrap();
+    assert_eq!(c.unwrap(U256::from(1)), not_authority(ALICE));
+}
+
+#[test]
+fn signed_burns_are_restricted() {
+    let (vm, mut c) = restricted();
+    let id = U256::ZERO;
+    let deadline = U256::from(100);
+    vm.set_sender(RELAYER);
+    let type_hash = keccak("Burn(address owner,uint256 tokenId,uint256 nonce,uint256 deadline)");
+    let hash = keccak((type_hash, ALICE, id, U256::ZERO, deadline).abi_encode());
+    let (v, r, s) = sign(&vm, ALICE, hash);
+    assert_eq!(
+        c.burn_with_sig(ALICE, id, deadline, v, r, s),
+        not_authority(RELAYER)
+    );
+    vm.set_sender(OWNER);
+    c.set_game_signer(BOB).unwrap();
+    vm.set_sender(RELAYER);
+    let type_hash = keccak("GameBurn(address player,uint256 tokenId,uint256 nonce)");
+    let hash = keccak((type_hash, ALICE, id, U256::ZERO).abi_encode());
+    let (v, r, s) = sign(&vm, BOB, hash);
+    assert_eq!(
+        c.burn_authorized(ALICE, id, U256::ZERO, v, r, s),
+        not_authority(RELAYER)
+    );
+    // 由销毁授权合约提交时通过，测试环境不回滚失败调用已消耗的 nonce
+    let hash = keccak((type_hash, ALICE, id, U256::from(1)).abi_encode());
+    let (v, r, s) = sign(&vm, BOB, hash);
+    vm.set_sender(AUTHORITY);
+    c.burn_authorized(ALICE, id, U256::from(1), v, r, s)
+        .unwrap();
+    assert!(!c.erc721.exists(id).unwrap());
+}
+
+#[test]
+fn authority_burns_approved_tokens() {
+    let (vm, mut c) = restricted();
+    c.erc721.approve(AUTHORITY, U256::ZERO).unwrap();
+    vm.set_sender(AUTHORITY);
+    c.redeem(U256::ZERO).unwrap();
+    assert_eq!(c.redeemer_of(U256::ZERO).unwrap(), AUTHORITY);
+}
+
+// 部署合约，为 ALICE 铸造 count 个 token，每次销毁奖励 10 积分
+fn rewarded(count: usize) -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    for _ in 0..count {
+        c.mint_to(ALICE).unwrap();
+    }
+    c.set_points_per_burn(U256::from(10)).unwrap();
+    vm.set_sender(ALICE);
+    (vm, c)
+}
+
+#[test]
+fn every_holder_burn_earns_points() {
+    let (_vm, mut c) = rewarded(4);
+    c.burn(U256::ZERO).unwrap();
+    c.burn_batch(vec![U256::from(1), U256::from(2)]).unwrap();
+    c.redeem(U256::from(3)).unwrap();
+    assert_eq!(c.points_of(ALICE).unwrap(), U256::from(40));
+}
+
+#[test]
+fn signed_burns_credit_the_owner() {
+    let (vm, mut c) = rewarded(2);
+    let deadline = U256::from(100);
+    vm.set_sender(RELAYER);
+    let type_hash = keccak("Burn(address owner,uint256 tokenId,uint256 nonce,uint256 deadline)");
+    let hash = keccak((type_hash, ALICE, U256::ZERO, U256::ZERO, deadline).abi_encode());
+    let (v, r, s) = sign(&vm, ALICE, hash);
+    c.burn_with_sig(ALICE, U256::ZERO, deadline, v, r, s)
+        .unwrap();
+    vm.set_sender(OWNER);
+    c.set_game_signer(BOB).unwrap();
+    vm.set_sender(RELAYER);
+    let type_hash = keccak("GameBurn(address player,uint256 tokenId,uint256 nonce)");
+    let hash = keccak((type_hash, ALICE, U256::from(1), U256::ZERO).abi_encode());
+    let (v, r, s) = sign(&vm, BOB, hash);
+    c.burn_authorized(ALICE, U256::from(1), U256::ZERO, v, r, s)
+        .unwrap();
+    // 积分记给拥有者而非中继者
+    assert_eq!(c.points_of(ALICE).unwrap(), U256::from(20));
+    assert_eq!(c.points_of(RELAYER).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn unwrap_earns_no_points() {
+    let (vm, mut c) = rewarded(0);
+    super::wrap::mock_collection(&vm, vm.contract_address());
+    c.wrap(super::wrap::COLLECTION, super::wrap::EXTERNAL_ID)
+        .unwrap();
+    c.unwrap(U256::ZERO).unwrap();
+    assert_eq!(c.points_of(ALICE).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn burn_clears_approval_and_enumeration() {
+    let (vm, mut c) = rewarded(2);
+    c.erc721.approve(BOB, U256::ZERO).unwrap();
+    c.burn(U256::ZERO).unwrap();
+    assert!(!c.erc721.exists(U256::ZERO).unwrap());
+    assert_eq!(c.erc721.get_approved(U256::ZERO).unwrap(), Address::ZERO);
+    assert_eq!(
+        c.erc721.tokens_of_owner(ALICE).unwrap(),
+        vec![U256::from(1)]
+    );
+    assert_eq!(c.erc721.enumerable_total_supply().unwrap(), U256::from(1));
+    assert_eq!(c.erc721.token_by_index(U256::ZERO).unwrap(), U256::from(1));
+    assert_eq!(c.total_supply().unwrap(), U256::from(1));
+    // 最后一条 Transfer 事件为 (ALICE, 0, 0)
+    let logs = vm.get_emitted_logs();
+    let (topics, _) = logs
+        .iter()
+        .rev()
+        .find(|(topics, _)| topics[0] == Transfer::SIGNATURE_HASH)
+        .unwrap();
+    assert_eq!(topics[1], ALICE.into_word());
+    assert_eq!(topics[2], Address::ZERO.into_word());
+    assert_eq!(topics[3], FixedBytes::<32>::ZERO);
+}
+
+#[test]
+fn holder_redeems_token() {
+    let (vm, mut c) = rewarded(1);
+    c.redeem(U256::ZERO).unwrap();
+    assert!(!c.erc721.exists(U256::ZERO).unwrap());
+    assert_eq!(c.redeemer_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(count_logs::<Redeemed>(&vm), 1);
+}
+
+#[test]
+fn unauthorized_redeem_reverts() {
+    let (vm, mut c) = rewarded(1);
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.redeem(U256::ZERO),
+        Err(Erc721Error::NotApproved(NotApproved {
+            owner: ALICE,
+            spender: BOB,
+            token_id: U256::ZERO,
+        }))
+    );
+    assert!(c.erc721.exists(U256::ZERO).unwrap());
+    assert_eq!(c.redeemer_of(U256::ZERO).unwrap(), Address::ZERO);
+}
+
+#[test]
+fn burn_batch_burns_every_token() {
+    let (vm, mut c) = rewarded(3);
+    c.burn_batch(vec![U256::ZERO, U256::from(2)]).unwrap();
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(1));
+    assert_eq!(c.total_supply().unwrap(), U256::from(1));
+    assert!(!c.erc721.exists(U256::ZERO).unwrap());
+    assert!(c.erc721.exists(U256::from(1)).unwrap());
+    assert_eq!(count_logs::<Transfer>(&vm), 5);
+}
+
+#[test]
+fn burn_batch_rejects_unowned_token() {
+    let (vm, mut c) = rewarded(1);
+    vm.set_sender(OWNER);
+    c.mint_to(BOB).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.burn_batch(vec![U256::ZERO, U256::from(1)]),
+        Err(Erc721Error::NotApproved(NotApproved {
+            owner: BOB,
+            spender: ALICE,
+            token_id: U256::from(1),
+        }))
+    );
+    assert!(c.erc721.exists(U256::from(1)).unwrap());
+}
+
+#[test]
+fn burn_batch_rejects_duplicates() {
+    let (_vm, mut c) = rewarded(1);
+    assert_eq!(
+        c.burn_batch(vec![U256::ZERO, U256::ZERO]),
+        Err(Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::ZERO
+        }))
+    );
+}
+
+#[test]
+fn owner_admin_burns_held_token() {
+    let (vm, mut c) = rewarded(1);
+    vm.set_sender(OWNER);
+    c.admin_burn(U256::ZERO).unwrap();
+    assert!(!c.erc721.exists(U256::ZERO).unwrap());
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::ZERO);
+    let logs = vm.get_emitted_logs();
+    let (topics, _) = logs.last().unwrap();
+    assert_eq!(topics[0], AdminBurn::SIGNATURE_HASH);
+    assert_eq!(topics[1], ALICE.into_word());
+    // 管理员销毁不奖励积分
+    assert_eq!(c.points_of(OWNER).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn admin_burn_is_owner_only() {
+    let (_vm, mut c) = rewarded(1);
+    assert_eq!(
+        c.admin_burn(U256::ZERO),
+        Err(Erc721Error::NotContractOwner(NotContractOwner {
+            caller: ALICE
+        }))
+    );
+    assert!(c.erc721.exists(U256::ZERO).unwrap());
+}
+
+#[test]
+fn burn_emits_transfer_and_burn() {
+    let (vm, mut c) = rewarded(1);
+    c.burn(U256::ZERO).unwrap();
+    let logs = vm.get_emitted_logs();
+    let burn = logs
+        .iter()
+        .find(|(topics, _)| topics[0] == Burn::SIGNATURE_HASH)
+        .unwrap();
+    assert_eq!(burn.0[1], ALICE.into_word());
+    assert_eq!(burn.0[2], FixedBytes::<32>::ZERO);
+    // 铸造和销毁各一条 Transfer
+    assert_eq!(count_logs::<Transfer>(&vm), 2);
+    assert_eq!(count_logs::<Burn>(&vm), 1);
+}
+
+#[test]
+fn burning_several_tokens_accumulates_points() {
+    let (vm, mut c) = rewarded(3);
+    for id in 0..3u64 {
+        c.burn(U256::from(id)).unwrap();
+        assert_eq!(c.points_of(ALICE).unwrap(), U256::from(10 * (id + 1)));
+    }
+    assert_eq!(count_logs::<PointsEarned>(&vm), 3);
+    // 最后一条事件记录累计积分
+    let logs = vm.get_emitted_logs();
+    let (topics, data) = logs
+        .iter()
+        .rev()
+        .find(|(topics, _)| topics[0] == PointsEarned::SIGNATURE_HASH)
+        .unwrap();
+    assert_eq!(topics[1], ALICE.into_word());
+    assert_eq!(
+        <(U256, U256)>::abi_decode_params(data, true).unwrap(),
+        (U256::from(10), U256::from(30))
+    );
+    assert_eq!(c.points_of(BOB).unwrap(), U256::ZERO);
+}
+
+// ALICE 签署的销毁 token_id 的授权
+fn burn_sig(vm: &TestVM, token_id: u64, nonce: u64) -> (u8, FixedBytes<32>, FixedBytes<32>) {
+    let type_hash = keccak("Burn(address owner,uint256 tokenId,uint256 nonce,uint256 deadline)");
+    let hash = keccak(
+        (
+            type_hash,
+            ALICE,
+            U256::from(token_id),
+            U256::from(nonce),
+            U256::from(100),
+        )
+            .abi_encode(),
+    );
+    sign(vm, ALICE, hash)
+}
+
+#[test]
+fn relayer_submits_signed_burn() {
+    let (vm, mut c) = rewarded(1);
+    vm.set_sender(RELAYER);
+    let (v, r, s) = burn_sig(&vm, 0, 0);
+    c.burn_with_sig(ALICE, U256::ZERO, U256::from(100), v, r, s)
+        .unwrap();
+    assert!(!c.erc721.exists(U256::ZERO).unwrap());
+    assert_eq!(c.erc721.burn_nonces(ALICE).unwrap(), U256::from(1));
+}
+
+#[test]
+fn replayed_burn_nonce_is_rejected() {
+    let (vm, mut c) = rewarded(2);
+    vm.set_sender(RELAYER);
+    let (v, r, s) = burn_sig(&vm, 0, 0);
+    c.burn_with_sig(ALICE, U256::ZERO, U256::from(100), v, r, s)
+        .unwrap();
+    // 已使用的 nonce 签署的授权无法用于其他 token
+    let (v, r, s) = burn_sig(&vm, 1, 0);
+    assert_eq!(
+        c.burn_with_sig(ALICE, U256::from(1), U256::from(100), v, r, s),
+        Err(Erc721Error::InvalidSignature(InvalidSignature {}))
+    );
+    assert!(c.erc721.exists(U256::from(1)).unwrap());
+}
+
+#[test]
+fn expired_burn_signature_is_rejected() {
+    let (vm, mut c) = rewarded(1);
+    vm.set_block_timestamp(101);
+    vm.set_sender(RELAYER);
+    let (v, r, s) = burn_sig(&vm, 0, 0);
+    assert_eq!(
+        c.burn_with_sig(ALICE, U256::ZERO, U256::from(100), v, r, s),
+        Err(Erc721Error::ExpiredSignature(ExpiredSignature {
+            deadline: U256::from(100)
+        }))
+    );
+}
+
+// 禁止销毁的集合
+struct SealedParams;
+impl Erc721Params for SealedParams {
+    const NAME: &'static str = "SEALED";
+    const SYMBOL: &'static str = "SEALED";
+    const BURNABLE: bool = false;
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+#[test]
+fn non_burnable_collection_rejects_burns() {
+    let vm = TestVM::default();
+    let mut token = Erc721::<SealedParams>::from(&vm);
+    let token_id = token.mint(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        token.burn(ALICE, token_id),
+        Err(Erc721Error::BurnDisabled(BurnDisabled {}))
+    );
+    assert_eq!(token.owner_of(token_id).unwrap(), ALICE);
+    assert_eq!(token.balance_of(ALICE).unwrap(), U256::from(1));
+}
+
+#[test]
+fn burn_clears_the_rental_user() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_block_timestamp(1_000);
+    vm.set_sender(ALICE);
+    c.erc721.set_user(U256::ZERO, BOB, 2_000).unwrap();
+    assert_eq!(c.erc721.user_of(U256::ZERO).unwrap(), BOB);
+    c.burn(U256::ZERO).unwrap();
+    assert_eq!(
+        c.erc721.user_of(U256::ZERO).unwrap_err(),
+        Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::ZERO
+        })
+    );
+    assert_eq!(c.erc721.user_expires(U256::ZERO).unwrap(), U256::ZERO);
+    // 设置和清除各记录一次 UpdateUser
+    assert_eq!(count_logs::<UpdateUser>(&vm), 2);
+    let logs = vm.get_emitted_logs();
+    let (topics, data) = logs
+        .iter()
+        .rev()
+        .find(|(topics, _)| topics[0] == UpdateUser::SIGNATURE_HASH)
+        .unwrap();
+    assert_eq!(topics[1], FixedBytes::<32>::ZERO);
+    assert_eq!(topics[2], Address::ZERO.into_word());
+    assert_eq!(data.as_slice(), [0u8; 32]);
+}
+
+// 以 BOB 为游戏签名者，由 signer 签署 ALICE 销毁 token_id 的授权，以 RELAYER 作为提交者
+fn game_burn(
+    vm: &TestVM,
+    c: &mut StylusNFT,
+    signer: Address,
+    token_id: U256,
+    nonce: U256,
+) -> (u8, FixedBytes<32>, FixedBytes<32>) {
+    vm.set_sender(OWNER);
+    c.set_game_signer(BOB).unwrap();
+    vm.set_sender(RELAYER);
+    let type_hash = keccak("GameBurn(address player,uint256 tokenId,uint256 nonce)");
+    sign(
+        vm,
+        signer,
+        keccak((type_hash, ALICE, token_id, nonce).abi_encode()),
+    )
+}
+
+#[test]
+fn game_signer_authorizes_player_burn() {
+    let (vm, mut c) = rewarded(1);
+    let (v, r, s) = game_burn(&vm, &mut c, BOB, U256::ZERO, U256::ZERO);
+    c.burn_authorized(ALICE, U256::ZERO, U256::ZERO, v, r, s)
+        .unwrap();
+    assert!(!c.erc721.exists(U256::ZERO).unwrap());
+    assert_eq!(c.game_burn_nonce(ALICE).unwrap(), U256::from(1));
+    assert_eq!(c.points_of(ALICE).unwrap(), U256::from(10));
+    // 相同授权不能重放
+    assert_eq!(
+        c.burn_authorized(ALICE, U256::ZERO, U256::ZERO, v, r, s),
+        Err(Erc721Error::BadNonce(BadNonce {
+            account: ALICE,
+            expected: U256::from(1),
+            provided: U256::ZERO,
+        }))
+    );
+}
+
+#[test]
+fn burn_authorized_by_wrong_signer_reverts() {
+    let (vm, mut c) = rewarded(1);
+    let (v, r, s) = game_burn(&vm, &mut c, RELAYER, U256::ZERO, U256::ZERO);
+    assert_eq!(
+        c.burn_authorized(ALICE, U256::ZERO, U256::ZERO, v, r, s),
+        Err(Erc721Error::InvalidSignature(InvalidSignature {}))
+    );
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(c.game_burn_nonce(ALICE).unwrap(), U256::ZERO);
+}
+
+// 只允许拥有者本人销毁的集合
+struct OwnerBurnParams;
+impl Erc721Params for OwnerBurnParams {
+    const NAME: &'static str = "OB";
+    const SYMBOL: &'static str = "OB";
+    const OWNER_ONLY_BURN: bool = true;
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+#[test]
+fn approved_operator_may_burn_by_default() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.erc721.set_approval_for_all(BOB, true).unwrap();
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.erc721.require_authorized_to_burn(U256::ZERO).unwrap(),
+        ALICE
+    );
+    c.burn_batch(vec![U256::ZERO]).unwrap();
+    assert!(!c.erc721.exists(U256::ZERO).unwrap());
+}
+
+#[test]
+fn owner_only_burn_rejects_approved_operator() {
+    let vm = TestVM::default();
+    let mut token = Erc721::<OwnerBurnParams>::from(&vm);
+    token.mint(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    token.set_approval_for_all(BOB, true).unwrap();
+    token.approve(RELAYER, U256::ZERO).unwrap();
+    for spender in [BOB, RELAYER] {
+        vm.set_sender(spender);
+        assert_eq!(
+            token.require_authorized_to_burn(U256::ZERO),
+            Err(Erc721Error::NotOwner(NotOwner {
+                from: spender,
+                token_id: U256::ZERO,
+                real_owner: ALICE,
+            }))
+        );
+    }
+    vm.set_sender(ALICE);
+    assert_eq!(token.require_authorized_to_burn(U256::ZERO).unwrap(), ALICE);
+}
@@ -0,0 +1,112 @@
+use super::*;
+use crate::erc721::Erc721;
+
+// 限量 5 个且全部为稀有 token 的集合
+struct GoldenParams;
+impl Erc721Params for GoldenParams {
+    const NAME: &'static str = "GD";
+    const SYMBOL: &'static str = "GD";
+    const MAX_SUPPLY: Option<u64> = Some(5);
+    const GOLDEN_COUNT: u64 = 5;
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+// 断言 ids 中的 token 均归 owner 所有
+fn assert_owned(c: &StylusNFT, owner: Address, ids: &[u64]) {
+    for id in ids {
+        assert_eq!(
+            c.erc721.owner_of(U256::from(*id)).unwrap(),
+            owner,
+            "token {id}"
+        );
+    }
+}
+
+// 部署合约并为 ALICE 连续铸造 token 0..5
+fn run_of_five() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    vm.set_block_timestamp(1_000);
+    c.mint_consecutive(ALICE, U256::from(5)).unwrap();
+    (vm, c)
+}
+
+#[test]
+fn run_of_five_is_owned_and_enumerated() {
+    let (_vm, c) = run_of_five();
+    assert_owned(&c, ALICE, &[0, 1, 2, 3, 4]);
+    assert!(!c.erc721.exists(U256::from(5)).unwrap());
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(5));
+    assert_eq!(
+        c.erc721
+            .token_of_owner_by_index(ALICE, U256::from(4))
+            .unwrap(),
+        U256::from(4)
+    );
+    assert_eq!(c.erc721.mint_count(ALICE).unwrap(), U256::from(5));
+    for id in 0..5u64 {
+        let id = U256::from(id);
+        assert_eq!(c.erc721.minted_at(id).unwrap(), 1_000);
+        assert_eq!(
+            c.erc721.last_transfer_of(id).unwrap(),
+            (Address::ZERO, ALICE, 1_000)
+        );
+    }
+}
+
+#[test]
+fn single_mints_follow_a_run() {
+    let (_vm, mut c) = run_of_five();
+    c.mint_to(BOB).unwrap();
+    c.mint_to(BOB).unwrap();
+    assert_owned(&c, ALICE, &[0, 1, 2, 3, 4]);
+    assert_owned(&c, BOB, &[5, 6]);
+}
+
+#[test]
+fn run_follows_single_mints() {
+    let (vm, mut c) = deploy();
+    c.mint_to(BOB).unwrap();
+    c.mint_to(BOB).unwrap();
+    vm.set_block_timestamp(1_000);
+    c.mint_consecutive(ALICE, U256::from(5)).unwrap();
+    c.mint_to(BOB).unwrap();
+    assert_owned(&c, BOB, &[0, 1, 7]);
+    assert_owned(&c, ALICE, &[2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn transfer_inside_run_keeps_neighbours() {
+    let (vm, mut c) = run_of_five();
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::from(2)).unwrap();
+    assert_owned(&c, ALICE, &[0, 1, 3, 4]);
+    assert_owned(&c, BOB, &[2]);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_owned(&c, ALICE, &[1, 3, 4]);
+    assert_owned(&c, BOB, &[0, 2]);
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(3));
+}
+
+#[test]
+fn burn_inside_run_keeps_neighbours() {
+    let (vm, mut c) = run_of_five();
+    vm.set_sender(ALICE);
+    c.burn(U256::from(3)).unwrap();
+    assert!(!c.erc721.exists(U256::from(3)).unwrap());
+    assert_owned(&c, ALICE, &[0, 1, 2, 4]);
+    c.burn(U256::from(4)).unwrap();
+    assert!(!c.erc721.exists(U256::from(4)).unwrap());
+    assert_owned(&c, ALICE, &[0, 1, 2]);
+}
+
+#[test]
+fn run_draws_golden_tokens() {
+    let vm = TestVM::default();
+    let mut erc721 = Erc721::<GoldenParams>::from(&vm);
+    erc721.mint_consecutive(ALICE, U256::from(5)).unwrap();
+    for id in 0..5u64 {
+        assert!(erc721.is_golden(U256::from(id)).unwrap());
+    }
+}
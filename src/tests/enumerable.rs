@@ -0,0 +1,59 @@
+use super::*;
+
+// 为 ALICE 铸造 token 0..3，为 BOB 铸造 token 3
+fn holders() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    for _ in 0..3 {
+        c.mint_to(ALICE).unwrap();
+    }
+    c.mint_to(BOB).unwrap();
+    (vm, c)
+}
+
+fn ids(ids: &[u64]) -> Vec<U256> {
+    ids.iter().map(|id| U256::from(*id)).collect()
+}
+
+#[test]
+fn tokens_of_owner_lists_all_holdings() {
+    let (_vm, c) = holders();
+    assert_eq!(c.erc721.tokens_of_owner(ALICE).unwrap(), ids(&[0, 1, 2]));
+    assert_eq!(c.erc721.tokens_of_owner(BOB).unwrap(), ids(&[3]));
+}
+
+#[test]
+fn tokens_of_empty_owner_is_empty() {
+    let (_vm, c) = holders();
+    assert!(c.erc721.tokens_of_owner(RELAYER).unwrap().is_empty());
+}
+
+#[test]
+fn tokens_of_owner_paged_clamps_to_balance() {
+    let (_vm, c) = holders();
+    let paged = |start: u64, count: u64| {
+        c.erc721
+            .tokens_of_owner_paged(ALICE, U256::from(start), U256::from(count))
+            .unwrap()
+    };
+    assert_eq!(paged(0, 2), ids(&[0, 1]));
+    assert_eq!(paged(2, 5), ids(&[2]));
+    assert!(paged(3, 1).is_empty());
+}
+
+#[test]
+fn enumerable_supply_tracks_live_tokens() {
+    let (vm, mut c) = holders();
+    assert_eq!(c.erc721.enumerable_total_supply().unwrap(), U256::from(4));
+    vm.set_sender(ALICE);
+    c.burn(U256::ZERO).unwrap();
+    c.burn(U256::from(2)).unwrap();
+    assert_eq!(c.erc721.enumerable_total_supply().unwrap(), U256::from(2));
+    let live: Vec<U256> = (0..2)
+        .map(|i| c.erc721.token_by_index(U256::from(i)).unwrap())
+        .collect();
+    assert!(live.contains(&U256::from(1)) && live.contains(&U256::from(3)));
+    // 已销毁的 id 不会被复用，新铸造的 token 计入
+    vm.set_sender(OWNER);
+    c.mint_to(BOB).unwrap();
+    assert_eq!(c.erc721.enumerable_total_supply().unwrap(), U256::from(3));
+}
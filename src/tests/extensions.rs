@@ -0,0 +1,122 @@
+use super::*;
+use crate::erc721::{
+    Erc721, Erc721Error, ExtensionDisabled, IERC2981, IERC4906, IERC4907, IERC5007,
+    IERC721_ENUMERABLE,
+};
+
+// 关闭全部可选扩展的集合
+struct BareParams;
+impl Erc721Params for BareParams {
+    const NAME: &'static str = "BARE";
+    const SYMBOL: &'static str = "BARE";
+    const ENUMERABLE: bool = false;
+    const TIMED: bool = false;
+    const RENTABLE: bool = false;
+    const ROYALTIES: bool = false;
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+// 注册扩展后为 ALICE 铸造 token 0
+fn bare() -> (TestVM, Erc721<BareParams>) {
+    let vm = TestVM::default();
+    let mut token = Erc721::<BareParams>::from(&vm);
+    token.register_extensions();
+    token.mint(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    (vm, token)
+}
+
+fn disabled(interface: FixedBytes<4>) -> Erc721Error {
+    Erc721Error::ExtensionDisabled(ExtensionDisabled {
+        interface_id: interface,
+    })
+}
+
+#[test]
+fn enabled_extensions_are_advertised() {
+    let (_vm, c) = deploy();
+    for interface in [IERC721_ENUMERABLE, IERC5007, IERC4907, IERC2981, IERC4906] {
+        assert!(c.erc721.supports_interface(interface).unwrap());
+    }
+    assert!(c.royalty_info(U256::ZERO, U256::from(10_000)).is_ok());
+}
+
+#[test]
+fn disabled_extensions_are_not_advertised() {
+    let (_vm, token) = bare();
+    for interface in [IERC721_ENUMERABLE, IERC5007, IERC4907, IERC2981] {
+        assert!(!token.supports_interface(interface).unwrap());
+    }
+    assert!(token.supports_interface(IERC4906).unwrap());
+}
+
+#[test]
+fn disabled_extensions_revert() {
+    let (_vm, mut token) = bare();
+    assert_eq!(
+        token.token_by_index(U256::ZERO).unwrap_err(),
+        disabled(IERC721_ENUMERABLE)
+    );
+    assert_eq!(
+        token
+            .token_of_owner_by_index(ALICE, U256::ZERO)
+            .unwrap_err(),
+        disabled(IERC721_ENUMERABLE)
+    );
+    assert_eq!(
+        token.tokens_of_owner(ALICE).unwrap_err(),
+        disabled(IERC721_ENUMERABLE)
+    );
+    assert_eq!(
+        token
+            .tokens_of_owner_paged(ALICE, U256::ZERO, U256::from(1))
+            .unwrap_err(),
+        disabled(IERC721_ENUMERABLE)
+    );
+    assert_eq!(
+        token.start_time(U256::ZERO).unwrap_err(),
+        disabled(IERC5007)
+    );
+    assert_eq!(
+        token.set_user(U256::ZERO, BOB, 1).unwrap_err(),
+        disabled(IERC4907)
+    );
+    assert_eq!(token.user_of(U256::ZERO).unwrap_err(), disabled(IERC4907));
+}
+
+#[test]
+fn registered_interface_is_advertised() {
+    let (_vm, mut c) = deploy();
+    let custom = FixedBytes([0x12, 0x34, 0x56, 0x78]);
+    assert!(!c.erc721.supports_interface(custom).unwrap());
+    c.erc721.register_interface(custom);
+    assert!(c.erc721.supports_interface(custom).unwrap());
+    // 重复注册不会重复记录，0xffffffff 永远不被支持
+    c.erc721.register_interface(custom);
+    c.erc721.register_interface(FixedBytes([0xff; 4]));
+    let listed = c.erc721.supported_interfaces().unwrap();
+    assert_eq!(listed.iter().filter(|id| **id == custom).count(), 1);
+    assert!(!c.erc721.supports_interface(FixedBytes([0xff; 4])).unwrap());
+}
+
+#[test]
+fn supported_interfaces_lists_base_and_registered() {
+    let (_vm, mut c) = deploy();
+    let base = [
+        FixedBytes([0x01, 0xff, 0xc9, 0xa7]),
+        FixedBytes([0x80, 0xac, 0x58, 0xcd]),
+        FixedBytes([0x5b, 0x5e, 0x13, 0x9f]),
+    ];
+    let custom = FixedBytes([0x12, 0x34, 0x56, 0x78]);
+    c.erc721.register_interface(custom);
+    let listed = c.erc721.supported_interfaces().unwrap();
+    for interface in base.into_iter().chain([IERC2981, custom]) {
+        assert!(listed.contains(&interface));
+    }
+    // 列表中的每个 id 都能通过 supports_interface 查询
+    for interface in listed {
+        assert!(c.erc721.supports_interface(interface).unwrap());
+    }
+}
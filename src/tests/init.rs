@@ -0,0 +1,51 @@
+use super::*;
+use crate::erc721::{AlreadyInitialized, Erc721Error, NotContractOwner};
+use crate::{DEFAULT_ADMIN_ROLE, MINTER_ROLE};
+
+#[test]
+fn init_sets_owner_and_config_once() {
+    let (_vm, mut c) = deploy();
+    c.init(ALICE, "ipfs://base/".into(), U256::from(1000))
+        .unwrap();
+    assert!(c.initialized().unwrap());
+    assert_eq!(c.owner().unwrap(), ALICE);
+    assert_eq!(c.mint_price().unwrap(), U256::from(1000));
+    assert!(c.has_role(DEFAULT_ADMIN_ROLE, ALICE).unwrap());
+    assert!(c.has_role(MINTER_ROLE, ALICE).unwrap());
+}
+
+#[test]
+fn init_twice_reverts() {
+    let (_vm, mut c) = deploy();
+    c.init(OWNER, "ipfs://base/".into(), U256::from(1000))
+        .unwrap();
+    assert_eq!(
+        c.init(OWNER, "ipfs://other/".into(), U256::from(1))
+            .unwrap_err(),
+        Erc721Error::AlreadyInitialized(AlreadyInitialized {})
+    );
+    assert_eq!(c.mint_price().unwrap(), U256::from(1000));
+}
+
+#[test]
+fn init_is_owner_only_after_constructor() {
+    let (vm, mut c) = deploy();
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.init(BOB, "ipfs://base/".into(), U256::ZERO).unwrap_err(),
+        Erc721Error::NotContractOwner(NotContractOwner { caller: BOB })
+    );
+    assert!(!c.initialized().unwrap());
+}
+
+#[test]
+fn init_without_constructor_runs_once() {
+    let vm = TestVM::default();
+    vm.set_sender(BOB);
+    let mut c = StylusNFT::from(&vm);
+    c.init(ALICE, "ipfs://base/".into(), U256::from(5)).unwrap();
+    assert_eq!(c.owner().unwrap(), ALICE);
+    // 初始化后其他地址无法再次设置
+    assert!(c.init(BOB, "ipfs://base/".into(), U256::ZERO).is_err());
+    assert_eq!(c.owner().unwrap(), ALICE);
+}
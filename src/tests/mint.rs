@@ -0,0 +1,468 @@
+use super::*;
+use crate::erc721::{
+    AlreadyClaimed, AlreadyClaimedFree, AlreadyMinted, BadNonce, Erc721, Erc721Error,
+    ExpiredSignature, FreeMintEnded, GoldenMinted, InvalidSignature, InvalidTimeRange,
+    InvalidTokenId, MintThrottled, SelfReferral, TokenDataTooLong, Transfer, IERC5007,
+};
+use crate::{Referral, MAX_TOKEN_DATA_LEN, MINTER_ROLE};
+
+#[test]
+fn airdrop_mints_one_to_each_recipient() {
+    let (vm, mut c) = deploy();
+    c.airdrop(vec![ALICE, BOB, RELAYER]).unwrap();
+    for (id, holder) in [ALICE, BOB, RELAYER].into_iter().enumerate() {
+        assert_eq!(c.erc721.balance_of(holder).unwrap(), U256::from(1));
+        assert_eq!(c.erc721.owner_of(U256::from(id)).unwrap(), holder);
+    }
+    assert_eq!(c.total_supply().unwrap(), U256::from(3));
+    assert_eq!(count_logs::<Transfer>(&vm), 3);
+}
+
+#[test]
+fn airdrop_is_owner_only() {
+    let (vm, mut c) = deploy();
+    vm.set_sender(ALICE);
+    assert!(c.airdrop(vec![ALICE]).is_err());
+}
+
+#[test]
+fn timed_mint_round_trips_times() {
+    let (_vm, mut c) = deploy();
+    c.mint_timed(ALICE, 100, 200).unwrap();
+    assert_eq!(c.erc721.start_time(U256::ZERO).unwrap(), 100);
+    assert_eq!(c.erc721.end_time(U256::ZERO).unwrap(), 200);
+    assert!(c.erc721.supports_interface(IERC5007).unwrap());
+}
+
+#[test]
+fn timed_mint_rejects_end_before_start() {
+    let (_vm, mut c) = deploy();
+    assert_eq!(
+        c.mint_timed(ALICE, 200, 100).unwrap_err(),
+        Erc721Error::InvalidTimeRange(InvalidTimeRange {
+            start_time: 200,
+            end_time: 100,
+        })
+    );
+}
+
+const VOUCHER_ID: U256 = U256::from_limbs([42, 0, 0, 0]);
+
+// 由 OWNER 签署为 ALICE 铸造 token 42、价格 100 的凭证，以 ALICE 作为调用者并支付
+fn voucher() -> (TestVM, StylusNFT, (u8, FixedBytes<32>, FixedBytes<32>)) {
+    let (vm, c) = deploy();
+    let type_hash =
+        keccak("Voucher(address to,uint256 tokenId,string uri,uint256 price,uint256 deadline)");
+    let hash = keccak(
+        (
+            type_hash,
+            ALICE,
+            VOUCHER_ID,
+            keccak("ipfs://voucher"),
+            U256::from(100),
+            U256::from(2_000),
+        )
+            .abi_encode(),
+    );
+    let signature = sign(&vm, OWNER, hash);
+    vm.set_block_timestamp(1_000);
+    vm.set_sender(ALICE);
+    vm.set_value(U256::from(100));
+    // 测试环境不会把随调用附带的 ETH 计入合约余额
+    vm.set_balance(vm.contract_address(), U256::from(100));
+    (vm, c, signature)
+}
+
+fn redeem(
+    c: &mut StylusNFT,
+    (v, r, s): (u8, FixedBytes<32>, FixedBytes<32>),
+) -> Result<(), Erc721Error> {
+    c.redeem_voucher(
+        ALICE,
+        VOUCHER_ID,
+        "ipfs://voucher".into(),
+        U256::from(100),
+        U256::from(2_000),
+        v,
+        r,
+        s,
+    )
+}
+
+#[test]
+fn valid_voucher_mints_and_pays_creator() {
+    let (vm, mut c, signature) = voucher();
+    redeem(&mut c, signature).unwrap();
+    assert_eq!(c.erc721.owner_of(VOUCHER_ID).unwrap(), ALICE);
+    assert_eq!(c.erc721.token_uri(VOUCHER_ID).unwrap(), "ipfs://voucher");
+    assert_eq!(vm.balance(OWNER), U256::from(100));
+}
+
+#[test]
+fn duplicate_voucher_is_rejected() {
+    let (_vm, mut c, signature) = voucher();
+    redeem(&mut c, signature).unwrap();
+    assert_eq!(
+        redeem(&mut c, signature).unwrap_err(),
+        Erc721Error::AlreadyMinted(AlreadyMinted {
+            token_id: VOUCHER_ID
+        })
+    );
+}
+
+#[test]
+fn voucher_from_non_minter_is_rejected() {
+    let (vm, mut c, signature) = voucher();
+    vm.set_sender(OWNER);
+    c.revoke_role(MINTER_ROLE, OWNER).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        redeem(&mut c, signature).unwrap_err(),
+        Erc721Error::InvalidSignature(InvalidSignature {})
+    );
+}
+
+// 由 signer 签署 ALICE 的第 nonce 次铸造授权
+fn mint_authorization(
+    vm: &TestVM,
+    signer: Address,
+    nonce: u64,
+) -> (u8, FixedBytes<32>, FixedBytes<32>) {
+    let type_hash = keccak("MintAuthorization(address minter,uint256 nonce)");
+    sign(
+        vm,
+        signer,
+        keccak((type_hash, ALICE, U256::from(nonce)).abi_encode()),
+    )
+}
+
+#[test]
+fn nonce_mints_in_order() {
+    let (vm, mut c) = deploy();
+    vm.set_sender(ALICE);
+    for nonce in 0..2u64 {
+        let (v, r, s) = mint_authorization(&vm, OWNER, nonce);
+        c.mint_with_nonce(U256::from(nonce), v, r, s).unwrap();
+    }
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(2));
+    assert_eq!(c.mint_nonce(ALICE).unwrap(), U256::from(2));
+}
+
+#[test]
+fn out_of_order_nonce_reverts() {
+    let (vm, mut c) = deploy();
+    vm.set_sender(ALICE);
+    let (v, r, s) = mint_authorization(&vm, OWNER, 1);
+    assert_eq!(
+        c.mint_with_nonce(U256::from(1), v, r, s).unwrap_err(),
+        Erc721Error::BadNonce(BadNonce {
+            account: ALICE,
+            expected: U256::ZERO,
+            provided: U256::from(1),
+        })
+    );
+}
+
+#[test]
+fn nonce_mint_requires_owner_signature() {
+    let (vm, mut c) = deploy();
+    vm.set_sender(ALICE);
+    let (v, r, s) = mint_authorization(&vm, BOB, 0);
+    assert_eq!(
+        c.mint_with_nonce(U256::ZERO, v, r, s).unwrap_err(),
+        Erc721Error::InvalidSignature(InvalidSignature {})
+    );
+    assert_eq!(c.mint_nonce(ALICE).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn next_token_id_matches_minted_id() {
+    let (vm, mut c) = deploy();
+    assert_eq!(c.erc721.next_token_id().unwrap(), U256::ZERO);
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.burn(U256::ZERO).unwrap();
+    // 销毁不影响下一个 id
+    let next = c.erc721.next_token_id().unwrap();
+    assert_eq!(next, U256::from(2));
+    vm.set_sender(OWNER);
+    c.mint_to(BOB).unwrap();
+    assert_eq!(c.erc721.owner_of(next).unwrap(), BOB);
+}
+
+#[test]
+fn minted_at_records_mint_time() {
+    let (vm, mut c) = deploy();
+    vm.set_block_timestamp(1_000);
+    c.mint_to(ALICE).unwrap();
+    vm.set_block_timestamp(1_500);
+    c.mint_to(BOB).unwrap();
+    assert_eq!(c.erc721.minted_at(U256::ZERO).unwrap(), 1_000);
+    assert_eq!(c.erc721.minted_at(U256::from(1)).unwrap(), 1_500);
+    // 转账不改变铸造时间
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.minted_at(U256::ZERO).unwrap(), 1_000);
+}
+
+#[test]
+fn minted_at_rejects_missing_token() {
+    let (_vm, c) = deploy();
+    assert_eq!(
+        c.erc721.minted_at(U256::from(5)).unwrap_err(),
+        Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::from(5)
+        })
+    );
+}
+
+#[test]
+fn referral_credits_referrer() {
+    let (vm, mut c) = deploy();
+    vm.set_block_number(1);
+    vm.set_sender(ALICE);
+    c.mint_with_referrer(BOB).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(c.referrals_of(BOB).unwrap(), U256::from(1));
+    let logs = vm.get_emitted_logs();
+    let (topics, _) = logs.last().unwrap();
+    assert_eq!(topics[0], Referral::SIGNATURE_HASH);
+    assert_eq!(topics[1], BOB.into_word());
+    assert_eq!(topics[2], ALICE.into_word());
+}
+
+#[test]
+fn self_referral_reverts() {
+    let (vm, mut c) = deploy();
+    vm.set_block_number(1);
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.mint_with_referrer(ALICE).unwrap_err(),
+        Erc721Error::SelfReferral(SelfReferral { account: ALICE })
+    );
+    assert_eq!(c.referrals_of(ALICE).unwrap(), U256::ZERO);
+}
+
+// 由 signer 签署 ALICE 在 deadline 前的一次铸造授权，以 ALICE 作为调用者
+fn signed_mint(
+    vm: &TestVM,
+    signer: Address,
+    deadline: u64,
+) -> (u8, FixedBytes<32>, FixedBytes<32>) {
+    let type_hash = keccak("SignedMint(address minter,uint256 deadline)");
+    vm.set_block_timestamp(1_000);
+    vm.set_sender(ALICE);
+    sign(
+        vm,
+        signer,
+        keccak((type_hash, ALICE, U256::from(deadline)).abi_encode()),
+    )
+}
+
+#[test]
+fn signed_mint_mints_once_per_address() {
+    let (vm, mut c) = deploy();
+    let (v, r, s) = signed_mint(&vm, OWNER, 2_000);
+    c.mint_signed(U256::from(2_000), v, r, s).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert!(c.has_signed_minted(ALICE).unwrap());
+    vm.set_block_number(1);
+    assert_eq!(
+        c.mint_signed(U256::from(2_000), v, r, s).unwrap_err(),
+        Erc721Error::AlreadyClaimed(AlreadyClaimed { account: ALICE })
+    );
+}
+
+#[test]
+fn expired_signed_mint_reverts() {
+    let (vm, mut c) = deploy();
+    let (v, r, s) = signed_mint(&vm, OWNER, 999);
+    assert_eq!(
+        c.mint_signed(U256::from(999), v, r, s).unwrap_err(),
+        Erc721Error::ExpiredSignature(ExpiredSignature {
+            deadline: U256::from(999)
+        })
+    );
+    assert!(!c.has_signed_minted(ALICE).unwrap());
+}
+
+#[test]
+fn signed_mint_requires_owner_signature() {
+    let (vm, mut c) = deploy();
+    let (v, r, s) = signed_mint(&vm, BOB, 2_000);
+    assert_eq!(
+        c.mint_signed(U256::from(2_000), v, r, s).unwrap_err(),
+        Erc721Error::InvalidSignature(InvalidSignature {})
+    );
+}
+
+#[test]
+fn one_public_mint_per_block() {
+    let (vm, mut c) = deploy();
+    vm.set_block_number(10);
+    vm.set_sender(ALICE);
+    c.mint().unwrap();
+    assert_eq!(c.last_mint_block(ALICE).unwrap(), U256::from(10));
+    assert_eq!(
+        c.mint().unwrap_err(),
+        Erc721Error::MintThrottled(MintThrottled {
+            account: ALICE,
+            block_number: U256::from(10),
+        })
+    );
+    // 同一区块内其他地址不受影响
+    vm.set_sender(BOB);
+    c.mint().unwrap();
+    vm.set_block_number(11);
+    vm.set_sender(ALICE);
+    c.mint().unwrap();
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(2));
+}
+
+// 限量 20 个、其中 3 个为稀有 token 的集合
+struct RareParams;
+impl Erc721Params for RareParams {
+    const NAME: &'static str = "RARE";
+    const SYMBOL: &'static str = "RARE";
+    const MAX_SUPPLY: Option<u64> = Some(20);
+    const GOLDEN_COUNT: u64 = 3;
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+// 统计已铸造 token 中的稀有数量
+fn golden_count(token: &Erc721<RareParams>) -> usize {
+    (0..20u64)
+        .filter(|id| token.is_golden(U256::from(*id)).unwrap())
+        .count()
+}
+
+#[test]
+fn mint_out_assigns_exactly_golden_count() {
+    for seed in [1u64, 7, 1_000] {
+        let vm = TestVM::default();
+        vm.set_block_number(seed);
+        vm.set_block_timestamp(seed * 12);
+        let mut token = Erc721::<RareParams>::from(&vm);
+        for _ in 0..20 {
+            token.mint(ALICE).unwrap();
+        }
+        assert_eq!(golden_count(&token), 3, "seed {seed}");
+        assert_eq!(count_logs::<GoldenMinted>(&vm), 3);
+    }
+}
+
+#[test]
+fn id_mints_take_part_in_the_draw() {
+    let vm = TestVM::default();
+    vm.set_block_number(1);
+    let mut token = Erc721::<RareParams>::from(&vm);
+    // 与凭证兑换相同，先按指定 id 铸造末尾的 token，再按序铸造其余 token
+    for id in 15..20u64 {
+        token.mint_id(BOB, U256::from(id)).unwrap();
+    }
+    for _ in 0..15 {
+        token.mint(ALICE).unwrap();
+    }
+    assert_eq!(golden_count(&token), 3);
+    assert_eq!(count_logs::<GoldenMinted>(&vm), 3);
+}
+
+#[test]
+fn free_mint_is_one_per_address_up_to_the_cap() {
+    let (vm, mut c) = deploy();
+    assert_eq!(c.free_mint_remaining().unwrap(), U256::ZERO);
+    c.set_free_mint_cap(U256::from(2)).unwrap();
+    vm.set_sender(ALICE);
+    c.free_mint().unwrap();
+    assert!(c.has_claimed_free(ALICE).unwrap());
+    assert_eq!(c.free_mint_remaining().unwrap(), U256::from(1));
+    assert_eq!(
+        c.free_mint().unwrap_err(),
+        Erc721Error::AlreadyClaimedFree(AlreadyClaimedFree { account: ALICE })
+    );
+    vm.set_sender(BOB);
+    c.free_mint().unwrap();
+    assert_eq!(c.free_mint_remaining().unwrap(), U256::ZERO);
+    vm.set_sender(RELAYER);
+    assert_eq!(
+        c.free_mint().unwrap_err(),
+        Erc721Error::FreeMintEnded(FreeMintEnded { cap: U256::from(2) })
+    );
+    assert_eq!(c.total_supply().unwrap(), U256::from(2));
+}
+
+#[test]
+fn top_minters_are_sorted_by_count() {
+    let (_vm, mut c) = deploy();
+    for to in [BOB, ALICE, RELAYER, ALICE, RELAYER, ALICE] {
+        c.erc721.mint(to).unwrap();
+    }
+    assert_eq!(
+        c.erc721.top_minters(U256::from(5)).unwrap(),
+        vec![
+            (ALICE, U256::from(3)),
+            (RELAYER, U256::from(2)),
+            (BOB, U256::from(1)),
+        ]
+    );
+    assert_eq!(
+        c.erc721.top_minters(U256::from(1)).unwrap(),
+        vec![(ALICE, U256::from(3))]
+    );
+}
+
+#[test]
+fn full_leaderboard_admits_only_higher_counts() {
+    let (_vm, mut c) = deploy();
+    let minters: Vec<Address> = (0..11u8)
+        .map(|i| Address::with_last_byte(0x10 + i))
+        .collect();
+    for to in &minters {
+        c.erc721.mint(*to).unwrap();
+    }
+    // 榜单已满，数量相同的后来者不能上榜
+    let board = c.erc721.top_minters(U256::MAX).unwrap();
+    assert_eq!(board.len(), 10);
+    assert!(!board.iter().any(|(account, _)| *account == minters[10]));
+    c.erc721.mint(minters[10]).unwrap();
+    let board = c.erc721.top_minters(U256::MAX).unwrap();
+    assert_eq!(board.len(), 10);
+    assert_eq!(board[0], (minters[10], U256::from(2)));
+    assert_eq!(board[1], (minters[0], U256::from(1)));
+    assert!(!board.iter().any(|(account, _)| *account == minters[9]));
+}
+
+#[test]
+fn mint_with_data_stores_bytes() {
+    let (_vm, mut c) = deploy();
+    let token_id = c
+        .mint_with_data(ALICE, vec![0xde, 0xad, 0xbe, 0xef].into())
+        .unwrap();
+    assert_eq!(c.erc721.owner_of(token_id).unwrap(), ALICE);
+    assert_eq!(
+        c.token_data(token_id).unwrap().0,
+        vec![0xde, 0xad, 0xbe, 0xef]
+    );
+    // 未附带数据的 token 返回空
+    c.mint_to(ALICE).unwrap();
+    assert!(c.token_data(U256::from(1)).unwrap().0.is_empty());
+}
+
+#[test]
+fn mint_with_data_enforces_length_cap() {
+    let (_vm, mut c) = deploy();
+    c.mint_with_data(ALICE, vec![1; MAX_TOKEN_DATA_LEN].into())
+        .unwrap();
+    assert_eq!(
+        c.mint_with_data(ALICE, vec![1; MAX_TOKEN_DATA_LEN + 1].into())
+            .unwrap_err(),
+        Erc721Error::TokenDataTooLong(TokenDataTooLong {
+            len: U256::from(MAX_TOKEN_DATA_LEN + 1),
+            max: U256::from(MAX_TOKEN_DATA_LEN),
+        })
+    );
+    assert_eq!(c.total_supply().unwrap(), U256::from(1));
+}
@@ -0,0 +1,104 @@
+use alloy_primitives::{address, Address, FixedBytes, U256};
+use alloy_sol_types::{SolEvent, SolValue};
+use stylus_sdk::{crypto::keccak, testing::*};
+
+use crate::{eip712, Erc721Params, StylusNFT, StylusNFTParams};
+
+mod account;
+mod allowlist;
+mod approval;
+mod bench;
+mod burn;
+mod consecutive;
+mod enumerable;
+mod extensions;
+mod init;
+mod mint;
+mod ownership;
+mod payment;
+mod permit;
+mod phase;
+mod receiver;
+mod refund;
+mod relay;
+mod rescue;
+mod roles;
+mod royalty;
+mod seal;
+mod snapshot;
+mod stake;
+mod supply;
+mod transfer;
+mod uri;
+mod uses;
+mod vault;
+mod views;
+mod votes;
+mod withdraw;
+mod wrap;
+
+// 测试中使用的固定地址
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("00000000000000000000000000000000000a11ce");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const RELAYER: Address = address!("000000000000000000000000000000000000feed");
+
+// ecrecover 预编译合约地址
+const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+
+// 以 OWNER 部署合约
+fn deploy() -> (TestVM, StylusNFT) {
+    let vm = TestVM::default();
+    vm.set_sender(OWNER);
+    let mut contract = StylusNFT::from(&vm);
+    contract.constructor(OWNER);
+    (vm, contract)
+}
+
+// 模拟 ecrecover 预编译，使给定结构体哈希的签名恢复为 signer
+fn sign(
+    vm: &TestVM,
+    signer: Address,
+    struct_hash: FixedBytes<32>,
+) -> (u8, FixedBytes<32>, FixedBytes<32>) {
+    let domain = eip712::domain_separator(vm, StylusNFTParams::NAME);
+    let digest = eip712::typed_data_hash(domain, struct_hash);
+    let (v, r, s) = (27u8, digest, FixedBytes::<32>::with_last_byte(1));
+    let mut input = digest.to_vec();
+    input.extend_from_slice(&U256::from(v).to_be_bytes::<32>());
+    input.extend_from_slice(r.as_slice());
+    input.extend_from_slice(s.as_slice());
+    vm.mock_static_call(ECRECOVER, input, Ok(signer.into_word().to_vec()));
+    (v, r, s)
+}
+
+// 构造签名转账的结构体哈希
+fn transfer_hash(
+    from: Address,
+    to: Address,
+    token_id: U256,
+    nonce: U256,
+    deadline: U256,
+) -> FixedBytes<32> {
+    let type_hash =
+        keccak("Transfer(address from,address to,uint256 tokenId,uint256 nonce,uint256 deadline)");
+    keccak((type_hash, from, to, token_id, nonce, deadline).abi_encode())
+}
+
+// 统计已记录的指定事件数量
+fn count_logs<E: SolEvent>(vm: &TestVM) -> usize {
+    vm.get_emitted_logs()
+        .iter()
+        .filter(|(topics, _)| topics.first() == Some(&E::SIGNATURE_HASH))
+        .count()
+}
+
+#[test]
+fn smoke() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(1));
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, OWNER, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), OWNER);
+}
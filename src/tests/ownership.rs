@@ -0,0 +1,53 @@
+use super::*;
+use crate::erc721::{Erc721Error, NotContractOwner, NotPendingOwner, ProvenanceAlreadySet};
+
+#[test]
+fn pending_owner_accepts_ownership() {
+    let (vm, mut c) = deploy();
+    c.transfer_ownership(ALICE).unwrap();
+    // 接收前所有者不变
+    assert_eq!(c.owner().unwrap(), OWNER);
+    assert_eq!(c.pending_owner().unwrap(), ALICE);
+    vm.set_sender(ALICE);
+    c.accept_ownership().unwrap();
+    assert_eq!(c.owner().unwrap(), ALICE);
+    assert_eq!(c.pending_owner().unwrap(), Address::ZERO);
+}
+
+#[test]
+fn non_pending_caller_cannot_accept() {
+    let (vm, mut c) = deploy();
+    c.transfer_ownership(ALICE).unwrap();
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.accept_ownership().unwrap_err(),
+        Erc721Error::NotPendingOwner(NotPendingOwner { caller: BOB })
+    );
+    assert_eq!(
+        c.transfer_ownership(BOB).unwrap_err(),
+        Erc721Error::NotContractOwner(NotContractOwner { caller: BOB })
+    );
+    assert_eq!(c.owner().unwrap(), OWNER);
+}
+
+#[test]
+fn provenance_hash_is_set_once() {
+    let (_vm, mut c) = deploy();
+    let hash = FixedBytes::<32>::with_last_byte(7);
+    assert!(c.provenance_hash().unwrap().is_zero());
+    c.set_provenance_hash(hash).unwrap();
+    assert_eq!(c.provenance_hash().unwrap(), hash);
+    assert_eq!(
+        c.set_provenance_hash(FixedBytes::<32>::with_last_byte(8))
+            .unwrap_err(),
+        Erc721Error::ProvenanceAlreadySet(ProvenanceAlreadySet {})
+    );
+    assert_eq!(c.provenance_hash().unwrap(), hash);
+}
+
+#[test]
+fn erc173_owner_is_advertised() {
+    let (_vm, c) = deploy();
+    assert!(c.erc721.supports_interface(crate::IERC173).unwrap());
+    assert_eq!(c.owner().unwrap(), OWNER);
+}
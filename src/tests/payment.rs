@@ -0,0 +1,328 @@
+use super::*;
+use crate::erc721::{
+    BelowReserve, Erc721Error, FeeRequired, InsufficientAllowance, InsufficientPayment,
+    PaymentFailed, RefundFailed, TxLimitExceeded,
+};
+use crate::IERC20;
+use alloy_sol_types::SolCall;
+
+const PRICE: u64 = 100;
+
+// 部署合约并设置单价，以 ALICE 作为调用者，合约预存足够退款的余额
+fn priced() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.set_mint_price(U256::from(PRICE)).unwrap();
+    vm.set_block_number(1);
+    vm.set_sender(ALICE);
+    // 测试环境不会把随调用附带的 ETH 计入合约余额
+    vm.set_balance(vm.contract_address(), U256::from(10_000));
+    (vm, c)
+}
+
+#[test]
+fn batch_mint_with_exact_payment() {
+    let (vm, mut c) = priced();
+    vm.set_value(U256::from(3 * PRICE));
+    c.mint_batch(U256::from(3)).unwrap();
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(3));
+    assert_eq!(vm.balance(ALICE), U256::ZERO);
+}
+
+#[test]
+fn batch_mint_refunds_overpayment() {
+    let (vm, mut c) = priced();
+    vm.set_value(U256::from(3 * PRICE + 50));
+    c.mint_batch(U256::from(3)).unwrap();
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(3));
+    assert_eq!(vm.balance(ALICE), U256::from(50));
+}
+
+#[test]
+fn batch_mint_rejects_underpayment() {
+    let (vm, mut c) = priced();
+    vm.set_value(U256::from(3 * PRICE - 1));
+    assert_eq!(
+        c.mint_batch(U256::from(3)).unwrap_err(),
+        Erc721Error::InsufficientPayment(InsufficientPayment {
+            required: U256::from(3 * PRICE),
+            paid: U256::from(3 * PRICE - 1),
+        })
+    );
+}
+
+#[test]
+fn failed_refund_reverts() {
+    let (vm, mut c) = priced();
+    vm.set_balance(vm.contract_address(), U256::ZERO);
+    vm.set_value(U256::from(PRICE + 50));
+    assert_eq!(
+        c.mint_batch(U256::from(1)).unwrap_err(),
+        Erc721Error::RefundFailed(RefundFailed {
+            to: ALICE,
+            amount: U256::from(50),
+        })
+    );
+}
+
+// 设置起始价 1000、结束价 200、时间 1000 开始、持续 100 秒的荷兰式拍卖
+fn auction() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = priced();
+    vm.set_sender(OWNER);
+    c.set_auction(
+        U256::from(1_000),
+        U256::from(200),
+        U256::from(1_000),
+        U256::from(100),
+    )
+    .unwrap();
+    vm.set_sender(ALICE);
+    (vm, c)
+}
+
+#[test]
+fn auction_price_decays_linearly() {
+    let (vm, c) = auction();
+    let price_at = |now: u64| {
+        vm.set_block_timestamp(now);
+        c.current_price().unwrap()
+    };
+    assert_eq!(price_at(900), U256::from(1_000));
+    assert_eq!(price_at(1_000), U256::from(1_000));
+    assert_eq!(price_at(1_050), U256::from(600));
+    assert_eq!(price_at(1_100), U256::from(200));
+    assert_eq!(price_at(5_000), U256::from(200));
+}
+
+#[test]
+fn auction_mint_charges_current_price() {
+    let (vm, mut c) = auction();
+    vm.set_block_timestamp(1_050);
+    vm.set_value(U256::from(1_000));
+    c.mint().unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(vm.balance(ALICE), U256::from(400));
+}
+
+#[test]
+fn auction_price_never_drops_below_reserve() {
+    let (vm, mut c) = auction();
+    vm.set_sender(OWNER);
+    c.set_reserve_price(U256::from(200)).unwrap();
+    for now in [1_050, 1_099, 1_100, 5_000] {
+        vm.set_block_timestamp(now);
+        assert!(c.current_price().unwrap() >= U256::from(200));
+    }
+    assert_eq!(c.current_price().unwrap(), U256::from(200));
+    // 保留价高于已启用拍卖的结束价格时回滚
+    assert_eq!(
+        c.set_reserve_price(U256::from(300)).unwrap_err(),
+        Erc721Error::BelowReserve(BelowReserve {
+            end_price: U256::from(200),
+            reserve_price: U256::from(300),
+        })
+    );
+    assert_eq!(c.reserve_price().unwrap(), U256::from(200));
+}
+
+#[test]
+fn auction_end_price_below_reserve_reverts() {
+    let (vm, mut c) = priced();
+    vm.set_sender(OWNER);
+    c.set_reserve_price(U256::from(250)).unwrap();
+    assert_eq!(
+        c.set_auction(
+            U256::from(1_000),
+            U256::from(200),
+            U256::from(1_000),
+            U256::from(100),
+        )
+        .unwrap_err(),
+        Erc721Error::BelowReserve(BelowReserve {
+            end_price: U256::from(200),
+            reserve_price: U256::from(250),
+        })
+    );
+}
+
+#[test]
+fn batch_at_tx_limit_succeeds() {
+    let (vm, mut c) = priced();
+    vm.set_value(U256::from(10 * PRICE));
+    c.mint_batch(U256::from(10)).unwrap();
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(10));
+}
+
+#[test]
+fn batch_over_tx_limit_reverts() {
+    let (vm, mut c) = priced();
+    vm.set_value(U256::from(11 * PRICE));
+    assert_eq!(
+        c.mint_batch(U256::from(11)).unwrap_err(),
+        Erc721Error::TxLimitExceeded(TxLimitExceeded {
+            requested: U256::from(11),
+            limit: U256::from(10),
+        })
+    );
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::ZERO);
+}
+
+// 基础价格 100、斜率 10 的联合曲线
+fn bonding() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = priced();
+    vm.set_sender(OWNER);
+    c.set_bonding_curve(U256::from(PRICE), U256::from(10))
+        .unwrap();
+    vm.set_sender(ALICE);
+    (vm, c)
+}
+
+#[test]
+fn bonding_price_rises_with_each_mint() {
+    let (vm, mut c) = bonding();
+    for minted in 0..3u64 {
+        let price = U256::from(PRICE + 10 * minted);
+        assert_eq!(c.current_price().unwrap(), price);
+        vm.set_block_number(minted + 1);
+        vm.set_value(price);
+        c.mint().unwrap();
+    }
+    assert_eq!(c.current_price().unwrap(), U256::from(PRICE + 30));
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(3));
+}
+
+#[test]
+fn bonding_mint_requires_current_price() {
+    let (vm, mut c) = bonding();
+    vm.set_value(U256::from(PRICE));
+    c.mint().unwrap();
+    // 每个区块只能铸造一次，多付部分退还
+    vm.set_block_number(2);
+    vm.set_value(U256::from(PRICE + 15));
+    c.mint().unwrap();
+    assert_eq!(vm.balance(ALICE), U256::from(5));
+    vm.set_block_number(3);
+    vm.set_value(U256::from(PRICE + 10));
+    assert_eq!(
+        c.mint().unwrap_err(),
+        Erc721Error::InsufficientPayment(InsufficientPayment {
+            required: U256::from(PRICE + 20),
+            paid: U256::from(PRICE + 10),
+        })
+    );
+}
+
+const FEE: u64 = 50;
+
+// 为 ALICE 铸造 token 0，BOB 为唯一版税收款人，按 enabled 设置转账手续费
+fn fee_on_transfer(enabled: bool) -> (TestVM, StylusNFT) {
+    let (vm, mut c) = priced();
+    vm.set_sender(OWNER);
+    c.mint_to(ALICE).unwrap();
+    c.set_payees(vec![BOB], vec![U256::from(1)]).unwrap();
+    c.set_transfer_fee(U256::from(FEE), enabled).unwrap();
+    vm.set_sender(ALICE);
+    (vm, c)
+}
+
+#[test]
+fn paid_transfer_fee_goes_to_payees() {
+    let (vm, mut c) = fee_on_transfer(true);
+    assert_eq!(c.transfer_fee().unwrap(), U256::from(FEE));
+    vm.set_value(U256::from(FEE));
+    c.transfer_from(ALICE, RELAYER, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), RELAYER);
+    assert_eq!(c.splitter.releasable(BOB).unwrap(), U256::from(FEE));
+}
+
+#[test]
+fn unpaid_transfer_fee_reverts() {
+    let (vm, mut c) = fee_on_transfer(true);
+    vm.set_value(U256::from(FEE - 1));
+    assert_eq!(
+        c.transfer_from(ALICE, RELAYER, U256::ZERO),
+        Err(Erc721Error::FeeRequired(FeeRequired {
+            required: U256::from(FEE),
+            paid: U256::from(FEE - 1),
+        }))
+    );
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+}
+
+#[test]
+fn disabled_fee_transfers_for_free() {
+    let (_vm, mut c) = fee_on_transfer(false);
+    assert_eq!(c.transfer_fee().unwrap(), U256::ZERO);
+    c.transfer_from(ALICE, RELAYER, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), RELAYER);
+}
+
+#[test]
+fn disabled_fee_refunds_attached_eth() {
+    let (vm, mut c) = fee_on_transfer(false);
+    vm.set_value(U256::from(30));
+    c.safe_transfer_from(ALICE, RELAYER, U256::ZERO).unwrap();
+    assert_eq!(vm.balance(ALICE), U256::from(30));
+    assert_eq!(c.splitter.releasable(BOB).unwrap(), U256::ZERO);
+}
+
+// 模拟的稳定币合约
+const STABLE: Address = address!("0000000000000000000000000000000000000e20");
+
+// 以 STABLE 计价 PRICE 的铸造，模拟 ALICE 的授权额度为 allowance、扣款返回 success
+fn erc20_priced(allowance: u64, success: bool) -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.set_erc20_payment(STABLE, U256::from(PRICE)).unwrap();
+    let allowance_call = IERC20::allowanceCall {
+        owner: ALICE,
+        spender: vm.contract_address(),
+    };
+    vm.mock_static_call(
+        STABLE,
+        allowance_call.abi_encode(),
+        Ok(U256::from(allowance).abi_encode()),
+    );
+    let transfer_call = IERC20::transferFromCall {
+        from: ALICE,
+        to: vm.contract_address(),
+        amount: U256::from(PRICE),
+    };
+    vm.mock_call(STABLE, transfer_call.abi_encode(), Ok(success.abi_encode()));
+    vm.set_block_number(1);
+    vm.set_sender(ALICE);
+    (vm, c)
+}
+
+#[test]
+fn erc20_mint_pulls_payment() {
+    let (_vm, mut c) = erc20_priced(PRICE, true);
+    assert_eq!(c.payment_token().unwrap(), STABLE);
+    assert_eq!(c.erc20_mint_price().unwrap(), U256::from(PRICE));
+    c.mint_with_erc20().unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+}
+
+#[test]
+fn erc20_mint_requires_allowance() {
+    let (_vm, mut c) = erc20_priced(PRICE - 1, true);
+    assert_eq!(
+        c.mint_with_erc20(),
+        Err(Erc721Error::InsufficientAllowance(InsufficientAllowance {
+            allowance: U256::from(PRICE - 1),
+            required: U256::from(PRICE),
+        }))
+    );
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn refused_erc20_transfer_reverts() {
+    let (_vm, mut c) = erc20_priced(PRICE, false);
+    assert_eq!(
+        c.mint_with_erc20(),
+        Err(Erc721Error::PaymentFailed(PaymentFailed {
+            token: STABLE,
+            amount: U256::from(PRICE),
+        }))
+    );
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::ZERO);
+}
@@ -0,0 +1,122 @@
+use super::*;
+use crate::erc721::{
+    Erc721Error, ExpiredSignature, InvalidSignature, NonceInvalidated, NotApproved,
+};
+
+const DEADLINE: U256 = U256::from_limbs([2_000, 0, 0, 0]);
+
+// 构造全部授权签名的结构体哈希
+fn permit_for_all_hash(operator: Address, approved: bool, nonce: U256) -> FixedBytes<32> {
+    let type_hash = keccak(
+        "PermitForAll(address owner,address operator,bool approved,uint256 nonce,uint256 deadline)",
+    );
+    keccak((type_hash, ALICE, operator, approved, nonce, DEADLINE).abi_encode())
+}
+
+// 部署合约，时间设为 1000，以 RELAYER 作为提交者
+fn relayed() -> (TestVM, StylusNFT) {
+    let (vm, c) = deploy();
+    vm.set_block_timestamp(1_000);
+    vm.set_sender(RELAYER);
+    (vm, c)
+}
+
+#[test]
+fn permit_for_all_sets_operator() {
+    let (vm, mut c) = relayed();
+    let (v, r, s) = sign(&vm, ALICE, permit_for_all_hash(BOB, true, U256::ZERO));
+    c.erc721
+        .permit_for_all(ALICE, BOB, true, DEADLINE, v, r, s)
+        .unwrap();
+    assert!(c.erc721.is_approved_for_all(ALICE, BOB).unwrap());
+    assert_eq!(c.erc721.operator_nonces(ALICE).unwrap(), U256::from(1));
+}
+
+#[test]
+fn replayed_permit_for_all_is_rejected() {
+    let (vm, mut c) = relayed();
+    let (v, r, s) = sign(&vm, ALICE, permit_for_all_hash(BOB, true, U256::ZERO));
+    c.erc721
+        .permit_for_all(ALICE, BOB, true, DEADLINE, v, r, s)
+        .unwrap();
+    assert_eq!(
+        c.erc721
+            .permit_for_all(ALICE, BOB, true, DEADLINE, v, r, s)
+            .unwrap_err(),
+        Erc721Error::InvalidSignature(InvalidSignature {})
+    );
+}
+
+#[test]
+fn expired_permit_for_all_is_rejected() {
+    let (vm, mut c) = relayed();
+    let (v, r, s) = sign(&vm, ALICE, permit_for_all_hash(BOB, true, U256::ZERO));
+    vm.set_block_timestamp(2_001);
+    assert_eq!(
+        c.erc721
+            .permit_for_all(ALICE, BOB, true, DEADLINE, v, r, s)
+            .unwrap_err(),
+        Erc721Error::ExpiredSignature(ExpiredSignature { deadline: DEADLINE })
+    );
+    assert!(!c.erc721.is_approved_for_all(ALICE, BOB).unwrap());
+}
+
+#[test]
+fn domain_separator_follows_chain_id() {
+    let (vm, c) = relayed();
+    let cached = c.erc721.domain_separator().unwrap();
+    assert_eq!(cached, eip712::domain_separator(&vm, StylusNFTParams::NAME));
+    vm.set_chain_id(vm.chain_id() + 1);
+    let forked = c.erc721.domain_separator().unwrap();
+    assert_ne!(forked, cached);
+    assert_eq!(forked, eip712::domain_separator(&vm, StylusNFTParams::NAME));
+}
+
+// 构造 token 签名授权的结构体哈希
+fn permit_hash(spender: Address, token_id: U256, nonce: U256) -> FixedBytes<32> {
+    let type_hash =
+        keccak("Permit(address spender,uint256 tokenId,uint256 nonce,uint256 deadline)");
+    keccak((type_hash, spender, token_id, nonce, DEADLINE).abi_encode())
+}
+
+#[test]
+fn invalidated_nonce_voids_outstanding_permit() {
+    let (vm, mut c) = relayed();
+    vm.set_sender(OWNER);
+    c.mint_to(ALICE).unwrap();
+    let (v, r, s) = sign(&vm, ALICE, permit_hash(BOB, U256::ZERO, U256::ZERO));
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.erc721.invalidate_nonce(U256::ZERO).unwrap(),
+        U256::from(1)
+    );
+    assert_eq!(c.erc721.nonces(U256::ZERO).unwrap(), U256::from(1));
+    assert_eq!(count_logs::<NonceInvalidated>(&vm), 1);
+    vm.set_sender(RELAYER);
+    assert_eq!(
+        c.erc721
+            .permit(BOB, U256::ZERO, DEADLINE, v, r, s)
+            .unwrap_err(),
+        Erc721Error::InvalidSignature(InvalidSignature {})
+    );
+    // 按新 nonce 重新签发的授权可用
+    let (v, r, s) = sign(&vm, ALICE, permit_hash(BOB, U256::ZERO, U256::from(1)));
+    c.erc721.permit(BOB, U256::ZERO, DEADLINE, v, r, s).unwrap();
+    assert_eq!(c.erc721.get_approved(U256::ZERO).unwrap(), BOB);
+}
+
+#[test]
+fn only_owner_or_approved_can_invalidate_nonce() {
+    let (vm, mut c) = relayed();
+    vm.set_sender(OWNER);
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(RELAYER);
+    assert_eq!(
+        c.erc721.invalidate_nonce(U256::ZERO).unwrap_err(),
+        Erc721Error::NotApproved(NotApproved {
+            owner: ALICE,
+            spender: RELAYER,
+            token_id: U256::ZERO,
+        })
+    );
+}
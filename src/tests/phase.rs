@@ -0,0 +1,159 @@
+use super::*;
+use crate::erc721::{
+    Erc721Error, InsufficientPayment, NoActivePhase, NonexistentPhase, NotAllowlisted,
+    PhaseLimitReached, PhaseSoldOut,
+};
+use crate::merkle;
+
+// 构造两个地址的默克尔树，返回根和各自的证明
+fn tree(a: Address, b: Address) -> (FixedBytes<32>, Vec<FixedBytes<32>>, Vec<FixedBytes<32>>) {
+    let (la, lb) = (merkle::leaf(a), merkle::leaf(b));
+    let (lo, hi) = if la <= lb { (la, lb) } else { (lb, la) };
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(lo.as_slice());
+    data[32..].copy_from_slice(hi.as_slice());
+    (keccak(data), vec![lb], vec![la])
+}
+
+// 阶段 0：[1000, 2000) 价格 100 每人 1 个，白名单 ALICE；
+// 阶段 1：[2000, 3000) 价格 200 每人 2 个，白名单 BOB
+fn phased() -> (TestVM, StylusNFT, Vec<FixedBytes<32>>, Vec<FixedBytes<32>>) {
+    let (vm, mut c) = deploy();
+    let (first_root, alice_proof, _) = tree(ALICE, RELAYER);
+    let (second_root, bob_proof, _) = tree(BOB, RELAYER);
+    c.add_phase(
+        U256::from(1000),
+        U256::from(2000),
+        U256::from(100),
+        U256::from(1),
+        first_root,
+    )
+    .unwrap();
+    c.add_phase(
+        U256::from(2000),
+        U256::from(3000),
+        U256::from(200),
+        U256::from(2),
+        second_root,
+    )
+    .unwrap();
+    vm.set_balance(vm.contract_address(), U256::from(10_000));
+    (vm, c, alice_proof, bob_proof)
+}
+
+// 在新区块中以 sender 身份支付 value 铸造
+fn mint_as(
+    vm: &TestVM,
+    c: &mut StylusNFT,
+    sender: Address,
+    value: u64,
+    proof: &[FixedBytes<32>],
+) -> Result<(), Erc721Error> {
+    vm.set_block_number(vm.block_number() + 1);
+    vm.set_sender(sender);
+    vm.set_value(U256::from(value));
+    c.mint_in_phase(proof.to_vec())
+}
+
+#[test]
+fn no_phase_before_start() {
+    let (vm, mut c, alice_proof, _) = phased();
+    vm.set_block_timestamp(500);
+    assert_eq!(
+        mint_as(&vm, &mut c, ALICE, 100, &alice_proof).unwrap_err(),
+        Erc721Error::NoActivePhase(NoActivePhase {})
+    );
+}
+
+#[test]
+fn first_phase_uses_its_root_and_limit() {
+    let (vm, mut c, alice_proof, bob_proof) = phased();
+    vm.set_block_timestamp(1500);
+    assert_eq!(c.active_phase().unwrap(), U256::ZERO);
+    mint_as(&vm, &mut c, ALICE, 100, &alice_proof).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(
+        mint_as(&vm, &mut c, ALICE, 100, &alice_proof).unwrap_err(),
+        Erc721Error::PhaseLimitReached(PhaseLimitReached {
+            phase: U256::ZERO,
+            account: ALICE,
+            limit: U256::from(1),
+        })
+    );
+    assert_eq!(
+        mint_as(&vm, &mut c, BOB, 100, &bob_proof).unwrap_err(),
+        Erc721Error::NotAllowlisted(NotAllowlisted { account: BOB })
+    );
+}
+
+#[test]
+fn second_phase_uses_its_price_and_root() {
+    let (vm, mut c, alice_proof, bob_proof) = phased();
+    vm.set_block_timestamp(2500);
+    assert_eq!(c.active_phase().unwrap(), U256::from(1));
+    mint_as(&vm, &mut c, BOB, 200, &bob_proof).unwrap();
+    mint_as(&vm, &mut c, BOB, 200, &bob_proof).unwrap();
+    assert_eq!(c.erc721.balance_of(BOB).unwrap(), U256::from(2));
+    assert_eq!(
+        mint_as(&vm, &mut c, ALICE, 200, &alice_proof).unwrap_err(),
+        Erc721Error::NotAllowlisted(NotAllowlisted { account: ALICE })
+    );
+    // 按第一阶段的价格支付不足
+    let (_, _, relayer_proof) = tree(BOB, RELAYER);
+    assert_eq!(
+        mint_as(&vm, &mut c, RELAYER, 100, &relayer_proof).unwrap_err(),
+        Erc721Error::InsufficientPayment(InsufficientPayment {
+            required: U256::from(200),
+            paid: U256::from(100),
+        })
+    );
+}
+
+#[test]
+fn phase_sells_out_at_its_tranche() {
+    let (vm, mut c) = deploy();
+    // 不设白名单的免费阶段，配额 2 个
+    c.add_phase(
+        U256::from(1000),
+        U256::from(2000),
+        U256::ZERO,
+        U256::from(5),
+        FixedBytes::ZERO,
+    )
+    .unwrap();
+    assert_eq!(c.phase_remaining(U256::ZERO).unwrap(), U256::MAX);
+    c.set_phase_max_supply(U256::ZERO, U256::from(2)).unwrap();
+    vm.set_block_timestamp(1500);
+    mint_as(&vm, &mut c, ALICE, 0, &[]).unwrap();
+    assert_eq!(c.phase_remaining(U256::ZERO).unwrap(), U256::from(1));
+    mint_as(&vm, &mut c, BOB, 0, &[]).unwrap();
+    assert_eq!(c.phase_remaining(U256::ZERO).unwrap(), U256::ZERO);
+    assert_eq!(
+        mint_as(&vm, &mut c, RELAYER, 0, &[]).unwrap_err(),
+        Erc721Error::PhaseSoldOut(PhaseSoldOut {
+            phase: U256::ZERO,
+            max_in_phase: U256::from(2),
+        })
+    );
+    // 全局供应仍有余量，其他铸造方式不受影响
+    vm.set_sender(OWNER);
+    c.mint_to(RELAYER).unwrap();
+    assert_eq!(c.total_supply().unwrap(), U256::from(3));
+}
+
+#[test]
+fn phase_cap_requires_existing_phase() {
+    let (_vm, mut c) = deploy();
+    assert_eq!(
+        c.set_phase_max_supply(U256::from(3), U256::from(1)),
+        Err(Erc721Error::NonexistentPhase(NonexistentPhase {
+            phase: U256::from(3)
+        }))
+    );
+    assert_eq!(
+        c.phase_remaining(U256::from(3)),
+        Err(Erc721Error::NonexistentPhase(NonexistentPhase {
+            phase: U256::from(3)
+        }))
+    );
+}
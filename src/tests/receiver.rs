@@ -0,0 +1,231 @@
+use super::*;
+use crate::erc721::{Erc721, Erc721Error, ReceiverRefused, ReceiverReverted};
+use alloy_primitives::Bytes;
+use stylus_sdk::prelude::*;
+
+pub(super) const RECEIVER: Address = address!("00000000000000000000000000000000000ecb1e");
+const SELECTOR: [u8; 4] = [0x15, 0x0b, 0x7a, 0x02];
+
+// 模拟接收合约对 ALICE 转入 token_id 的 onERC721Received 回调结果
+pub(super) fn mock_receiver(vm: &TestVM, token_id: U256, result: Result<Vec<u8>, Vec<u8>>) {
+    vm.set_code(RECEIVER, vec![0x00]);
+    let mut call = SELECTOR.to_vec();
+    call.extend_from_slice(&(ALICE, ALICE, token_id, Bytes::new()).abi_encode_params());
+    vm.mock_call(RECEIVER, call, result);
+}
+
+// 接收合约返回正确选择器时的返回数据
+pub(super) fn accepted() -> Vec<u8> {
+    FixedBytes(SELECTOR).abi_encode()
+}
+
+// 为 ALICE 铸造 token 0、1，并以 ALICE 作为调用者
+fn held() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    (vm, c)
+}
+
+#[test]
+fn accepting_receiver_gets_token() {
+    let (vm, mut c) = held();
+    mock_receiver(&vm, U256::ZERO, Ok(accepted()));
+    c.safe_transfer_from(ALICE, RECEIVER, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), RECEIVER);
+}
+
+#[test]
+fn receiver_revert_data_is_surfaced() {
+    let (vm, mut c) = held();
+    // Error(string) 编码的回滚原因
+    let mut reason = vec![0x08, 0xc3, 0x79, 0xa0];
+    reason.extend_from_slice(&("no thanks",).abi_encode_params());
+    mock_receiver(&vm, U256::ZERO, Err(reason.clone()));
+    assert_eq!(
+        c.safe_transfer_from(ALICE, RECEIVER, U256::ZERO)
+            .unwrap_err(),
+        Erc721Error::ReceiverReverted(ReceiverReverted {
+            receiver: RECEIVER,
+            token_id: U256::ZERO,
+            reason: reason.into(),
+        })
+    );
+}
+
+#[test]
+fn wrong_selector_is_refused() {
+    let (vm, mut c) = held();
+    mock_receiver(&vm, U256::ZERO, Ok(FixedBytes([1, 2, 3, 4]).abi_encode()));
+    assert_eq!(
+        c.safe_transfer_from(ALICE, RECEIVER, U256::ZERO)
+            .unwrap_err(),
+        Erc721Error::ReceiverRefused(ReceiverRefused {
+            receiver: RECEIVER,
+            token_id: U256::ZERO,
+            returned: FixedBytes([1, 2, 3, 4]),
+        })
+    );
+}
+
+#[test]
+fn receiver_selector_matches_interface() {
+    let selector = keccak("onERC721Received(address,address,uint256,bytes)");
+    assert_eq!(
+        Erc721::<StylusNFTParams>::erc721_receiver_selector().unwrap(),
+        FixedBytes::<4>::from_slice(&selector[..4])
+    );
+    assert_eq!(
+        Erc721::<StylusNFTParams>::erc721_receiver_selector().unwrap(),
+        FixedBytes(SELECTOR)
+    );
+}
+
+// 模拟 EVM 的回滚语义：调用返回错误时恢复调用前的存储
+fn reverting<R>(
+    vm: &TestVM,
+    call: impl FnOnce() -> Result<R, Erc721Error>,
+) -> Result<R, Erc721Error> {
+    let before = vm.snapshot().storage;
+    let result = call();
+    if result.is_err() {
+        vm.clear_storage();
+        for (key, value) in before {
+            vm.set_storage(key, value);
+        }
+    }
+    result
+}
+
+#[test]
+fn batch_to_accepting_receiver_moves_all() {
+    let (vm, mut c) = held();
+    mock_receiver(&vm, U256::ZERO, Ok(accepted()));
+    mock_receiver(&vm, U256::from(1), Ok(accepted()));
+    c.safe_transfer_from_batch(
+        ALICE,
+        RECEIVER,
+        vec![U256::ZERO, U256::from(1)],
+        Vec::new().into(),
+    )
+    .unwrap();
+    assert_eq!(c.erc721.balance_of(RECEIVER).unwrap(), U256::from(2));
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn refused_token_reverts_whole_batch() {
+    let (vm, mut c) = held();
+    // 接收者接受 token 0，拒绝 token 1
+    mock_receiver(&vm, U256::ZERO, Ok(accepted()));
+    mock_receiver(&vm, U256::from(1), Ok(FixedBytes([0; 4]).abi_encode()));
+    let result = reverting(&vm, || {
+        c.safe_transfer_from_batch(
+            ALICE,
+            RECEIVER,
+            vec![U256::ZERO, U256::from(1)],
+            Vec::new().into(),
+        )
+    });
+    assert_eq!(
+        result.unwrap_err(),
+        Erc721Error::ReceiverRefused(ReceiverRefused {
+            receiver: RECEIVER,
+            token_id: U256::from(1),
+            returned: FixedBytes([0; 4]),
+        })
+    );
+    // 已转出的 token 0 随整批回滚
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(2));
+    assert_eq!(c.erc721.balance_of(RECEIVER).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn gas_starved_receiver_is_refused() {
+    let (vm, mut c) = held();
+    vm.set_sender(OWNER);
+    c.set_receiver_gas_limit(30_000).unwrap();
+    assert_eq!(c.erc721.receiver_gas_limit().unwrap(), 30_000);
+    vm.set_sender(ALICE);
+    // gas 耗尽的接收者不返回回滚数据
+    mock_receiver(&vm, U256::ZERO, Err(Vec::new()));
+    assert_eq!(
+        c.safe_transfer_from(ALICE, RECEIVER, U256::ZERO)
+            .unwrap_err(),
+        Erc721Error::ReceiverRefused(ReceiverRefused {
+            receiver: RECEIVER,
+            token_id: U256::ZERO,
+            returned: FixedBytes([0; 4]),
+        })
+    );
+    // 上限足够时接收者正常返回
+    mock_receiver(&vm, U256::from(1), Ok(accepted()));
+    c.safe_transfer_from(ALICE, RECEIVER, U256::from(1))
+        .unwrap();
+    assert_eq!(c.erc721.owner_of(U256::from(1)).unwrap(), RECEIVER);
+}
+
+#[test]
+fn unlimited_gas_surfaces_empty_revert() {
+    let (vm, mut c) = held();
+    mock_receiver(&vm, U256::ZERO, Err(Vec::new()));
+    assert_eq!(
+        c.safe_transfer_from(ALICE, RECEIVER, U256::ZERO)
+            .unwrap_err(),
+        Erc721Error::ReceiverReverted(ReceiverReverted {
+            receiver: RECEIVER,
+            token_id: U256::ZERO,
+            reason: Vec::new().into(),
+        })
+    );
+}
+
+// 关闭接收者检查的集合
+struct LaxParams;
+impl Erc721Params for LaxParams {
+    const NAME: &'static str = "LAX";
+    const SYMBOL: &'static str = "LAX";
+    const ENFORCE_RECEIVER: bool = false;
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+// 安全转账需要顶层存储，仅包装关闭检查的集合
+sol_storage! {
+    struct LaxHolder {
+        #[borrow]
+        Erc721<LaxParams> erc721;
+    }
+}
+
+unsafe impl TopLevelStorage for LaxHolder {}
+
+#[test]
+fn disabled_receiver_check_skips_callback() {
+    let vm = TestVM::default();
+    let mut holder = LaxHolder::from(&vm);
+    holder.erc721.mint(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    // 接收合约拒绝，但关闭检查时不会回调
+    mock_receiver(&vm, U256::ZERO, Ok(FixedBytes([0; 4]).abi_encode()));
+    Erc721::<LaxParams>::safe_transfer_from(&mut holder, ALICE, RECEIVER, U256::ZERO).unwrap();
+    assert_eq!(holder.erc721.owner_of(U256::ZERO).unwrap(), RECEIVER);
+}
+
+#[test]
+fn enforced_receiver_check_rejects_bad_receiver() {
+    let (vm, mut c) = held();
+    mock_receiver(&vm, U256::ZERO, Ok(FixedBytes([0; 4]).abi_encode()));
+    assert_eq!(
+        c.safe_transfer_from(ALICE, RECEIVER, U256::ZERO)
+            .unwrap_err(),
+        Erc721Error::ReceiverRefused(ReceiverRefused {
+            receiver: RECEIVER,
+            token_id: U256::ZERO,
+            returned: FixedBytes([0; 4]),
+        })
+    );
+}
@@ -0,0 +1,116 @@
+use super::*;
+use crate::erc721::{Erc721Error, NotBurnAuthority, NotOwner, RefundWindowClosed};
+
+const PRICE: u64 = 1_000;
+const TREASURY: Address = address!("0000000000000000000000000000000000007ea5");
+
+// 部署合约，开启 100 秒退款期，ALICE 在时间 1_000 以 PRICE 铸造 token 0
+fn minted() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.set_mint_price(U256::from(PRICE)).unwrap();
+    c.set_refund_window(U256::from(100)).unwrap();
+    vm.set_block_number(1);
+    vm.set_block_timestamp(1_000);
+    vm.set_sender(ALICE);
+    vm.set_value(U256::from(PRICE));
+    c.mint().unwrap();
+    vm.set_value(U256::ZERO);
+    // 测试环境不会把随调用附带的 ETH 计入合约余额
+    vm.set_balance(vm.contract_address(), U256::from(PRICE));
+    (vm, c)
+}
+
+#[test]
+fn refund_pays_the_minter() {
+    let (vm, mut c) = minted();
+    c.refund(U256::ZERO).unwrap();
+    assert_eq!(vm.balance(ALICE), U256::from(PRICE));
+    assert!(!c.erc721.exists(U256::ZERO).unwrap());
+}
+
+#[test]
+fn refund_after_transfer_is_rejected() {
+    let (vm, mut c) = minted();
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.refund(U256::ZERO),
+        Err(Erc721Error::NotOwner(NotOwner {
+            from: ALICE,
+            token_id: U256::ZERO,
+            real_owner: BOB
+        }))
+    );
+    assert_eq!(vm.balance(BOB), U256::ZERO);
+}
+
+#[test]
+fn refund_after_window_is_rejected() {
+    let (vm, mut c) = minted();
+    vm.set_block_timestamp(1_101);
+    assert_eq!(
+        c.refund(U256::ZERO),
+        Err(Erc721Error::RefundWindowClosed(RefundWindowClosed {
+            token_id: U256::ZERO,
+            deadline: U256::from(1_100)
+        }))
+    );
+}
+
+#[test]
+fn withdraw_keeps_open_refunds() {
+    let (vm, mut c) = minted();
+    vm.set_balance(vm.contract_address(), U256::from(PRICE + 500));
+    vm.set_sender(OWNER);
+    c.withdraw(TREASURY).unwrap();
+    assert_eq!(vm.balance(TREASURY), U256::from(500));
+    // 退款仍可全额支付
+    vm.set_sender(ALICE);
+    c.refund(U256::ZERO).unwrap();
+    assert_eq!(vm.balance(ALICE), U256::from(PRICE));
+}
+
+#[test]
+fn expired_refunds_are_released() {
+    let (vm, mut c) = minted();
+    vm.set_sender(OWNER);
+    c.withdraw(TREASURY).unwrap();
+    assert_eq!(vm.balance(TREASURY), U256::ZERO);
+    vm.set_block_timestamp(1_101);
+    c.withdraw(TREASURY).unwrap();
+    assert_eq!(vm.balance(TREASURY), U256::from(PRICE));
+}
+
+#[test]
+fn burned_tokens_release_their_refund() {
+    let (vm, mut c) = minted();
+    c.burn(U256::ZERO).unwrap();
+    vm.set_sender(OWNER);
+    c.withdraw(TREASURY).unwrap();
+    assert_eq!(vm.balance(TREASURY), U256::from(PRICE));
+}
+
+#[test]
+fn refund_respects_restricted_burn() {
+    let (vm, mut c) = minted();
+    vm.set_sender(OWNER);
+    c.set_restricted_burn(true).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.refund(U256::ZERO),
+        Err(Erc721Error::NotBurnAuthority(NotBurnAuthority {
+            caller: ALICE
+        }))
+    );
+}
+
+#[test]
+fn withdrawable_excludes_open_refunds() {
+    let (vm, mut c) = minted();
+    vm.set_balance(vm.contract_address(), U256::from(PRICE + 500));
+    vm.set_sender(OWNER);
+    c.set_proceeds_split(TREASURY, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.withdrawable(TREASURY).unwrap(), U256::from(500));
+    vm.set_block_timestamp(1_101);
+    assert_eq!(c.withdrawable(TREASURY).unwrap(), U256::from(PRICE + 500));
+}
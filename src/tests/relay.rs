@@ -0,0 +1,128 @@
+use super::*;
+use crate::erc721::{
+    BudgetExhausted, Erc721Error, ExpiredSignature, InvalidSignature, MarketplaceNotAllowed,
+};
+
+// 部署合约并为 ALICE 铸造 token 0，返回签名期限
+fn setup() -> (TestVM, StylusNFT, U256) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_block_timestamp(1_000);
+    vm.set_sender(RELAYER);
+    (vm, c, U256::from(2_000))
+}
+
+#[test]
+fn relayed_transfer_moves_token_and_bumps_nonce() {
+    let (vm, mut c, deadline) = setup();
+    let id = U256::ZERO;
+    let (v, r, s) = sign(
+        &vm,
+        ALICE,
+        transfer_hash(ALICE, BOB, id, U256::ZERO, deadline),
+    );
+    c.transfer_with_sig(ALICE, BOB, id, deadline, v, r, s)
+        .unwrap();
+    assert_eq!(c.erc721.owner_of(id).unwrap(), BOB);
+    assert_eq!(c.erc721.transfer_nonces(ALICE).unwrap(), U256::from(1));
+}
+
+#[test]
+fn expired_deadline_is_rejected() {
+    let (vm, mut c, deadline) = setup();
+    let id = U256::ZERO;
+    let (v, r, s) = sign(
+        &vm,
+        ALICE,
+        transfer_hash(ALICE, BOB, id, U256::ZERO, deadline),
+    );
+    vm.set_block_timestamp(2_001);
+    assert_eq!(
+        c.transfer_with_sig(ALICE, BOB, id, deadline, v, r, s),
+        Err(Erc721Error::ExpiredSignature(ExpiredSignature { deadline }))
+    );
+    assert_eq!(c.erc721.owner_of(id).unwrap(), ALICE);
+}
+
+#[test]
+fn replayed_signature_is_rejected() {
+    let (vm, mut c, deadline) = setup();
+    let id = U256::ZERO;
+    let (v, r, s) = sign(
+        &vm,
+        ALICE,
+        transfer_hash(ALICE, BOB, id, U256::ZERO, deadline),
+    );
+    c.transfer_with_sig(ALICE, BOB, id, deadline, v, r, s)
+        .unwrap();
+    // BOB 把 token 还给 ALICE 后重放同一签名
+    vm.set_sender(BOB);
+    c.transfer_from(BOB, ALICE, id).unwrap();
+    vm.set_sender(RELAYER);
+    assert_eq!(
+        c.transfer_with_sig(ALICE, BOB, id, deadline, v, r, s),
+        Err(Erc721Error::InvalidSignature(InvalidSignature {}))
+    );
+    assert_eq!(c.erc721.owner_of(id).unwrap(), ALICE);
+}
+
+#[test]
+fn relayed_transfer_respects_marketplace_restriction() {
+    let (vm, mut c, deadline) = setup();
+    vm.set_sender(OWNER);
+    c.set_restrict_transfers(true).unwrap();
+    vm.set_sender(RELAYER);
+    let id = U256::ZERO;
+    let (v, r, s) = sign(
+        &vm,
+        ALICE,
+        transfer_hash(ALICE, BOB, id, U256::ZERO, deadline),
+    );
+    assert_eq!(
+        c.transfer_with_sig(ALICE, BOB, id, deadline, v, r, s),
+        Err(Erc721Error::MarketplaceNotAllowed(MarketplaceNotAllowed {
+            caller: RELAYER
+        }))
+    );
+    vm.set_sender(OWNER);
+    c.set_allowed_marketplace(RELAYER, true).unwrap();
+    vm.set_sender(RELAYER);
+    c.transfer_with_sig(ALICE, BOB, id, deadline, v, r, s)
+        .unwrap();
+    assert_eq!(c.erc721.owner_of(id).unwrap(), BOB);
+}
+
+#[test]
+fn relayed_transfer_consumes_signer_budget() {
+    let (vm, mut c, deadline) = setup();
+    let id = U256::ZERO;
+    // ALICE 授权 BOB 为操作者，额度为 1
+    vm.set_sender(ALICE);
+    c.erc721.set_approval_for_all(BOB, true).unwrap();
+    c.erc721.set_operator_budget(BOB, U256::from(1)).unwrap();
+    vm.set_sender(RELAYER);
+    let (v, r, s) = sign(
+        &vm,
+        BOB,
+        transfer_hash(ALICE, BOB, id, U256::ZERO, deadline),
+    );
+    c.transfer_with_sig(ALICE, BOB, id, deadline, v, r, s)
+        .unwrap();
+    assert_eq!(c.erc721.operator_budget(ALICE, BOB).unwrap(), U256::ZERO);
+    // token 回到 ALICE 后，BOB 的额度已用完
+    vm.set_sender(BOB);
+    c.transfer_from(BOB, ALICE, id).unwrap();
+    vm.set_sender(RELAYER);
+    let (v, r, s) = sign(
+        &vm,
+        BOB,
+        transfer_hash(ALICE, BOB, id, U256::from(1), deadline),
+    );
+    assert_eq!(
+        c.transfer_with_sig(ALICE, BOB, id, deadline, v, r, s),
+        Err(Erc721Error::BudgetExhausted(BudgetExhausted {
+            owner: ALICE,
+            operator: BOB
+        }))
+    );
+}
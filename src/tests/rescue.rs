@@ -0,0 +1,55 @@
+use super::*;
+use crate::erc721::{Erc20TransferFailed, Erc721Error, NotContractOwner};
+use crate::{TokenRescued, IERC20};
+use alloy_sol_types::SolCall;
+
+const TOKEN: Address = address!("0000000000000000000000000000000000000e20");
+const AMOUNT: u64 = 500;
+
+// 模拟 ERC-20 对向 BOB 转出 AMOUNT 的调用返回 success
+fn mock_transfer(vm: &TestVM, success: bool) {
+    let call = IERC20::transferCall {
+        to: BOB,
+        amount: U256::from(AMOUNT),
+    };
+    vm.mock_call(TOKEN, call.abi_encode(), Ok(success.abi_encode()));
+}
+
+#[test]
+fn owner_rescues_tokens() {
+    let (vm, mut c) = deploy();
+    mock_transfer(&vm, true);
+    c.rescue_erc20(TOKEN, BOB, U256::from(AMOUNT)).unwrap();
+    let logs = vm.get_emitted_logs();
+    let (topics, data) = logs.last().unwrap();
+    assert_eq!(topics[0], TokenRescued::SIGNATURE_HASH);
+    assert_eq!(topics[1], TOKEN.into_word());
+    assert_eq!(topics[2], BOB.into_word());
+    assert_eq!(U256::abi_decode(data, true).unwrap(), U256::from(AMOUNT));
+}
+
+#[test]
+fn refused_transfer_reverts() {
+    let (vm, mut c) = deploy();
+    mock_transfer(&vm, false);
+    assert_eq!(
+        c.rescue_erc20(TOKEN, BOB, U256::from(AMOUNT)).unwrap_err(),
+        Erc721Error::Erc20TransferFailed(Erc20TransferFailed {
+            token: TOKEN,
+            to: BOB,
+            amount: U256::from(AMOUNT),
+        })
+    );
+    assert_eq!(count_logs::<TokenRescued>(&vm), 0);
+}
+
+#[test]
+fn only_owner_can_rescue() {
+    let (vm, mut c) = deploy();
+    mock_transfer(&vm, true);
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.rescue_erc20(TOKEN, BOB, U256::from(AMOUNT)).unwrap_err(),
+        Erc721Error::NotContractOwner(NotContractOwner { caller: BOB })
+    );
+}
@@ -0,0 +1,37 @@
+use super::*;
+use crate::erc721::{Erc721Error, MissingRole};
+use crate::{DEFAULT_ADMIN_ROLE, MINTER_ROLE};
+
+#[test]
+fn granted_minter_mints_until_revoked() {
+    let (vm, mut c) = deploy();
+    c.grant_role(MINTER_ROLE, ALICE).unwrap();
+    assert!(c.has_role(MINTER_ROLE, ALICE).unwrap());
+    vm.set_sender(ALICE);
+    c.mint_to(BOB).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+    vm.set_sender(OWNER);
+    c.revoke_role(MINTER_ROLE, ALICE).unwrap();
+    assert!(!c.has_role(MINTER_ROLE, ALICE).unwrap());
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.mint_to(BOB).unwrap_err(),
+        Erc721Error::MissingRole(MissingRole {
+            account: ALICE,
+            role: MINTER_ROLE,
+        })
+    );
+}
+
+#[test]
+fn only_admin_grants_roles() {
+    let (vm, mut c) = deploy();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.grant_role(MINTER_ROLE, ALICE).unwrap_err(),
+        Erc721Error::MissingRole(MissingRole {
+            account: ALICE,
+            role: DEFAULT_ADMIN_ROLE,
+        })
+    );
+}
@@ -0,0 +1,192 @@
+use super::*;
+use crate::erc721::{Erc721Error, NotSaleHook, NothingToRelease, RoyaltyReset, RoyaltySet};
+use crate::splitter::PaymentReleased;
+use crate::RoyaltyAccrued;
+
+// 设置 ALICE 与 BOB 按 1:3 分账、版税 5%，并为 ALICE 铸造 token 0
+fn split() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.set_payees(vec![ALICE, BOB], vec![U256::from(1), U256::from(3)])
+        .unwrap();
+    c.set_royalty(U256::from(500)).unwrap();
+    (vm, c)
+}
+
+// 以 RELAYER 向合约直接转入 amount
+fn pay_royalty(vm: &TestVM, c: &mut StylusNFT, amount: u64) {
+    vm.set_sender(RELAYER);
+    vm.set_value(U256::from(amount));
+    // 测试环境不会把随调用附带的 ETH 计入合约余额
+    vm.set_balance(
+        vm.contract_address(),
+        vm.balance(vm.contract_address()) + U256::from(amount),
+    );
+    c.receive().unwrap();
+    vm.set_value(U256::ZERO);
+}
+
+#[test]
+fn royalties_are_paid_to_the_splitter() {
+    let (vm, c) = split();
+    assert_eq!(
+        c.royalty_info(U256::ZERO, U256::from(10_000)).unwrap(),
+        (vm.contract_address(), U256::from(500))
+    );
+}
+
+#[test]
+fn payees_release_proportional_amounts() {
+    let (vm, mut c) = split();
+    pay_royalty(&vm, &mut c, 400);
+    assert_eq!(c.splitter.releasable(ALICE).unwrap(), U256::from(100));
+    c.splitter.release(ALICE).unwrap();
+    c.splitter.release(BOB).unwrap();
+    assert_eq!(vm.balance(ALICE), U256::from(100));
+    assert_eq!(vm.balance(BOB), U256::from(300));
+    assert_eq!(c.splitter.total_released().unwrap(), U256::from(400));
+    assert_eq!(count_logs::<PaymentReleased>(&vm), 2);
+    // 再次到账后只能提取新增部分
+    pay_royalty(&vm, &mut c, 800);
+    c.splitter.release(ALICE).unwrap();
+    assert_eq!(vm.balance(ALICE), U256::from(300));
+    assert_eq!(c.splitter.released(ALICE).unwrap(), U256::from(300));
+}
+
+#[test]
+fn nothing_to_release_reverts() {
+    let (vm, mut c) = split();
+    pay_royalty(&vm, &mut c, 400);
+    c.splitter.release(ALICE).unwrap();
+    assert_eq!(
+        c.splitter.release(ALICE).unwrap_err(),
+        Erc721Error::NothingToRelease(NothingToRelease { account: ALICE })
+    );
+}
+
+#[test]
+fn burn_resets_token_royalty() {
+    let (vm, mut c) = split();
+    c.set_token_royalty(U256::ZERO, U256::from(1_000)).unwrap();
+    assert!(c.erc721.has_token_royalty(U256::ZERO).unwrap());
+    assert_eq!(
+        c.royalty_info(U256::ZERO, U256::from(10_000)).unwrap().1,
+        U256::from(1_000)
+    );
+    vm.set_sender(ALICE);
+    c.burn(U256::ZERO).unwrap();
+    assert!(!c.erc721.has_token_royalty(U256::ZERO).unwrap());
+    // 查询回到集合默认版税
+    assert_eq!(
+        c.royalty_info(U256::ZERO, U256::from(10_000)).unwrap().1,
+        U256::from(500)
+    );
+    assert_eq!(count_logs::<RoyaltySet>(&vm), 1);
+    assert_eq!(count_logs::<RoyaltyReset>(&vm), 1);
+}
+
+#[test]
+fn unset_royalty_falls_back_to_creator() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    let price = U256::from(10_000);
+    // 未设置创作者时回退给所有者
+    assert_eq!(
+        c.royalty_info(U256::ZERO, price).unwrap(),
+        (OWNER, U256::from(500))
+    );
+    c.set_proceeds_split(BOB, RELAYER, U256::from(1_000))
+        .unwrap();
+    assert_eq!(
+        c.royalty_info(U256::ZERO, price).unwrap(),
+        (BOB, U256::from(500))
+    );
+    // 关闭回退后版税为零
+    c.set_royalty_fallback(false).unwrap();
+    assert!(!c.royalty_fallback().unwrap());
+    assert_eq!(
+        c.royalty_info(U256::ZERO, price).unwrap(),
+        (vm.contract_address(), U256::ZERO)
+    );
+}
+
+#[test]
+fn explicit_and_token_royalties_override_fallback() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    let price = U256::from(10_000);
+    c.set_royalty(U256::from(250)).unwrap();
+    c.set_token_royalty(U256::from(1), U256::from(1_000))
+        .unwrap();
+    assert_eq!(
+        c.royalty_info(U256::ZERO, price).unwrap(),
+        (vm.contract_address(), U256::from(250))
+    );
+    assert_eq!(
+        c.royalty_info(U256::from(1), price).unwrap(),
+        (vm.contract_address(), U256::from(1_000))
+    );
+    // 显式设置为零时不回退
+    c.set_royalty(U256::ZERO).unwrap();
+    assert_eq!(
+        c.royalty_info(U256::ZERO, price).unwrap(),
+        (vm.contract_address(), U256::ZERO)
+    );
+}
+
+#[test]
+fn withdrawable_tracks_deposits_and_releases() {
+    let (vm, mut c) = split();
+    assert_eq!(c.contract_balance().unwrap(), U256::ZERO);
+    pay_royalty(&vm, &mut c, 400);
+    assert_eq!(c.contract_balance().unwrap(), U256::from(400));
+    assert_eq!(c.withdrawable(ALICE).unwrap(), U256::from(100));
+    assert_eq!(c.withdrawable(BOB).unwrap(), U256::from(300));
+    c.splitter.release(ALICE).unwrap();
+    assert_eq!(c.contract_balance().unwrap(), U256::from(300));
+    assert_eq!(c.withdrawable(ALICE).unwrap(), U256::ZERO);
+    assert_eq!(c.withdrawable(BOB).unwrap(), U256::from(300));
+    pay_royalty(&vm, &mut c, 800);
+    assert_eq!(c.withdrawable(ALICE).unwrap(), U256::from(200));
+    assert_eq!(c.withdrawable(BOB).unwrap(), U256::from(900));
+    assert_eq!(c.withdrawable(RELAYER).unwrap(), U256::ZERO);
+}
+
+// 上报成交价的销售回调合约
+const HOOK: Address = address!("000000000000000000000000000000000000b00c");
+
+#[test]
+fn reported_sales_accrue_royalties() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    c.set_sale_hook(HOOK, false).unwrap();
+    c.set_token_royalty(U256::from(1), U256::from(1_000))
+        .unwrap();
+    vm.set_sender(HOOK);
+    // token 0 按回退版税记给所有者，token 1 按专属版税记给本合约
+    c.report_sale(U256::ZERO, U256::from(10_000)).unwrap();
+    c.report_sale(U256::ZERO, U256::from(2_000)).unwrap();
+    c.report_sale(U256::from(1), U256::from(10_000)).unwrap();
+    assert_eq!(c.royalties_accrued(OWNER).unwrap(), U256::from(600));
+    assert_eq!(
+        c.royalties_accrued(vm.contract_address()).unwrap(),
+        U256::from(1_000)
+    );
+    assert_eq!(c.royalties_accrued(ALICE).unwrap(), U256::ZERO);
+    assert_eq!(count_logs::<RoyaltyAccrued>(&vm), 3);
+}
+
+#[test]
+fn only_sale_hook_reports_sales() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.set_sale_hook(HOOK, false).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.report_sale(U256::ZERO, U256::from(10_000)),
+        Err(Erc721Error::NotSaleHook(NotSaleHook { caller: ALICE }))
+    );
+    assert_eq!(c.royalties_accrued(OWNER).unwrap(), U256::ZERO);
+}
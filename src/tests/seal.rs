@@ -0,0 +1,96 @@
+use super::*;
+use crate::erc721::{ApprovalForAll, ContractSealed, Erc721Error, MigrationSealed};
+
+// 部署后为 ALICE 铸造 token 0 并封存配置
+fn sealed() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.seal().unwrap();
+    (vm, c)
+}
+
+// 断言调用因配置已封存而回滚
+fn assert_sealed<T: core::fmt::Debug>(result: Result<T, Erc721Error>) {
+    assert_eq!(
+        result.unwrap_err(),
+        Erc721Error::ContractSealed(ContractSealed {})
+    );
+}
+
+#[test]
+fn seal_is_irreversible() {
+    let (_vm, mut c) = sealed();
+    assert!(c.sealed().unwrap());
+    assert_sealed(c.seal());
+}
+
+#[test]
+fn seal_blocks_metadata_setters() {
+    let (_vm, mut c) = sealed();
+    assert_sealed(c.reveal());
+    assert_sealed(c.reveal_range(U256::ZERO, U256::from(1), "ipfs://x/".into()));
+    assert_sealed(c.freeze_token(U256::ZERO));
+    assert_sealed(c.bump_metadata_version());
+}
+
+#[test]
+fn seal_blocks_owner_mints() {
+    let (_vm, mut c) = sealed();
+    assert_sealed(c.mint_to_with_uri(ALICE, "ipfs://x".into()));
+    assert_sealed(c.mint_timed(ALICE, 0, 1));
+    assert_sealed(c.grant_mint_allowance(ALICE, U256::from(1)));
+}
+
+#[test]
+fn seal_blocks_holder_overrides() {
+    let (_vm, mut c) = sealed();
+    assert_sealed(c.force_transfer(ALICE, BOB, U256::ZERO));
+    assert_sealed(c.force_set_approval_for_all(ALICE, BOB, true));
+    assert_sealed(c.seed_operator_approvals(vec![ALICE], BOB));
+    assert_sealed(c.seal_migration());
+    assert_sealed(c.admin_burn(U256::ZERO));
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+}
+
+#[test]
+fn seal_blocks_config_setters() {
+    let (_vm, mut c) = sealed();
+    assert_sealed(c.set_mint_price(U256::from(1)));
+    assert_sealed(c.set_royalty(U256::from(100)));
+    assert_sealed(c.set_transfer_fee(U256::from(1), true));
+}
+
+#[test]
+fn seal_keeps_mint_and_transfer() {
+    let (vm, mut c) = sealed();
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+    vm.set_sender(OWNER);
+    c.mint_to(ALICE).unwrap();
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(1));
+}
+
+#[test]
+fn seeded_operator_approvals_stop_after_migration_seal() {
+    let (vm, mut c) = deploy();
+    c.seed_operator_approvals(vec![ALICE, BOB], RELAYER)
+        .unwrap();
+    assert!(c.erc721.is_approved_for_all(ALICE, RELAYER).unwrap());
+    assert!(c.erc721.is_approved_for_all(BOB, RELAYER).unwrap());
+    assert_eq!(count_logs::<ApprovalForAll>(&vm), 2);
+    // 持有者仍可自行撤销
+    vm.set_sender(ALICE);
+    c.erc721.set_approval_for_all(RELAYER, false).unwrap();
+    assert!(!c.erc721.is_approved_for_all(ALICE, RELAYER).unwrap());
+    assert!(c.seed_operator_approvals(vec![OWNER], RELAYER).is_err());
+
+    vm.set_sender(OWNER);
+    c.seal_migration().unwrap();
+    assert!(c.migration_sealed().unwrap());
+    assert_eq!(
+        c.seed_operator_approvals(vec![ALICE], RELAYER),
+        Err(Erc721Error::MigrationSealed(MigrationSealed {}))
+    );
+    assert!(!c.erc721.is_approved_for_all(ALICE, RELAYER).unwrap());
+}
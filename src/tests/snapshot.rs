@@ -0,0 +1,47 @@
+use super::*;
+use crate::erc721::{Erc721Error, NonexistentSnapshot};
+
+#[test]
+fn balances_are_recorded_per_snapshot() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    assert_eq!(c.snapshot().unwrap(), U256::from(1));
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    vm.set_sender(OWNER);
+    assert_eq!(c.snapshot().unwrap(), U256::from(2));
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::from(1)).unwrap();
+    vm.set_sender(OWNER);
+    c.mint_to(BOB).unwrap();
+    let at = |owner: Address, id: u64| c.erc721.balance_of_at(owner, U256::from(id)).unwrap();
+    assert_eq!(at(ALICE, 1), U256::from(2));
+    assert_eq!(at(BOB, 1), U256::ZERO);
+    assert_eq!(at(ALICE, 2), U256::from(1));
+    assert_eq!(at(BOB, 2), U256::from(1));
+    assert_eq!(c.erc721.balance_of(BOB).unwrap(), U256::from(3));
+}
+
+#[test]
+fn unchanged_balance_reads_current_value() {
+    let (_vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.snapshot().unwrap();
+    assert_eq!(
+        c.erc721.balance_of_at(ALICE, U256::from(1)).unwrap(),
+        U256::from(1)
+    );
+}
+
+#[test]
+fn future_snapshot_reverts() {
+    let (_vm, mut c) = deploy();
+    c.snapshot().unwrap();
+    assert_eq!(
+        c.erc721.balance_of_at(ALICE, U256::from(2)).unwrap_err(),
+        Erc721Error::NonexistentSnapshot(NonexistentSnapshot {
+            snapshot_id: U256::from(2)
+        })
+    );
+}
@@ -0,0 +1,52 @@
+use super::*;
+use crate::erc721::{Erc721Error, NotOwner, TokenNotStaked, TokenStaked};
+
+// 为 ALICE 铸造 token 0，以 ALICE 作为调用者
+fn staked() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    vm.set_block_timestamp(500);
+    c.erc721.stake(U256::ZERO).unwrap();
+    (vm, c)
+}
+
+#[test]
+fn staked_token_cannot_transfer_until_unstaked() {
+    let (_vm, mut c) = staked();
+    assert!(c.erc721.is_staked(U256::ZERO).unwrap());
+    assert_eq!(c.erc721.staked_at(U256::ZERO).unwrap(), U256::from(500));
+    assert_eq!(
+        c.transfer_from(ALICE, BOB, U256::ZERO).unwrap_err(),
+        Erc721Error::TokenStaked(TokenStaked {
+            token_id: U256::ZERO
+        })
+    );
+    c.erc721.unstake(U256::ZERO).unwrap();
+    assert!(!c.erc721.is_staked(U256::ZERO).unwrap());
+    assert_eq!(c.erc721.staked_at(U256::ZERO).unwrap(), U256::ZERO);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+}
+
+#[test]
+fn only_owner_stakes_and_unstakes() {
+    let (vm, mut c) = staked();
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.erc721.unstake(U256::ZERO).unwrap_err(),
+        Erc721Error::NotOwner(NotOwner {
+            from: BOB,
+            token_id: U256::ZERO,
+            real_owner: ALICE,
+        })
+    );
+    vm.set_sender(ALICE);
+    c.erc721.unstake(U256::ZERO).unwrap();
+    assert_eq!(
+        c.erc721.unstake(U256::ZERO).unwrap_err(),
+        Erc721Error::TokenNotStaked(TokenNotStaked {
+            token_id: U256::ZERO
+        })
+    );
+}
@@ -0,0 +1,127 @@
+use super::*;
+use crate::erc721::{Erc721, Erc721Error, SupplyOverflow, SupplyUnderflow};
+
+// 不限制供应量的普通集合
+struct PlainParams;
+impl Erc721Params for PlainParams {
+    const NAME: &'static str = "PLAIN";
+    const SYMBOL: &'static str = "PLAIN";
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+fn plain() -> (TestVM, Erc721<PlainParams>) {
+    let vm = TestVM::default();
+    let token = Erc721::<PlainParams>::from(&vm);
+    (vm, token)
+}
+
+// 固定种子的 xorshift 伪随机数，保证测试可复现
+fn next_rand(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn random_mints_and_burns_keep_counters_consistent() {
+    let holders = [ALICE, BOB, RELAYER];
+    for seed in [1u64, 0x5eed, 0xdead_beef] {
+        let (_vm, mut token) = plain();
+        let mut state = seed;
+        let mut live: Vec<(U256, Address)> = Vec::new();
+        let mut minted = 0u64;
+        for _ in 0..200 {
+            let roll = next_rand(&mut state);
+            if live.is_empty() || roll % 3 != 0 {
+                let to = holders[(roll >> 8) as usize % holders.len()];
+                let token_id = token.mint(to).unwrap();
+                assert_eq!(token_id, U256::from(minted));
+                minted += 1;
+                live.push((token_id, to));
+            } else {
+                let (token_id, owner) = live.swap_remove((roll >> 8) as usize % live.len());
+                token.burn(owner, token_id).unwrap();
+                // 重复销毁与销毁从未铸造的 token 均在修改计数前回滚
+                assert!(token.burn(owner, token_id).is_err());
+                assert!(token.burn(owner, U256::from(minted)).is_err());
+            }
+            assert_eq!(token.next_token_id().unwrap(), U256::from(minted));
+            assert_eq!(token.total_supply.get(), U256::from(live.len()));
+            assert!(token.balances_consistent());
+        }
+        for holder in holders {
+            let held = live.iter().filter(|(_, owner)| *owner == holder).count();
+            assert_eq!(token.balance_of(holder).unwrap(), U256::from(held));
+        }
+    }
+}
+
+#[test]
+fn mint_past_max_id_reverts_without_panic() {
+    let (_vm, mut token) = plain();
+    token.next_token_id.set(U256::MAX);
+    assert_eq!(
+        token.mint(ALICE).unwrap_err(),
+        Erc721Error::SupplyOverflow(SupplyOverflow {})
+    );
+    assert_eq!(
+        token.mint_consecutive(ALICE, U256::from(2)).unwrap_err(),
+        Erc721Error::SupplyOverflow(SupplyOverflow {})
+    );
+    assert_eq!(token.next_token_id().unwrap(), U256::MAX);
+    assert!(token.total_supply.get().is_zero());
+}
+
+#[test]
+fn live_supply_overflow_reverts() {
+    let (_vm, mut token) = plain();
+    token.total_supply.set(U256::MAX);
+    assert_eq!(
+        token.mint(ALICE).unwrap_err(),
+        Erc721Error::SupplyOverflow(SupplyOverflow {})
+    );
+}
+
+#[test]
+fn live_supply_underflow_reverts() {
+    let (_vm, mut token) = plain();
+    token.mint(ALICE).unwrap();
+    // 人为破坏计数，正常流程下不可达
+    token.total_supply.set(U256::ZERO);
+    assert_eq!(
+        token.burn(ALICE, U256::ZERO).unwrap_err(),
+        Erc721Error::SupplyUnderflow(SupplyUnderflow {})
+    );
+}
+
+// 最大供应量为 10 的集合
+struct CappedParams;
+impl Erc721Params for CappedParams {
+    const NAME: &'static str = "CAP";
+    const SYMBOL: &'static str = "CAP";
+    const MAX_SUPPLY: Option<u64> = Some(10);
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+#[test]
+fn supply_views_stay_consistent() {
+    let vm = TestVM::default();
+    let mut token = Erc721::<CappedParams>::from(&vm);
+    for _ in 0..4 {
+        token.mint(ALICE).unwrap();
+    }
+    token.burn(ALICE, U256::from(1)).unwrap();
+    // 销毁不减少已铸造数量，也不释放剩余额度
+    assert_eq!(token.max_supply().unwrap(), U256::from(10));
+    assert_eq!(token.minted().unwrap(), U256::from(4));
+    assert_eq!(token.remaining().unwrap(), U256::from(6));
+    assert_eq!(token.total_supply.get(), U256::from(3));
+    // 超发时剩余数量归零而不是下溢
+    token.next_token_id.set(U256::from(12));
+    assert_eq!(token.remaining().unwrap(), U256::ZERO);
+}
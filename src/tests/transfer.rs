@@ -0,0 +1,653 @@
+use super::*;
+use crate::erc721::{
+    DestinationNotAllowed, Erc721, Erc721Error, HookFailed, Locked, MarketplaceNotAllowed,
+    MemoTooLong, MetadataUpdate, MinHoldNotMet, NotContractOwner, NotKycVerified, NotUnlocker,
+    OperationPaused, RecoveryModeDisabled, TokenLocked, Transfer, TransferCooldown,
+    TransferToContractSelf, Unlocked,
+};
+use crate::{ForcedTransfer, TransferMemo, MAX_MEMO_LEN};
+
+// 灵魂绑定集合
+struct SoulboundParams;
+impl Erc721Params for SoulboundParams {
+    const NAME: &'static str = "SB";
+    const SYMBOL: &'static str = "SB";
+    const SOULBOUND: bool = true;
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+// 转账冷却期为 100 秒的集合
+struct CooldownParams;
+impl Erc721Params for CooldownParams {
+    const NAME: &'static str = "CD";
+    const SYMBOL: &'static str = "CD";
+    const TRANSFER_COOLDOWN: u64 = 100;
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+// 铸造后 100 秒内可转让的集合
+struct GraceParams;
+impl Erc721Params for GraceParams {
+    const NAME: &'static str = "GR";
+    const SYMBOL: &'static str = "GR";
+    const TRANSFER_GRACE: Option<u64> = Some(100);
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+// 取得后至少持有 100 秒才可转出的集合
+struct HoldParams;
+impl Erc721Params for HoldParams {
+    const NAME: &'static str = "HD";
+    const SYMBOL: &'static str = "HD";
+    const MIN_HOLD_TIME: u64 = 100;
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+#[test]
+fn self_transfer_keeps_balance_and_enumeration() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.erc721.approve(BOB, U256::ZERO).unwrap();
+    c.transfer_from(ALICE, ALICE, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::from(1));
+    assert_eq!(
+        c.erc721.token_of_owner_by_index(ALICE, U256::ZERO).unwrap(),
+        U256::ZERO
+    );
+    assert_eq!(c.erc721.token_by_index(U256::ZERO).unwrap(), U256::ZERO);
+    assert_eq!(c.erc721.enumerable_total_supply().unwrap(), U256::from(1));
+    // 授权被清除
+    assert_eq!(c.erc721.get_approved(U256::ZERO).unwrap(), Address::ZERO);
+}
+
+#[test]
+fn self_transfer_of_soulbound_token_is_rejected() {
+    let vm = TestVM::default();
+    let mut erc721 = Erc721::<SoulboundParams>::from(&vm);
+    erc721.mint(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        erc721.transfer_from(ALICE, ALICE, U256::ZERO),
+        Err(Erc721Error::TokenLocked(TokenLocked {
+            token_id: U256::ZERO
+        }))
+    );
+}
+
+#[test]
+fn self_transfer_respects_cooldown() {
+    let vm = TestVM::default();
+    vm.set_block_timestamp(1_000);
+    let mut erc721 = Erc721::<CooldownParams>::from(&vm);
+    erc721.mint(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        erc721.transfer_from(ALICE, ALICE, U256::ZERO),
+        Err(Erc721Error::TransferCooldown(TransferCooldown {
+            token_id: U256::ZERO,
+            remaining: U256::from(100)
+        }))
+    );
+    vm.set_block_timestamp(1_100);
+    erc721.transfer_from(ALICE, ALICE, U256::ZERO).unwrap();
+}
+
+#[test]
+fn quick_second_transfer_waits_for_cooldown() {
+    let vm = TestVM::default();
+    vm.set_block_timestamp(1_000);
+    let mut erc721 = Erc721::<CooldownParams>::from(&vm);
+    erc721.mint(ALICE).unwrap();
+    vm.set_block_timestamp(1_100);
+    vm.set_sender(ALICE);
+    erc721.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    vm.set_block_timestamp(1_130);
+    vm.set_sender(BOB);
+    assert_eq!(
+        erc721.cooldown_remaining(U256::ZERO).unwrap(),
+        U256::from(70)
+    );
+    assert_eq!(
+        erc721.transfer_from(BOB, ALICE, U256::ZERO),
+        Err(Erc721Error::TransferCooldown(TransferCooldown {
+            token_id: U256::ZERO,
+            remaining: U256::from(70)
+        }))
+    );
+    vm.set_block_timestamp(1_200);
+    assert_eq!(erc721.cooldown_remaining(U256::ZERO).unwrap(), U256::ZERO);
+    erc721.transfer_from(BOB, ALICE, U256::ZERO).unwrap();
+    assert_eq!(erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+}
+
+#[test]
+fn transfer_allowed_within_grace_window() {
+    let vm = TestVM::default();
+    vm.set_block_timestamp(1_000);
+    let mut erc721 = Erc721::<GraceParams>::from(&vm);
+    erc721.mint(ALICE).unwrap();
+    assert_eq!(
+        erc721.transferable_until(U256::ZERO).unwrap(),
+        U256::from(1_100)
+    );
+    vm.set_block_timestamp(1_100);
+    vm.set_sender(ALICE);
+    erc721.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(erc721.owner_of(U256::ZERO).unwrap(), BOB);
+}
+
+#[test]
+fn transfer_locked_after_grace_window() {
+    let vm = TestVM::default();
+    vm.set_block_timestamp(1_000);
+    let mut erc721 = Erc721::<GraceParams>::from(&vm);
+    erc721.mint(ALICE).unwrap();
+    vm.set_block_timestamp(1_101);
+    vm.set_sender(ALICE);
+    assert_eq!(
+        erc721.transfer_from(ALICE, BOB, U256::ZERO),
+        Err(Erc721Error::TokenLocked(TokenLocked {
+            token_id: U256::ZERO
+        }))
+    );
+    // 锁定后仍可销毁
+    erc721.burn(ALICE, U256::ZERO).unwrap();
+    assert!(!erc721.exists(U256::ZERO).unwrap());
+}
+
+#[test]
+fn transfers_are_counted_without_mints_and_burns() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    c.transfer_from(ALICE, BOB, U256::from(1)).unwrap();
+    vm.set_sender(BOB);
+    c.transfer_from(BOB, ALICE, U256::ZERO).unwrap();
+    c.burn(U256::from(1)).unwrap();
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.total_transfers().unwrap(), U256::from(4));
+    assert_eq!(c.erc721.transfer_count(U256::ZERO).unwrap(), U256::from(3));
+    assert_eq!(
+        c.erc721.transfer_count(U256::from(1)).unwrap(),
+        U256::from(1)
+    );
+}
+
+#[test]
+fn only_unlocker_can_release_locked_token() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.erc721.lock(U256::ZERO, BOB).unwrap();
+    assert!(c.erc721.locked(U256::ZERO).unwrap());
+    assert_eq!(
+        c.transfer_from(ALICE, RELAYER, U256::ZERO),
+        Err(Erc721Error::TokenLocked(TokenLocked {
+            token_id: U256::ZERO
+        }))
+    );
+    // 拥有者不能自行解锁
+    assert_eq!(
+        c.erc721.unlock(U256::ZERO),
+        Err(Erc721Error::NotUnlocker(NotUnlocker {
+            caller: ALICE,
+            token_id: U256::ZERO
+        }))
+    );
+    vm.set_sender(BOB);
+    c.erc721.unlock(U256::ZERO).unwrap();
+    assert!(!c.erc721.locked(U256::ZERO).unwrap());
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, RELAYER, U256::ZERO).unwrap();
+    assert_eq!(count_logs::<Locked>(&vm), 1);
+    assert_eq!(count_logs::<Unlocked>(&vm), 1);
+}
+
+#[test]
+fn soulbound_mint_locks_token() {
+    let vm = TestVM::default();
+    let mut erc721 = Erc721::<SoulboundParams>::from(&vm);
+    erc721.mint(ALICE).unwrap();
+    assert_eq!(count_logs::<Locked>(&vm), 1);
+    assert!(erc721.locked(U256::ZERO).unwrap());
+    assert!(erc721
+        .supports_interface(FixedBytes([0xb4, 0x5a, 0x3c, 0x0e]))
+        .unwrap());
+    vm.set_sender(ALICE);
+    assert_eq!(
+        erc721.transfer_from(ALICE, BOB, U256::ZERO),
+        Err(Erc721Error::TokenLocked(TokenLocked {
+            token_id: U256::ZERO
+        }))
+    );
+    // 灵魂绑定 token 仍可销毁
+    erc721.burn(ALICE, U256::ZERO).unwrap();
+    assert!(!erc721.exists(U256::ZERO).unwrap());
+}
+
+#[test]
+fn pausing_mint_leaves_transfers_open() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    // 仅暂停铸造
+    c.set_paused(1).unwrap();
+    assert_eq!(c.erc721.paused_flags().unwrap(), 1);
+    assert_eq!(
+        c.mint_to(ALICE),
+        Err(Erc721Error::OperationPaused(OperationPaused { flag: 1 }))
+    );
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+    // 再暂停转账
+    vm.set_sender(OWNER);
+    c.set_paused(1 | 2).unwrap();
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.transfer_from(BOB, ALICE, U256::ZERO),
+        Err(Erc721Error::OperationPaused(OperationPaused { flag: 2 }))
+    );
+}
+
+const MARKET: Address = address!("000000000000000000000000000000000000a4e7");
+
+// 为 ALICE 铸造 token 0，并授权 MARKET 和 BOB 管理其全部 token
+fn listed() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.erc721.set_approval_for_all(MARKET, true).unwrap();
+    c.erc721.set_approval_for_all(BOB, true).unwrap();
+    vm.set_sender(OWNER);
+    (vm, c)
+}
+
+#[test]
+fn unrestricted_transfers_allow_any_operator() {
+    let (vm, mut c) = listed();
+    vm.set_sender(BOB);
+    c.transfer_from(ALICE, RELAYER, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), RELAYER);
+}
+
+#[test]
+fn restricted_transfers_allow_holder_and_listed_marketplace() {
+    let (vm, mut c) = listed();
+    c.set_restrict_transfers(true).unwrap();
+    c.set_allowed_marketplace(MARKET, true).unwrap();
+    vm.set_sender(BOB);
+    assert_eq!(
+        c.transfer_from(ALICE, RELAYER, U256::ZERO),
+        Err(Erc721Error::MarketplaceNotAllowed(MarketplaceNotAllowed {
+            caller: BOB
+        }))
+    );
+    vm.set_sender(MARKET);
+    c.transfer_from(ALICE, RELAYER, U256::ZERO).unwrap();
+    vm.set_sender(RELAYER);
+    c.transfer_from(RELAYER, ALICE, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+}
+
+// 元数据随持有者变化的集合
+struct DynamicParams;
+impl Erc721Params for DynamicParams {
+    const NAME: &'static str = "DYN";
+    const SYMBOL: &'static str = "DYN";
+    const DYNAMIC_METADATA: bool = true;
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+}
+
+// 按转账次数更新 Transfers 属性的集合
+struct MutatingParams;
+impl Erc721Params for MutatingParams {
+    const NAME: &'static str = "MUT";
+    const SYMBOL: &'static str = "MUT";
+    fn token_uri(_token_id: U256) -> String {
+        String::new()
+    }
+    fn on_transfer_mutate(_token_id: U256, transfer_count: U256) -> Vec<(String, String)> {
+        vec![("Transfers".into(), transfer_count.to_string())]
+    }
+}
+
+#[test]
+fn dynamic_metadata_refreshes_on_transfer() {
+    let vm = TestVM::default();
+    let mut erc721 = Erc721::<DynamicParams>::from(&vm);
+    erc721.mint(ALICE).unwrap();
+    assert_eq!(count_logs::<MetadataUpdate>(&vm), 0);
+    vm.set_sender(ALICE);
+    erc721.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    let logs = vm.get_emitted_logs();
+    let (topics, data) = logs.last().unwrap();
+    assert_eq!(topics[0], MetadataUpdate::SIGNATURE_HASH);
+    assert_eq!(U256::abi_decode(data, true).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn static_metadata_skips_refresh() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(count_logs::<MetadataUpdate>(&vm), 0);
+}
+
+#[test]
+fn force_transfer_requires_recovery_mode() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    assert_eq!(
+        c.force_transfer(ALICE, BOB, U256::ZERO),
+        Err(Erc721Error::RecoveryModeDisabled(RecoveryModeDisabled {}))
+    );
+    c.set_recovery_mode(true).unwrap();
+    c.force_transfer(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+    assert_eq!(count_logs::<ForcedTransfer>(&vm), 1);
+    assert_eq!(count_logs::<Transfer>(&vm), 2);
+    // 关闭后再次回滚
+    c.set_recovery_mode(false).unwrap();
+    assert!(c.force_transfer(BOB, ALICE, U256::ZERO).is_err());
+}
+
+#[test]
+fn transfers_to_the_contract_itself_are_rejected() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    let this = vm.contract_address();
+    vm.set_sender(ALICE);
+    let rejected = Err(Erc721Error::TransferToContractSelf(
+        TransferToContractSelf {
+            token_id: U256::ZERO,
+        },
+    ));
+    assert_eq!(c.transfer_from(ALICE, this, U256::ZERO), rejected);
+    assert_eq!(c.safe_transfer_from(ALICE, this, U256::ZERO), rejected);
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+}
+
+#[test]
+fn self_hold_can_be_enabled_for_escrow() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.set_allow_self_hold(true).unwrap();
+    assert!(c.allow_self_hold().unwrap());
+    let this = vm.contract_address();
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, this, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), this);
+}
+
+#[test]
+fn kyc_gates_mints_and_transfers() {
+    let (vm, mut c) = deploy();
+    c.set_kyc_required(true).unwrap();
+    c.set_kyc(ALICE, true).unwrap();
+    c.mint_to(ALICE).unwrap();
+    assert!(c.erc721.is_kyc_verified(ALICE).unwrap());
+
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.transfer_from(ALICE, BOB, U256::ZERO),
+        Err(Erc721Error::NotKycVerified(NotKycVerified { account: BOB }))
+    );
+    assert_eq!(
+        c.set_kyc(BOB, true),
+        Err(Erc721Error::NotContractOwner(NotContractOwner {
+            caller: ALICE
+        }))
+    );
+    // 验证者可代替所有者标记地址
+    vm.set_sender(OWNER);
+    c.set_kyc_verifier(RELAYER, true).unwrap();
+    vm.set_sender(RELAYER);
+    c.set_kyc(BOB, true).unwrap();
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+    // 铸造给未验证的地址
+    vm.set_sender(OWNER);
+    assert_eq!(
+        c.mint_to(RELAYER),
+        Err(Erc721Error::NotKycVerified(NotKycVerified {
+            account: RELAYER
+        }))
+    );
+}
+
+#[test]
+fn kyc_is_ignored_when_not_required() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert!(!c.erc721.kyc_required().unwrap());
+}
+
+#[test]
+fn memo_transfer_emits_transfer_and_memo() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.transfer_from_with_memo(ALICE, BOB, U256::ZERO, "invoice #7".into())
+        .unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+    assert_eq!(count_logs::<Transfer>(&vm), 2);
+    let logs = vm.get_emitted_logs();
+    let (topics, data) = logs.last().unwrap();
+    let memo = TransferMemo::decode_raw_log(topics.iter().copied(), data, true).unwrap();
+    assert_eq!(
+        (memo.from, memo.to, memo.token_id, memo.memo.as_str()),
+        (ALICE, BOB, U256::ZERO, "invoice #7")
+    );
+}
+
+#[test]
+fn overlong_memo_is_rejected() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.transfer_from_with_memo(ALICE, BOB, U256::ZERO, "m".repeat(MAX_MEMO_LEN + 1)),
+        Err(Erc721Error::MemoTooLong(MemoTooLong {
+            len: U256::from(MAX_MEMO_LEN + 1),
+            max: U256::from(MAX_MEMO_LEN),
+        }))
+    );
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+}
+
+#[test]
+fn min_hold_time_applies_after_each_acquisition() {
+    let vm = TestVM::default();
+    vm.set_block_timestamp(1_000);
+    let mut erc721 = Erc721::<HoldParams>::from(&vm);
+    erc721.mint(ALICE).unwrap();
+    assert_eq!(
+        erc721.transferable_at(U256::ZERO).unwrap(),
+        U256::from(1_100)
+    );
+    vm.set_block_timestamp(1_099);
+    vm.set_sender(ALICE);
+    assert_eq!(
+        erc721.transfer_from(ALICE, BOB, U256::ZERO),
+        Err(Erc721Error::MinHoldNotMet(MinHoldNotMet {
+            token_id: U256::ZERO,
+            transferable_at: U256::from(1_100),
+        }))
+    );
+    vm.set_block_timestamp(1_100);
+    erc721.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    // 转入后重新计时
+    assert_eq!(
+        erc721.transferable_at(U256::ZERO).unwrap(),
+        U256::from(1_200)
+    );
+    vm.set_sender(BOB);
+    assert!(erc721.transfer_from(BOB, ALICE, U256::ZERO).is_err());
+    vm.set_block_timestamp(1_200);
+    erc721.transfer_from(BOB, ALICE, U256::ZERO).unwrap();
+    assert_eq!(erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+}
+
+#[test]
+fn transfer_hook_bumps_stored_attribute() {
+    let vm = TestVM::default();
+    let mut erc721 = Erc721::<MutatingParams>::from(&vm);
+    erc721.mint(ALICE).unwrap();
+    // 铸造不调用钩子
+    assert_eq!(
+        erc721.get_attributes(U256::ZERO).unwrap(),
+        (Vec::new(), Vec::new())
+    );
+    vm.set_sender(ALICE);
+    erc721.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(
+        erc721.get_attributes(U256::ZERO).unwrap(),
+        (vec!["Transfers".to_string()], vec!["1".to_string()])
+    );
+    let logs = vm.get_emitted_logs();
+    let (topics, data) = logs.last().unwrap();
+    assert_eq!(topics[0], MetadataUpdate::SIGNATURE_HASH);
+    assert_eq!(U256::abi_decode(data, true).unwrap(), U256::ZERO);
+    vm.set_sender(BOB);
+    erc721.transfer_from(BOB, ALICE, U256::ZERO).unwrap();
+    assert_eq!(
+        erc721.get_attributes(U256::ZERO).unwrap(),
+        (vec!["Transfers".to_string()], vec!["2".to_string()])
+    );
+    assert_eq!(count_logs::<MetadataUpdate>(&vm), 2);
+}
+
+// 模拟的二级销售回调合约
+const HOOK: Address = address!("000000000000000000000000000000000000b00c");
+
+// 设置回调合约，并模拟其对 ALICE 将 token 0 转给 BOB 的回调返回 result
+fn hooked(strict: bool, result: Result<Vec<u8>, Vec<u8>>) -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.set_sale_hook(HOOK, strict).unwrap();
+    vm.set_code(HOOK, vec![0x00]);
+    let mut call = keccak("onSecondarySale(address,address,uint256)")[..4].to_vec();
+    call.extend_from_slice(&(ALICE, BOB, U256::ZERO).abi_encode_params());
+    vm.mock_call(HOOK, call, result);
+    vm.set_sender(ALICE);
+    (vm, c)
+}
+
+#[test]
+fn lenient_hook_failure_keeps_transfer() {
+    let (_vm, mut c) = hooked(false, Err(Vec::new()));
+    assert_eq!(c.sale_hook().unwrap(), (HOOK, false));
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+}
+
+#[test]
+fn strict_hook_accepts_successful_callback() {
+    let (_vm, mut c) = hooked(true, Ok(Vec::new()));
+    c.safe_transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), BOB);
+}
+
+#[test]
+fn strict_hook_failure_reverts_transfer() {
+    let (_vm, mut c) = hooked(true, Err(Vec::new()));
+    assert_eq!(
+        c.transfer_from(ALICE, BOB, U256::ZERO),
+        Err(Erc721Error::HookFailed(HookFailed {
+            hook: HOOK,
+            token_id: U256::ZERO,
+        }))
+    );
+}
+
+#[test]
+fn last_transfer_reflects_latest_movement() {
+    let (vm, mut c) = deploy();
+    vm.set_block_timestamp(1_000);
+    c.mint_to(ALICE).unwrap();
+    assert_eq!(
+        c.erc721.last_transfer_of(U256::ZERO).unwrap(),
+        (Address::ZERO, ALICE, 1_000)
+    );
+    vm.set_block_timestamp(1_500);
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    vm.set_block_timestamp(2_000);
+    vm.set_sender(BOB);
+    c.transfer_from(BOB, RELAYER, U256::ZERO).unwrap();
+    assert_eq!(
+        c.erc721.last_transfer_of(U256::ZERO).unwrap(),
+        (BOB, RELAYER, 2_000)
+    );
+    // 销毁同样记录
+    vm.set_sender(RELAYER);
+    c.burn(U256::ZERO).unwrap();
+    assert_eq!(
+        c.erc721.last_transfer_of(U256::ZERO).unwrap(),
+        (RELAYER, Address::ZERO, 2_000)
+    );
+}
+
+// 白名单中的游戏合约与未列入白名单的合约
+const GAME: Address = address!("0000000000000000000000000000000000006a3e");
+const STRANGER: Address = address!("0000000000000000000000000000000000005ca1");
+
+#[test]
+fn restricted_destinations_allow_eoas_and_whitelisted_contracts() {
+    let (vm, mut c) = deploy();
+    for _ in 0..3 {
+        c.mint_to(ALICE).unwrap();
+    }
+    vm.set_code(GAME, vec![0x00]);
+    vm.set_code(STRANGER, vec![0x00]);
+    c.set_restrict_contract_dest(true).unwrap();
+    c.set_dest_whitelisted(GAME, true).unwrap();
+    assert!(c.is_dest_whitelisted(GAME).unwrap());
+    vm.set_sender(ALICE);
+    // 外部账户不受限制
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    c.transfer_from(ALICE, GAME, U256::from(1)).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::from(1)).unwrap(), GAME);
+    assert_eq!(
+        c.transfer_from(ALICE, STRANGER, U256::from(2)),
+        Err(Erc721Error::DestinationNotAllowed(DestinationNotAllowed {
+            to: STRANGER
+        }))
+    );
+    assert_eq!(
+        c.safe_transfer_from(ALICE, STRANGER, U256::from(2)),
+        Err(Erc721Error::DestinationNotAllowed(DestinationNotAllowed {
+            to: STRANGER
+        }))
+    );
+    assert_eq!(c.erc721.owner_of(U256::from(2)).unwrap(), ALICE);
+}
+
+#[test]
+fn unrestricted_destinations_accept_any_contract() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_code(STRANGER, vec![0x00]);
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, STRANGER, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), STRANGER);
+}
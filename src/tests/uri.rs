@@ -0,0 +1,425 @@
+use super::*;
+use crate::erc721::{
+    BatchMetadataUpdate, Erc721, Erc721Error, InvalidTokenId, InvalidUriMode, LengthMismatch,
+    MetadataUpdate, TokenFrozen, TokenMetadataFrozen, Transfer, UriTooLong,
+};
+
+// 0x12 0x20 前缀加 32 字节摘要的 CIDv0
+fn cid() -> Vec<u8> {
+    let mut cid = vec![0x12, 0x20];
+    cid.extend_from_slice(&[7u8; 32]);
+    cid
+}
+
+// 部署后为 ALICE 铸造 token 0，设置基础 URI 和 CID
+fn with_cid() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.erc721.set_base_uri("ipfs://".into()).unwrap();
+    c.set_token_cid(U256::ZERO, cid().into()).unwrap();
+    (vm, c)
+}
+
+#[test]
+fn cid_uri_uses_suffix() {
+    let (_vm, mut c) = with_cid();
+    let encoded = crate::base58::encode(&cid());
+    assert_eq!(
+        c.erc721.token_uri(U256::ZERO).unwrap(),
+        format!("ipfs://{}.json", encoded)
+    );
+    c.set_uri_suffix("".into()).unwrap();
+    assert_eq!(
+        c.erc721.token_uri(U256::ZERO).unwrap(),
+        format!("ipfs://{}", encoded)
+    );
+}
+
+#[test]
+fn cid_uri_carries_metadata_version() {
+    let (_vm, mut c) = with_cid();
+    c.bump_metadata_version().unwrap();
+    assert_eq!(
+        c.erc721.token_uri(U256::ZERO).unwrap(),
+        format!("ipfs://{}.json?v=1", crate::base58::encode(&cid()))
+    );
+}
+
+// 揭示前返回占位 URI 的集合
+struct HiddenParams;
+impl Erc721Params for HiddenParams {
+    const NAME: &'static str = "HID";
+    const SYMBOL: &'static str = "HID";
+    const PLACEHOLDER_URI: Option<&'static str> = Some("ipfs://hidden");
+    fn token_uri(token_id: U256) -> String {
+        format!("ipfs://meta/{}", token_id)
+    }
+}
+
+#[test]
+fn reveal_shifts_metadata_ids_by_offset() {
+    let vm = TestVM::default();
+    let mut token = Erc721::<HiddenParams>::from(&vm);
+    for _ in 0..4 {
+        token.mint(ALICE).unwrap();
+    }
+    assert_eq!(token.token_uri(U256::ZERO).unwrap(), "ipfs://hidden");
+    // 种子 6 对 4 个 token 取模得到偏移 2
+    token.reveal(U256::from(6)).unwrap();
+    let uris: Vec<String> = (0..4u64)
+        .map(|id| token.token_uri(U256::from(id)).unwrap())
+        .collect();
+    assert_eq!(
+        uris,
+        [
+            "ipfs://meta/2",
+            "ipfs://meta/3",
+            "ipfs://meta/0",
+            "ipfs://meta/1"
+        ]
+    );
+    // 揭示后新铸造的 id 不参与偏移
+    token.mint(ALICE).unwrap();
+    assert_eq!(token.token_uri(U256::from(4)).unwrap(), "ipfs://meta/4");
+}
+
+#[test]
+fn mint_with_uri_stores_override() {
+    let (vm, mut c) = deploy();
+    c.mint_to_with_uri(ALICE, "ipfs://bespoke".into()).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(c.erc721.token_uri(U256::ZERO).unwrap(), "ipfs://bespoke");
+    assert_eq!(count_logs::<Transfer>(&vm), 1);
+    assert_eq!(count_logs::<MetadataUpdate>(&vm), 1);
+}
+
+#[test]
+fn token_uri_requires_existing_token() {
+    let (_vm, mut c) = deploy();
+    assert_eq!(
+        c.erc721.set_token_uri(U256::from(7), "ipfs://x".into()),
+        Err(Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::from(7)
+        }))
+    );
+}
+
+#[test]
+fn uri_mode_selects_the_strategy() {
+    let (_vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    c.erc721.set_base_uri("ipfs://base/".into()).unwrap();
+    c.erc721
+        .set_token_uri(U256::ZERO, "ar://token".into())
+        .unwrap();
+    let uris = |c: &StylusNFT| {
+        (
+            c.erc721.token_uri(U256::ZERO).unwrap(),
+            c.erc721.token_uri(U256::from(1)).unwrap(),
+        )
+    };
+    // 自动模式：专属 URI 优先，否则基础 URI 加 id
+    assert_eq!(uris(&c), ("ar://token".into(), "ipfs://base/1.json".into()));
+    c.set_uri_mode(1).unwrap();
+    assert_eq!(
+        uris(&c),
+        ("ipfs://base/0.json".into(), "ipfs://base/1.json".into())
+    );
+    c.set_uri_mode(2).unwrap();
+    assert_eq!(uris(&c), ("ar://token".into(), String::new()));
+    c.set_uri_mode(3).unwrap();
+    let (first, _) = uris(&c);
+    assert!(first.starts_with("data:application/json;base64,"));
+    assert_eq!(
+        c.set_uri_mode(4),
+        Err(Erc721Error::InvalidUriMode(InvalidUriMode { mode: 4 }))
+    );
+}
+
+#[test]
+fn reveal_segments_pick_their_base() {
+    let vm = TestVM::default();
+    let mut token = Erc721::<HiddenParams>::from(&vm);
+    for _ in 0..6 {
+        token.mint(ALICE).unwrap();
+    }
+    token
+        .reveal_range(U256::ZERO, U256::from(1), "ipfs://a/".into())
+        .unwrap();
+    token
+        .reveal_range(U256::from(4), U256::from(5), "ipfs://b/".into())
+        .unwrap();
+    let uri = |id: u64| token.token_uri(U256::from(id)).unwrap();
+    assert_eq!(uri(1), "ipfs://a/1.json");
+    assert_eq!(uri(4), "ipfs://b/4.json");
+    assert_eq!(uri(2), "ipfs://hidden");
+    assert_eq!(uri(3), "ipfs://hidden");
+}
+
+#[test]
+fn token_uri_batch_sets_each_override() {
+    let (vm, mut c) = deploy();
+    for _ in 0..3 {
+        c.mint_to(ALICE).unwrap();
+    }
+    c.set_token_uri_batch(
+        vec![U256::from(2), U256::ZERO],
+        vec!["ipfs://two".into(), "ipfs://zero".into()],
+    )
+    .unwrap();
+    assert_eq!(c.erc721.token_uri(U256::ZERO).unwrap(), "ipfs://zero");
+    assert_eq!(c.erc721.token_uri(U256::from(2)).unwrap(), "ipfs://two");
+    // 批量事件覆盖最小到最大 id
+    let logs = vm.get_emitted_logs();
+    let (topics, data) = logs.last().unwrap();
+    assert_eq!(topics[0], BatchMetadataUpdate::SIGNATURE_HASH);
+    assert_eq!(
+        <(U256, U256)>::abi_decode_params(data, true).unwrap(),
+        (U256::ZERO, U256::from(2))
+    );
+}
+
+#[test]
+fn token_uri_batch_rejects_length_mismatch() {
+    let (_vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    assert_eq!(
+        c.set_token_uri_batch(vec![U256::ZERO], vec![]),
+        Err(Erc721Error::LengthMismatch(LengthMismatch {
+            left: U256::from(1),
+            right: U256::ZERO,
+        }))
+    );
+}
+
+#[test]
+fn version_bump_changes_uri_suffix() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.erc721.set_base_uri("ipfs://base/".into()).unwrap();
+    assert_eq!(
+        c.erc721.token_uri(U256::ZERO).unwrap(),
+        "ipfs://base/0.json"
+    );
+    assert_eq!(c.bump_metadata_version().unwrap(), U256::from(1));
+    assert_eq!(
+        c.erc721.token_uri(U256::ZERO).unwrap(),
+        "ipfs://base/0.json?v=1"
+    );
+    c.bump_metadata_version().unwrap();
+    assert_eq!(
+        c.erc721.token_uri(U256::ZERO).unwrap(),
+        "ipfs://base/0.json?v=2"
+    );
+    assert_eq!(count_logs::<BatchMetadataUpdate>(&vm), 2);
+}
+
+#[test]
+fn uri_suffix_is_configurable() {
+    let (_vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.erc721.set_base_uri("ar://base/".into()).unwrap();
+    assert_eq!(c.erc721.uri_suffix().unwrap(), ".json");
+    assert_eq!(c.erc721.token_uri(U256::ZERO).unwrap(), "ar://base/0.json");
+    c.set_uri_suffix("".into()).unwrap();
+    assert_eq!(c.erc721.uri_suffix().unwrap(), "");
+    assert_eq!(c.erc721.token_uri(U256::ZERO).unwrap(), "ar://base/0");
+    c.set_uri_suffix(".meta".into()).unwrap();
+    assert_eq!(c.erc721.token_uri(U256::ZERO).unwrap(), "ar://base/0.meta");
+}
+
+#[test]
+fn reveal_state_before_and_after() {
+    let vm = TestVM::default();
+    let mut token = Erc721::<HiddenParams>::from(&vm);
+    for _ in 0..6 {
+        token.mint(ALICE).unwrap();
+    }
+    let range = |token: &Erc721<HiddenParams>, start: u64, end: u64| {
+        token
+            .is_range_revealed(U256::from(start), U256::from(end))
+            .unwrap()
+    };
+    assert!(!token.is_revealed().unwrap());
+    token
+        .reveal_range(U256::ZERO, U256::from(1), "ipfs://a/".into())
+        .unwrap();
+    token
+        .reveal_range(U256::from(2), U256::from(3), "ipfs://b/".into())
+        .unwrap();
+    // 相邻区间拼接后完整覆盖
+    assert!(range(&token, 0, 3));
+    assert!(!range(&token, 2, 4));
+    assert!(!token.is_revealed().unwrap());
+    token.reveal(U256::ZERO).unwrap();
+    assert!(token.is_revealed().unwrap());
+    assert!(range(&token, 0, 5));
+}
+
+// 解码标准 base64，仅用于检查链上元数据
+fn decode_base64(encoded: &str) -> Vec<u8> {
+    const TABLE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let digits: Vec<u32> = encoded
+        .bytes()
+        .filter(|&b| b != b'=')
+        .map(|b| TABLE.iter().position(|&t| t == b).unwrap() as u32)
+        .collect();
+    let mut out = Vec::new();
+    for chunk in digits.chunks(4) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, d)| n | d << (18 - 6 * i));
+        out.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+    }
+    out
+}
+
+// 去掉 data URI 前缀并解码内容
+fn decode_data_uri(uri: &str, mime: &str) -> String {
+    let prefix = format!("data:{};base64,", mime);
+    let payload = uri.strip_prefix(prefix.as_str()).unwrap();
+    String::from_utf8(decode_base64(payload)).unwrap()
+}
+
+#[test]
+fn onchain_uri_embeds_rendered_svg() {
+    let (_vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    c.set_uri_mode(3).unwrap();
+    let json = decode_data_uri(
+        &c.erc721.token_uri(U256::from(1)).unwrap(),
+        "application/json",
+    );
+    assert!(json.starts_with("{\"name\":\"DOG #1\""));
+    // image 字段为 SVG data URI
+    let image = json
+        .split("\"image\":\"")
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap();
+    let svg = decode_data_uri(image, "image/svg+xml");
+    assert_eq!(
+        svg,
+        <StylusNFTParams as Erc721Params>::render_svg(U256::from(1))
+    );
+    assert!(svg.starts_with("<svg") && svg.contains("DOG #1"));
+}
+
+#[test]
+fn freezing_one_token_leaves_others_editable() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    c.freeze_token(U256::ZERO).unwrap();
+    assert!(c.erc721.is_token_frozen(U256::ZERO).unwrap());
+    assert!(!c.erc721.is_token_frozen(U256::from(1)).unwrap());
+    assert_eq!(
+        c.erc721.set_token_uri(U256::ZERO, "ar://late".into()),
+        Err(Erc721Error::TokenFrozen(TokenFrozen {
+            token_id: U256::ZERO
+        }))
+    );
+    c.erc721
+        .set_token_uri(U256::from(1), "ar://edited".into())
+        .unwrap();
+    assert_eq!(c.erc721.token_uri(U256::from(1)).unwrap(), "ar://edited");
+    // 重复冻结不再记录事件
+    c.freeze_token(U256::ZERO).unwrap();
+    assert_eq!(count_logs::<TokenMetadataFrozen>(&vm), 1);
+    // 不存在的 token 无法冻结
+    assert_eq!(
+        c.freeze_token(U256::from(9)),
+        Err(Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::from(9)
+        }))
+    );
+}
+
+#[test]
+fn attributes_appear_in_onchain_json() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.set_attribute(U256::ZERO, "Background".into(), "Blue".into())
+        .unwrap();
+    c.set_attribute(U256::ZERO, "Eyes".into(), "Laser".into())
+        .unwrap();
+    // 同名属性覆盖原值
+    c.set_attribute(U256::ZERO, "Background".into(), "Red".into())
+        .unwrap();
+    assert_eq!(
+        c.erc721.get_attributes(U256::ZERO).unwrap(),
+        (
+            vec!["Background".to_string(), "Eyes".to_string()],
+            vec!["Red".to_string(), "Laser".to_string()]
+        )
+    );
+    assert_eq!(count_logs::<MetadataUpdate>(&vm), 3);
+    c.set_uri_mode(3).unwrap();
+    let json = decode_data_uri(&c.erc721.token_uri(U256::ZERO).unwrap(), "application/json");
+    assert!(json.ends_with(
+        "\"attributes\":[{\"trait_type\":\"Background\",\"value\":\"Red\"},\
+         {\"trait_type\":\"Eyes\",\"value\":\"Laser\"}]}"
+    ));
+    vm.set_sender(ALICE);
+    assert!(c
+        .set_attribute(U256::ZERO, "Eyes".into(), "None".into())
+        .is_err());
+}
+
+#[test]
+fn uris_are_bounded_by_max_len() {
+    let (_vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    let max = StylusNFTParams::MAX_URI_LEN.unwrap();
+    let too_long = Err(Erc721Error::UriTooLong(UriTooLong {
+        len: U256::from(max + 1),
+        max: U256::from(max),
+    }));
+    c.erc721.set_base_uri("a".repeat(max)).unwrap();
+    c.erc721.set_token_uri(U256::ZERO, "b".repeat(max)).unwrap();
+    assert_eq!(c.erc721.set_base_uri("a".repeat(max + 1)), too_long);
+    assert_eq!(
+        c.erc721.set_token_uri(U256::ZERO, "b".repeat(max + 1)),
+        too_long
+    );
+    assert_eq!(c.erc721.token_uri(U256::ZERO).unwrap(), "b".repeat(max));
+}
+
+#[test]
+fn burned_tokens_resolve_to_tombstone() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    c.set_tombstone_uri("ipfs://tombstone".into()).unwrap();
+    let live = c.erc721.token_uri(U256::from(1)).unwrap();
+    vm.set_sender(ALICE);
+    c.burn(U256::ZERO).unwrap();
+    assert_eq!(c.erc721.token_uri(U256::ZERO).unwrap(), "ipfs://tombstone");
+    // 存活的 token 不受墓碑影响
+    assert_eq!(c.erc721.token_uri(U256::from(1)).unwrap(), live);
+    assert_ne!(live, "ipfs://tombstone");
+    // 从未铸造的 token 仍回滚
+    assert_eq!(
+        c.erc721.token_uri(U256::from(7)),
+        Err(Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::from(7)
+        }))
+    );
+}
+
+#[test]
+fn burned_tokens_revert_without_tombstone() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    c.burn(U256::ZERO).unwrap();
+    assert_eq!(
+        c.erc721.token_uri(U256::ZERO),
+        Err(Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::ZERO
+        }))
+    );
+}
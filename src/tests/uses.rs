@@ -0,0 +1,48 @@
+use super::*;
+use crate::erc721::{Consumed, Erc721Error, MissingRole, NoUsesLeft};
+use crate::MINTER_ROLE;
+
+// 为 ALICE 铸造 token 0 并设置 2 次可使用次数，以 ALICE 作为调用者
+fn ticket() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.set_uses(U256::ZERO, U256::from(2)).unwrap();
+    vm.set_sender(ALICE);
+    (vm, c)
+}
+
+#[test]
+fn consume_counts_down_to_zero() {
+    let (vm, mut c) = ticket();
+    assert_eq!(c.erc721.uses_remaining(U256::ZERO).unwrap(), U256::from(2));
+    c.erc721.consume(U256::ZERO).unwrap();
+    c.erc721.consume(U256::ZERO).unwrap();
+    assert_eq!(c.erc721.uses_remaining(U256::ZERO).unwrap(), U256::ZERO);
+    assert_eq!(count_logs::<Consumed>(&vm), 2);
+    assert_eq!(
+        c.erc721.consume(U256::ZERO).unwrap_err(),
+        Erc721Error::NoUsesLeft(NoUsesLeft {
+            token_id: U256::ZERO
+        })
+    );
+}
+
+#[test]
+fn holder_cannot_refill_uses() {
+    let (_vm, mut c) = ticket();
+    c.erc721.consume(U256::ZERO).unwrap();
+    assert_eq!(
+        c.set_uses(U256::ZERO, U256::from(100)).unwrap_err(),
+        Erc721Error::MissingRole(MissingRole {
+            account: ALICE,
+            role: MINTER_ROLE,
+        })
+    );
+    assert_eq!(c.erc721.uses_remaining(U256::ZERO).unwrap(), U256::from(1));
+}
+
+#[test]
+fn set_uses_requires_existing_token() {
+    let (_vm, mut c) = deploy();
+    assert!(c.set_uses(U256::from(5), U256::from(1)).is_err());
+}
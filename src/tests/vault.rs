@@ -0,0 +1,51 @@
+use super::*;
+use crate::erc721::{Erc721Error, InvalidTokenId, NotVault, VaultNotSet};
+
+// 模拟托管合约
+const VAULT: Address = address!("000000000000000000000000000000000000ba17");
+
+fn with_vault() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    vm.set_code(VAULT, vec![0x00]);
+    c.set_vault(VAULT).unwrap();
+    (vm, c)
+}
+
+#[test]
+fn mint_to_vault_requires_a_vault() {
+    let (_vm, mut c) = deploy();
+    assert_eq!(
+        c.mint_to_vault(ALICE).unwrap_err(),
+        Erc721Error::VaultNotSet(VaultNotSet {})
+    );
+}
+
+#[test]
+fn vault_holds_custody_and_releases_to_user() {
+    let (vm, mut c) = with_vault();
+    let token_id = c.mint_to_vault(ALICE).unwrap();
+    assert_eq!(c.erc721.owner_of(token_id).unwrap(), VAULT);
+    assert_eq!(c.beneficial_owner(token_id).unwrap(), ALICE);
+
+    vm.set_sender(VAULT);
+    c.release(token_id).unwrap();
+    assert_eq!(c.erc721.owner_of(token_id).unwrap(), ALICE);
+    assert_eq!(c.beneficial_owner(token_id).unwrap(), Address::ZERO);
+    // 已释放的 token 不能再次释放
+    assert_eq!(
+        c.release(token_id).unwrap_err(),
+        Erc721Error::InvalidTokenId(InvalidTokenId { token_id })
+    );
+}
+
+#[test]
+fn only_vault_can_release() {
+    let (vm, mut c) = with_vault();
+    let token_id = c.mint_to_vault(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.release(token_id).unwrap_err(),
+        Erc721Error::NotVault(NotVault { caller: ALICE })
+    );
+    assert_eq!(c.erc721.owner_of(token_id).unwrap(), VAULT);
+}
@@ -0,0 +1,244 @@
+use super::*;
+use crate::erc721::{BatchTooLarge, Erc721Error, InvalidTokenId, LengthMismatch, NotContractOwner};
+
+// 为 ALICE 铸造 token 0、1，为 BOB 铸造 token 2，ALICE 随后销毁 token 1
+fn minted() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(BOB).unwrap();
+    vm.set_sender(ALICE);
+    c.burn(U256::from(1)).unwrap();
+    vm.set_sender(OWNER);
+    (vm, c)
+}
+
+#[test]
+fn owner_of_batch_zeroes_missing_ids() {
+    let (_vm, c) = minted();
+    let ids = [0u64, 1, 2, 99].map(U256::from).to_vec();
+    assert_eq!(
+        c.erc721.owner_of_batch(ids).unwrap(),
+        vec![ALICE, Address::ZERO, BOB, Address::ZERO]
+    );
+}
+
+#[test]
+fn token_uri_batch_blanks_missing_ids() {
+    let (_vm, c) = minted();
+    let ids = [0u64, 1, 2].map(U256::from).to_vec();
+    assert_eq!(
+        c.erc721.token_uri_batch(ids).unwrap(),
+        vec![
+            c.erc721.token_uri(U256::ZERO).unwrap(),
+            String::new(),
+            c.erc721.token_uri(U256::from(2)).unwrap(),
+        ]
+    );
+    assert!(!c.erc721.token_uri(U256::ZERO).unwrap().is_empty());
+}
+
+fn too_large(len: usize) -> Erc721Error {
+    Erc721Error::BatchTooLarge(BatchTooLarge {
+        len: U256::from(len),
+        max: U256::from(StylusNFTParams::MAX_BATCH),
+    })
+}
+
+#[test]
+fn batches_at_limit_pass_and_over_limit_revert() {
+    let (vm, mut c) = deploy();
+    let max = StylusNFTParams::MAX_BATCH;
+    for _ in 0..max {
+        c.mint_to(ALICE).unwrap();
+    }
+    let ids: Vec<U256> = (0..max).map(U256::from).collect();
+    let over: Vec<U256> = (0..=max).map(U256::from).collect();
+    assert_eq!(c.erc721.owner_of_batch(ids.clone()).unwrap().len(), max);
+    assert_eq!(
+        c.erc721.owner_of_batch(over.clone()).unwrap_err(),
+        too_large(max + 1)
+    );
+    vm.set_sender(ALICE);
+    assert_eq!(c.burn_batch(over).unwrap_err(), too_large(max + 1));
+    c.burn_batch(ids).unwrap();
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn owners_in_range_spans_minted_burned_and_unminted() {
+    let (_vm, c) = minted();
+    assert_eq!(
+        c.erc721.owners_in_range(U256::ZERO, U256::from(5)).unwrap(),
+        vec![ALICE, Address::ZERO, BOB, Address::ZERO, Address::ZERO]
+    );
+    assert_eq!(
+        c.erc721
+            .owners_in_range(U256::from(2), U256::from(3))
+            .unwrap(),
+        vec![BOB]
+    );
+    // 空区间或反向区间返回空列表
+    assert!(c
+        .erc721
+        .owners_in_range(U256::from(3), U256::from(1))
+        .unwrap()
+        .is_empty());
+    let max = StylusNFTParams::MAX_BATCH;
+    assert_eq!(
+        c.erc721
+            .owners_in_range(U256::ZERO, U256::from(max))
+            .unwrap()
+            .len(),
+        max
+    );
+    assert_eq!(
+        c.erc721
+            .owners_in_range(U256::ZERO, U256::from(max + 1))
+            .unwrap_err(),
+        too_large(max + 1)
+    );
+}
+
+#[test]
+fn owner_of_or_zero_never_reverts() {
+    let (_vm, c) = minted();
+    assert_eq!(c.erc721.owner_of_or_zero(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(
+        c.erc721.owner_of_or_zero(U256::from(1)).unwrap(),
+        Address::ZERO
+    );
+    assert_eq!(
+        c.erc721.owner_of_or_zero(U256::from(99)).unwrap(),
+        Address::ZERO
+    );
+}
+
+#[test]
+fn balance_of_batch_keeps_order() {
+    let (_vm, c) = minted();
+    assert_eq!(
+        c.erc721
+            .balance_of_batch(vec![BOB, RELAYER, ALICE])
+            .unwrap(),
+        vec![U256::from(1), U256::ZERO, U256::from(1)]
+    );
+}
+
+#[test]
+fn invariants_hold_after_mints_transfers_and_burns() {
+    let (vm, mut c) = minted();
+    vm.set_sender(ALICE);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    vm.set_sender(OWNER);
+    assert!(c.verify_invariants().unwrap());
+    // 人为破坏余额记账后检测到不一致
+    c.erc721.balances.insert(BOB, U256::from(3));
+    assert!(!c.verify_invariants().unwrap());
+}
+
+#[test]
+fn invariants_check_is_owner_only() {
+    let (vm, c) = minted();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.verify_invariants().unwrap_err(),
+        Erc721Error::NotContractOwner(NotContractOwner { caller: ALICE })
+    );
+}
+
+#[test]
+fn holder_count_tracks_zero_crossings() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    c.mint_to(ALICE).unwrap();
+    assert_eq!(c.erc721.holder_count().unwrap(), U256::from(1));
+    vm.set_sender(ALICE);
+    // 转给新持有者
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    assert_eq!(c.erc721.holder_count().unwrap(), U256::from(2));
+    // 转给自己不变
+    c.transfer_from(ALICE, ALICE, U256::from(1)).unwrap();
+    assert_eq!(c.erc721.holder_count().unwrap(), U256::from(2));
+    // 转给已有持有者，ALICE 余额归零
+    c.transfer_from(ALICE, BOB, U256::from(1)).unwrap();
+    assert_eq!(c.erc721.holder_count().unwrap(), U256::from(1));
+    vm.set_sender(BOB);
+    c.burn(U256::ZERO).unwrap();
+    assert_eq!(c.erc721.holder_count().unwrap(), U256::from(1));
+    c.burn(U256::from(1)).unwrap();
+    assert_eq!(c.erc721.holder_count().unwrap(), U256::ZERO);
+}
+
+#[test]
+fn is_owner_of_checks_the_holder() {
+    let (_vm, c) = minted();
+    assert!(c.erc721.is_owner_of(ALICE, U256::ZERO).unwrap());
+    assert!(!c.erc721.is_owner_of(BOB, U256::ZERO).unwrap());
+    // 已销毁的 token 回滚
+    assert_eq!(
+        c.erc721.is_owner_of(ALICE, U256::from(1)).unwrap_err(),
+        Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::from(1)
+        })
+    );
+}
+
+#[test]
+fn are_owners_of_checks_each_pair() {
+    let (_vm, c) = minted();
+    let ids = [0u64, 2, 2].map(U256::from).to_vec();
+    assert_eq!(
+        c.erc721
+            .are_owners_of(vec![ALICE, BOB, ALICE], ids)
+            .unwrap(),
+        vec![true, true, false]
+    );
+    // 任一 token 不存在时整体回滚
+    assert_eq!(
+        c.erc721
+            .are_owners_of(vec![ALICE, ALICE], vec![U256::ZERO, U256::from(99)])
+            .unwrap_err(),
+        Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::from(99)
+        })
+    );
+    assert_eq!(
+        c.erc721
+            .are_owners_of(vec![ALICE], vec![U256::ZERO, U256::from(2)])
+            .unwrap_err(),
+        Erc721Error::LengthMismatch(LengthMismatch {
+            left: U256::from(1),
+            right: U256::from(2)
+        })
+    );
+}
+
+#[test]
+fn token_state_matches_individual_getters() {
+    let (vm, mut c) = minted();
+    vm.set_sender(BOB);
+    c.erc721.approve(ALICE, U256::from(2)).unwrap();
+    c.erc721.lock(U256::from(2), BOB).unwrap();
+    for token_id in [U256::ZERO, U256::from(2)] {
+        assert_eq!(
+            c.erc721.token_state(token_id).unwrap(),
+            (
+                c.erc721.owner_of(token_id).unwrap(),
+                c.erc721.get_approved(token_id).unwrap(),
+                c.erc721.token_uri(token_id).unwrap(),
+                c.erc721.locked(token_id).unwrap(),
+            )
+        );
+    }
+    assert_eq!(
+        c.erc721.token_state(U256::from(2)).unwrap(),
+        (BOB, ALICE, c.erc721.token_uri(U256::from(2)).unwrap(), true)
+    );
+    assert_eq!(
+        c.erc721.token_state(U256::from(1)).unwrap_err(),
+        Erc721Error::InvalidTokenId(InvalidTokenId {
+            token_id: U256::from(1)
+        })
+    );
+}
@@ -0,0 +1,72 @@
+use super::*;
+use crate::erc721::{Erc721Error, FutureLookup};
+
+// 区块 1 为 ALICE 铸造 3 个 token，区块 2 ALICE 委托给自己，区块 5 转给 BOB 一个，
+// 区块 6 BOB 委托给自己，区块 10 再转给 BOB 一个，最后停在区块 11
+fn history() -> (TestVM, StylusNFT) {
+    let (vm, mut c) = deploy();
+    vm.set_block_number(1);
+    for _ in 0..3 {
+        c.mint_to(ALICE).unwrap();
+    }
+    vm.set_sender(ALICE);
+    vm.set_block_number(2);
+    c.erc721.delegate(ALICE).unwrap();
+    vm.set_block_number(5);
+    c.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+    vm.set_sender(BOB);
+    vm.set_block_number(6);
+    c.erc721.delegate(BOB).unwrap();
+    vm.set_sender(ALICE);
+    vm.set_block_number(10);
+    c.transfer_from(ALICE, BOB, U256::from(1)).unwrap();
+    vm.set_block_number(11);
+    (vm, c)
+}
+
+fn past_votes(c: &StylusNFT, account: Address, block: u64) -> U256 {
+    c.erc721.get_past_votes(account, U256::from(block)).unwrap()
+}
+
+#[test]
+fn delegation_assigns_current_votes() {
+    let (_vm, c) = history();
+    assert_eq!(c.erc721.delegates(ALICE).unwrap(), ALICE);
+    assert_eq!(c.erc721.delegates(BOB).unwrap(), BOB);
+    assert_eq!(c.erc721.get_votes(ALICE).unwrap(), U256::from(1));
+    assert_eq!(c.erc721.get_votes(BOB).unwrap(), U256::from(2));
+    assert_eq!(c.erc721.get_votes(RELAYER).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn transfers_move_voting_power() {
+    let (_vm, c) = history();
+    assert_eq!(past_votes(&c, ALICE, 1), U256::ZERO);
+    assert_eq!(past_votes(&c, ALICE, 2), U256::from(3));
+    assert_eq!(past_votes(&c, ALICE, 5), U256::from(2));
+    assert_eq!(past_votes(&c, ALICE, 10), U256::from(1));
+    // BOB 委托前收到的 token 不计票
+    assert_eq!(past_votes(&c, BOB, 5), U256::ZERO);
+    assert_eq!(past_votes(&c, BOB, 6), U256::from(1));
+    assert_eq!(past_votes(&c, BOB, 10), U256::from(2));
+}
+
+#[test]
+fn past_votes_between_checkpoints_use_the_earlier_one() {
+    let (_vm, c) = history();
+    assert_eq!(past_votes(&c, ALICE, 4), U256::from(3));
+    assert_eq!(past_votes(&c, ALICE, 7), U256::from(2));
+    assert_eq!(past_votes(&c, BOB, 8), U256::from(1));
+}
+
+#[test]
+fn past_votes_reject_current_block() {
+    let (_vm, c) = history();
+    assert_eq!(
+        c.erc721.get_past_votes(ALICE, U256::from(11)).unwrap_err(),
+        Erc721Error::FutureLookup(FutureLookup {
+            block_number: U256::from(11),
+            current_block: U256::from(11),
+        })
+    );
+}
@@ -0,0 +1,77 @@
+use super::*;
+use crate::erc721::{Erc721Error, InvalidProceedsSplit};
+use crate::Received;
+
+const CREATOR: Address = address!("00000000000000000000000000000000000c4ea7");
+const PLATFORM: Address = address!("000000000000000000000000000000000000d0c5");
+
+// 部署合约并向合约存入 10_000 wei
+fn funded() -> (TestVM, StylusNFT) {
+    let (vm, c) = deploy();
+    vm.set_balance(vm.contract_address(), U256::from(10_000));
+    (vm, c)
+}
+
+#[test]
+fn withdraw_without_split_pays_recipient() {
+    let (vm, mut c) = funded();
+    c.withdraw(BOB).unwrap();
+    assert_eq!(vm.balance(BOB), U256::from(10_000));
+    assert_eq!(vm.balance(vm.contract_address()), U256::ZERO);
+}
+
+#[test]
+fn withdraw_with_split_follows_split() {
+    let (vm, mut c) = funded();
+    c.set_proceeds_split(CREATOR, PLATFORM, U256::from(2_500))
+        .unwrap();
+    c.withdraw(BOB).unwrap();
+    assert_eq!(vm.balance(BOB), U256::ZERO);
+    assert_eq!(vm.balance(PLATFORM), U256::from(2_500));
+    assert_eq!(vm.balance(CREATOR), U256::from(7_500));
+}
+
+#[test]
+fn anyone_can_trigger_split() {
+    let (vm, mut c) = funded();
+    c.set_proceeds_split(CREATOR, PLATFORM, U256::from(1_000))
+        .unwrap();
+    assert_eq!(c.withdrawable(CREATOR).unwrap(), U256::from(9_000));
+    vm.set_sender(ALICE);
+    c.withdraw_split().unwrap();
+    assert_eq!(vm.balance(PLATFORM), U256::from(1_000));
+    assert_eq!(vm.balance(CREATOR), U256::from(9_000));
+}
+
+#[test]
+fn split_requires_configuration() {
+    let (_vm, mut c) = funded();
+    assert_eq!(
+        c.withdraw_split(),
+        Err(Erc721Error::InvalidProceedsSplit(InvalidProceedsSplit {
+            creator: Address::ZERO,
+            platform: Address::ZERO,
+            platform_bps: U256::ZERO
+        }))
+    );
+}
+
+#[test]
+fn received_eth_is_logged_and_withdrawable() {
+    let (vm, mut c) = deploy();
+    vm.set_sender(BOB);
+    vm.set_value(U256::from(300));
+    // 测试环境不会把随调用附带的 ETH 计入合约余额
+    vm.set_balance(vm.contract_address(), U256::from(300));
+    c.receive().unwrap();
+    let logs = vm.get_emitted_logs();
+    let (topics, data) = logs.last().unwrap();
+    assert_eq!(topics[0], Received::SIGNATURE_HASH);
+    assert_eq!(topics[1], BOB.into_word());
+    assert_eq!(U256::abi_decode(data, true).unwrap(), U256::from(300));
+    assert_eq!(c.contract_balance().unwrap(), U256::from(300));
+    vm.set_sender(OWNER);
+    vm.set_value(U256::ZERO);
+    c.withdraw(ALICE).unwrap();
+    assert_eq!(vm.balance(ALICE), U256::from(300));
+}
@@ -0,0 +1,125 @@
+use super::*;
+use crate::erc721::{Erc721Error, ExternalTransferFailed, NotWrapped};
+use crate::IERC721;
+use alloy_sol_types::SolCall;
+
+pub(super) const COLLECTION: Address = address!("00000000000000000000000000000000000c011e");
+pub(super) const EXTERNAL_ID: U256 = U256::from_limbs([7, 0, 0, 0]);
+
+// 模拟外部集合：transferFrom 成功，ownerOf 返回 custodian
+pub(super) fn mock_collection(vm: &TestVM, custodian: Address) {
+    vm.set_code(COLLECTION, vec![0x00]);
+    let pull = IERC721::transferFromCall {
+        from: ALICE,
+        to: vm.contract_address(),
+        token_id: EXTERNAL_ID,
+    };
+    vm.mock_call(COLLECTION, pull.abi_encode(), Ok(Vec::new()));
+    let owner_of = IERC721::ownerOfCall {
+        token_id: EXTERNAL_ID,
+    };
+    vm.mock_static_call(
+        COLLECTION,
+        owner_of.abi_encode(),
+        Ok(custodian.abi_encode()),
+    );
+}
+
+fn transfer_failed() -> Erc721Error {
+    Erc721Error::ExternalTransferFailed(ExternalTransferFailed {
+        collection: COLLECTION,
+        token_id: EXTERNAL_ID,
+    })
+}
+
+#[test]
+fn wrap_mints_when_custody_is_confirmed() {
+    let (vm, mut c) = deploy();
+    mock_collection(&vm, vm.contract_address());
+    vm.set_sender(ALICE);
+    c.wrap(COLLECTION, EXTERNAL_ID).unwrap();
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(
+        c.wrapped_token(U256::ZERO).unwrap(),
+        (COLLECTION, EXTERNAL_ID)
+    );
+}
+
+#[test]
+fn wrap_rejects_collection_that_keeps_the_token() {
+    let (vm, mut c) = deploy();
+    mock_collection(&vm, ALICE);
+    vm.set_sender(ALICE);
+    assert_eq!(c.wrap(COLLECTION, EXTERNAL_ID), Err(transfer_failed()));
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn wrap_rejects_account_without_owner_of() {
+    let (vm, mut c) = deploy();
+    vm.set_sender(ALICE);
+    assert_eq!(c.wrap(COLLECTION, EXTERNAL_ID), Err(transfer_failed()));
+    assert_eq!(c.erc721.balance_of(ALICE).unwrap(), U256::ZERO);
+}
+
+#[test]
+fn wrap_rejects_reverting_transfer() {
+    let (vm, mut c) = deploy();
+    mock_collection(&vm, vm.contract_address());
+    let pull = IERC721::transferFromCall {
+        from: ALICE,
+        to: vm.contract_address(),
+        token_id: EXTERNAL_ID,
+    };
+    vm.mock_call(COLLECTION, pull.abi_encode(), Err(Vec::new()));
+    vm.set_sender(ALICE);
+    assert_eq!(c.wrap(COLLECTION, EXTERNAL_ID), Err(transfer_failed()));
+}
+
+#[test]
+fn unwrap_returns_the_external_token() {
+    let (vm, mut c) = deploy();
+    mock_collection(&vm, vm.contract_address());
+    vm.set_sender(ALICE);
+    c.wrap(COLLECTION, EXTERNAL_ID).unwrap();
+    let release = IERC721::transferFromCall {
+        from: vm.contract_address(),
+        to: ALICE,
+        token_id: EXTERNAL_ID,
+    };
+    vm.mock_call(COLLECTION, release.abi_encode(), Ok(Vec::new()));
+    c.unwrap(U256::ZERO).unwrap();
+    assert!(!c.erc721.exists(U256::ZERO).unwrap());
+    assert_eq!(
+        c.wrapped_token(U256::ZERO).unwrap(),
+        (Address::ZERO, U256::ZERO)
+    );
+}
+
+#[test]
+fn unwrap_of_unwrapped_id_reverts() {
+    let (vm, mut c) = deploy();
+    c.mint_to(ALICE).unwrap();
+    vm.set_sender(ALICE);
+    assert_eq!(
+        c.unwrap(U256::ZERO),
+        Err(Erc721Error::NotWrapped(NotWrapped {
+            wrapper_id: U256::ZERO
+        }))
+    );
+}
+
+#[test]
+fn unwrap_by_non_owner_reverts() {
+    let (vm, mut c) = deploy();
+    mock_collection(&vm, vm.contract_address());
+    vm.set_sender(ALICE);
+    c.wrap(COLLECTION, EXTERNAL_ID).unwrap();
+    vm.set_sender(BOB);
+    assert!(c.unwrap(U256::ZERO).is_err());
+    assert_eq!(c.erc721.owner_of(U256::ZERO).unwrap(), ALICE);
+    assert_eq!(
+        c.wrapped_token(U256::ZERO).unwrap(),
+        (COLLECTION, EXTERNAL_ID)
+    );
+}